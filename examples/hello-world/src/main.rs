@@ -1,9 +1,9 @@
 use steamgear::api::SteamApi;
 
 fn main() {
-    let steam_api = SteamApi::new_client(None).unwrap();
+    let steam_api = SteamApi::client().build().unwrap();
 
-    dbg!(steam_api.apps().get_launch_command_line());
+    dbg!(steam_api.apps().get_launch_command_line().unwrap());
 
     steam_api.shutdown();
 }
@@ -0,0 +1,230 @@
+//! JSON config read from `STEAMGEAR_STUB_CONFIG` on init, every key is optional.
+//!
+//! ```json
+//! {
+//!     "init_result": 0,
+//!     "init_error": "",
+//!     "restart_required": false,
+//!     "subscribed": true,
+//!     "language": "english",
+//!     "available_languages": "english,german",
+//!     "owned_apps": [480],
+//!     "installed_apps": { "480": "/games/spacewar" },
+//!     "installed_depots": { "480": [481] },
+//!     "purchase_times": { "480": 1700000000 },
+//!     "purchase_keys": { "480": "AAAAA-BBBBB-CCCCC" },
+//!     "dlc": [{ "id": 1001, "name": "Soundtrack", "available": true, "installed": false }],
+//!     "beta": "public",
+//!     "app_owner": 76561197960265728,
+//!     "build_id": 12,
+//!     "launch_command_line": "+connect 127.0.0.1",
+//!     "launch_query_params": { "server": "127.0.0.1" },
+//!     "timed_trial": { "allowed": 3600, "played": 60 },
+//!     "file_details": { "game.pak": { "size": 42, "sha1": "0a0b..." } },
+//!     "call_failure": 1,
+//!     "callbacks": [{ "frame": 2, "pipe": "client", "id": 1005, "data": "e9030000" }]
+//! }
+//! ```
+//!
+//! `init_result` is an `ESteamAPIInitResult`, `call_failure` an `ESteamAPICallFailure`
+//! that fails every call result. Scripted callbacks are handed out on the given
+//! `SteamAPI_ManualDispatch_RunFrame` call of their pipe, `data` is hex.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+pub(crate) const CLIENT_PIPE: i32 = 1;
+pub(crate) const SERVER_PIPE: i32 = 2;
+
+#[derive(Debug, Default)]
+pub(crate) struct Config {
+    pub(crate) init_result: i32,
+    pub(crate) init_error: String,
+    pub(crate) restart_required: bool,
+
+    pub(crate) subscribed: bool,
+    pub(crate) family_sharing: bool,
+    pub(crate) free_weekend: bool,
+    pub(crate) low_violence: bool,
+    pub(crate) cybercafe: bool,
+    pub(crate) vac_banned: bool,
+    pub(crate) language: String,
+    pub(crate) available_languages: String,
+    pub(crate) owned_apps: Vec<u32>,
+    pub(crate) installed_apps: HashMap<u32, String>,
+    pub(crate) installed_depots: HashMap<u32, Vec<u32>>,
+    pub(crate) purchase_times: HashMap<u32, u32>,
+    pub(crate) purchase_keys: HashMap<u32, String>,
+    pub(crate) dlc: Vec<Dlc>,
+    pub(crate) beta: Option<String>,
+    pub(crate) app_owner: u64,
+    pub(crate) build_id: i32,
+    pub(crate) launch_command_line: String,
+    pub(crate) launch_query_params: HashMap<String, String>,
+    pub(crate) timed_trial: Option<(u32, u32)>,
+    pub(crate) file_details: HashMap<String, (u64, [u8; 20])>,
+    pub(crate) call_failure: Option<i32>,
+    pub(crate) callbacks: Vec<ScriptedCallback>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Dlc {
+    pub(crate) id: u32,
+    pub(crate) name: String,
+    pub(crate) available: bool,
+    pub(crate) installed: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct ScriptedCallback {
+    pub(crate) frame: u64,
+    pub(crate) pipe: i32,
+    pub(crate) id: i32,
+    pub(crate) data: Vec<u8>,
+}
+
+impl Config {
+    /// Reads the file named by `STEAMGEAR_STUB_CONFIG`, the defaults without it.
+    pub(crate) fn from_env() -> Result<Self, String> {
+        let Some(path) = std::env::var_os("STEAMGEAR_STUB_CONFIG") else {
+            return Ok(Self::default());
+        };
+
+        let text = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Couldn't read {}: {}", path.to_string_lossy(), err))?;
+        let json: Value = serde_json::from_str(&text)
+            .map_err(|err| format!("Invalid config {}: {}", path.to_string_lossy(), err))?;
+
+        Self::parse(&json)
+    }
+
+    fn parse(json: &Value) -> Result<Self, String> {
+        let flag = |key: &str| json[key].as_bool().unwrap_or(false);
+        let string = |key: &str| json[key].as_str().unwrap_or_default().to_string();
+
+        Ok(Self {
+            init_result: json["init_result"].as_i64().unwrap_or(0) as i32,
+            init_error: string("init_error"),
+            restart_required: flag("restart_required"),
+            subscribed: flag("subscribed"),
+            family_sharing: flag("family_sharing"),
+            free_weekend: flag("free_weekend"),
+            low_violence: flag("low_violence"),
+            cybercafe: flag("cybercafe"),
+            vac_banned: flag("vac_banned"),
+            language: string("language"),
+            available_languages: string("available_languages"),
+            owned_apps: json["owned_apps"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|app| app.as_u64().map(|app| app as u32))
+                .collect(),
+            installed_apps: app_map(&json["installed_apps"], |dir| {
+                dir.as_str().map(str::to_string)
+            })?,
+            installed_depots: app_map(&json["installed_depots"], |depots| {
+                Some(
+                    depots
+                        .as_array()?
+                        .iter()
+                        .filter_map(|depot| depot.as_u64().map(|depot| depot as u32))
+                        .collect(),
+                )
+            })?,
+            purchase_times: app_map(&json["purchase_times"], |time| {
+                time.as_u64().map(|time| time as u32)
+            })?,
+            purchase_keys: app_map(&json["purchase_keys"], |key| {
+                key.as_str().map(str::to_string)
+            })?,
+            dlc: json["dlc"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|dlc| {
+                    Ok(Dlc {
+                        id: dlc["id"].as_u64().ok_or("DLC without id")? as u32,
+                        name: dlc["name"].as_str().unwrap_or_default().to_string(),
+                        available: dlc["available"].as_bool().unwrap_or(true),
+                        installed: dlc["installed"].as_bool().unwrap_or(false),
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+            beta: json["beta"].as_str().map(str::to_string),
+            app_owner: json["app_owner"].as_u64().unwrap_or(0),
+            build_id: json["build_id"].as_i64().unwrap_or(0) as i32,
+            launch_command_line: string("launch_command_line"),
+            launch_query_params: json["launch_query_params"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| (key.clone(), value.as_str().unwrap_or_default().to_string()))
+                .collect(),
+            timed_trial: json["timed_trial"].as_object().map(|trial| {
+                (
+                    trial["allowed"].as_u64().unwrap_or(0) as u32,
+                    trial["played"].as_u64().unwrap_or(0) as u32,
+                )
+            }),
+            file_details: json["file_details"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(name, details)| {
+                    let sha1 = hex(details["sha1"].as_str().unwrap_or_default())?;
+                    let sha1 = sha1
+                        .try_into()
+                        .map_err(|_| format!("SHA-1 of {} isn't 20 bytes", name))?;
+                    Ok((name.clone(), (details["size"].as_u64().unwrap_or(0), sha1)))
+                })
+                .collect::<Result<_, String>>()?,
+            call_failure: json["call_failure"].as_i64().map(|failure| failure as i32),
+            callbacks: json["callbacks"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|callback| {
+                    Ok(ScriptedCallback {
+                        frame: callback["frame"].as_u64().unwrap_or(1),
+                        pipe: match callback["pipe"].as_str() {
+                            Some("server") => SERVER_PIPE,
+                            _ => CLIENT_PIPE,
+                        },
+                        id: callback["id"].as_i64().ok_or("Callback without id")? as i32,
+                        data: hex(callback["data"].as_str().unwrap_or_default())?,
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+        })
+    }
+}
+
+fn app_map<T>(
+    json: &Value,
+    value: impl Fn(&Value) -> Option<T>,
+) -> Result<HashMap<u32, T>, String> {
+    json.as_object()
+        .into_iter()
+        .flatten()
+        .map(|(app, entry)| {
+            let app = app.parse().map_err(|_| format!("Invalid app id {}", app))?;
+            let entry = value(entry).ok_or_else(|| format!("Invalid entry for app {}", app))?;
+            Ok((app, entry))
+        })
+        .collect()
+}
+
+fn hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err(format!("Odd length hex string {}", text));
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| format!("Invalid hex {}", text))
+        })
+        .collect()
+}
@@ -0,0 +1,871 @@
+//! A stand-in `libsteam_api` exporting the flat API functions steamgear calls.
+//!
+//! Load it with steamgear's `runtime-link` feature and `library_path`, or rename it to
+//! `libsteam_api` and put it where that is linked from. Its behavior comes from the file in `STEAMGEAR_STUB_CONFIG`, see
+//! [`config`]. Only one client and one game server exist per process, like in Steam.
+
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)]
+
+mod config;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    sync::Mutex,
+};
+
+use config::{Config, CLIENT_PIPE, SERVER_PIPE};
+
+const INIT_OK: c_int = 0;
+const INIT_FAILED_GENERIC: c_int = 1;
+
+const RESULT_OK: c_int = 1;
+const RESULT_FAIL: c_int = 2;
+const RESULT_FILE_NOT_FOUND: c_int = 9;
+
+const CALL_FAILURE_NONE: c_int = -1;
+const CALL_FAILURE_INVALID_HANDLE: c_int = 2;
+
+const DLC_INSTALLED: c_int = 1005;
+const PROOF_OF_PURCHASE_KEY_RESPONSE: c_int = 1021;
+const FILE_DETAILS_RESULT: c_int = 1023;
+const API_CALL_COMPLETED: c_int = 703;
+
+const ERR_MSG_LEN: usize = 1024;
+
+#[cfg_attr(windows, repr(C, packed(8)))]
+#[cfg_attr(not(windows), repr(C, packed(4)))]
+pub struct CallbackMsg {
+    m_hSteamUser: i32,
+    m_iCallback: c_int,
+    m_pubParam: *mut u8,
+    m_cubParam: c_int,
+}
+
+#[cfg_attr(windows, repr(C, packed(8)))]
+#[cfg_attr(not(windows), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct ApiCallCompleted {
+    m_hAsyncCall: u64,
+    m_iCallback: c_int,
+    m_cubParam: u32,
+}
+
+#[cfg_attr(windows, repr(C, packed(8)))]
+#[cfg_attr(not(windows), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct FileDetailsResult {
+    m_eResult: c_int,
+    m_ulFileSize: u64,
+    m_FileSHA: [u8; 20],
+    m_unFlags: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ProofOfPurchaseKeyResponse {
+    m_eResult: c_int,
+    m_nAppID: u32,
+    m_cchKeyLength: u32,
+    m_rgchKey: [c_char; 240],
+}
+
+enum Event {
+    Callback { id: c_int, data: Vec<u8> },
+    CallCompleted { call: u64, id: c_int, size: u32 },
+}
+
+#[derive(Default)]
+struct Stub {
+    config: Config,
+    client_running: bool,
+    server_running: bool,
+
+    frames: HashMap<i32, u64>,
+    events: HashMap<i32, VecDeque<Event>>,
+    /// Payload of the callback last handed out, valid until the next one.
+    last_callback: Vec<u8>,
+    call_results: HashMap<u64, (c_int, Vec<u8>)>,
+    next_call: u64,
+    /// Strings handed out to the caller, they live as long as the stub.
+    strings: Vec<CString>,
+}
+
+static STUB: Mutex<Option<Stub>> = Mutex::new(None);
+
+/// Stands in for the interface pointers, the stub doesn't look at them.
+static INTERFACE: u8 = 0;
+
+fn with_stub<R>(f: impl FnOnce(&mut Stub) -> R) -> Option<R> {
+    let mut guard = STUB.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    guard.as_mut().map(f)
+}
+
+fn interface<T>() -> *mut T {
+    &INTERFACE as *const u8 as *mut T
+}
+
+impl Stub {
+    fn dlc_mut(&mut self, app_id: u32) -> Option<&mut config::Dlc> {
+        self.config.dlc.iter_mut().find(|dlc| dlc.id == app_id)
+    }
+
+    fn keep(&mut self, string: &str) -> *const c_char {
+        let string = CString::new(string).unwrap_or_default();
+        let ptr = string.as_ptr();
+        self.strings.push(string);
+        ptr
+    }
+
+    fn push(&mut self, pipe: i32, event: Event) {
+        self.events.entry(pipe).or_default().push_back(event);
+    }
+
+    fn push_proof_of_purchase_key(&mut self, app_id: u32) {
+        let mut response = ProofOfPurchaseKeyResponse {
+            m_eResult: RESULT_FAIL,
+            m_nAppID: app_id,
+            m_cchKeyLength: 0,
+            m_rgchKey: [0; 240],
+        };
+
+        if let Some(key) = self.config.purchase_keys.get(&app_id) {
+            let len = key.len().min(response.m_rgchKey.len() - 1);
+            response.m_eResult = RESULT_OK;
+            response.m_cchKeyLength = len as u32;
+            unsafe { copy_str(key, response.m_rgchKey.as_mut_ptr(), len + 1) };
+        }
+
+        self.push(
+            CLIENT_PIPE,
+            Event::Callback {
+                id: PROOF_OF_PURCHASE_KEY_RESPONSE,
+                data: bytes(&response),
+            },
+        );
+    }
+
+    fn start_call(&mut self, id: c_int, data: Vec<u8>) -> u64 {
+        self.next_call += 1;
+        let call = self.next_call;
+
+        self.push(
+            CLIENT_PIPE,
+            Event::CallCompleted {
+                call,
+                id,
+                size: data.len() as u32,
+            },
+        );
+        self.call_results.insert(call, (id, data));
+
+        call
+    }
+
+    fn take_call_result(
+        &mut self,
+        call: u64,
+        buffer: *mut c_void,
+        size: c_int,
+        expected: c_int,
+        failed: *mut bool,
+    ) -> bool {
+        let Some((id, data)) = self.call_results.remove(&call) else {
+            return false;
+        };
+
+        let ok =
+            self.config.call_failure.is_none() && id == expected && data.len() == size as usize;
+        if ok {
+            unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), buffer as *mut u8, data.len()) };
+        }
+        unsafe { write(failed, !ok) };
+
+        true
+    }
+}
+
+unsafe fn write<T>(ptr: *mut T, value: T) {
+    if !ptr.is_null() {
+        ptr.write_unaligned(value);
+    }
+}
+
+unsafe fn copy_str(src: &str, dst: *mut c_char, size: usize) {
+    if dst.is_null() || size == 0 {
+        return;
+    }
+
+    let len = src.len().min(size - 1);
+    std::ptr::copy_nonoverlapping(src.as_ptr(), dst as *mut u8, len);
+    *dst.add(len) = 0;
+}
+
+fn init(err_msg: *mut c_char, server: bool) -> c_int {
+    let mut guard = STUB.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if guard.is_none() {
+        match Config::from_env() {
+            Ok(config) => {
+                *guard = Some(Stub {
+                    config,
+                    ..Default::default()
+                })
+            }
+            Err(err) => {
+                unsafe { copy_str(&err, err_msg, ERR_MSG_LEN) };
+                return INIT_FAILED_GENERIC;
+            }
+        }
+    }
+
+    let stub = guard.as_mut().unwrap();
+    if stub.config.init_result != INIT_OK {
+        let result = stub.config.init_result;
+        unsafe { copy_str(&stub.config.init_error, err_msg, ERR_MSG_LEN) };
+
+        if !stub.client_running && !stub.server_running {
+            *guard = None;
+        }
+        return result;
+    }
+
+    if server {
+        stub.server_running = true;
+    } else {
+        stub.client_running = true;
+    }
+
+    INIT_OK
+}
+
+fn shutdown(server: bool) {
+    let mut guard = STUB.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(stub) = guard.as_mut() {
+        if server {
+            stub.server_running = false;
+            stub.events.remove(&SERVER_PIPE);
+        } else {
+            stub.client_running = false;
+            stub.events.remove(&CLIENT_PIPE);
+        }
+
+        if !stub.client_running && !stub.server_running {
+            *guard = None;
+        }
+    }
+}
+
+// Lifecycle
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamInternal_SteamAPI_Init(
+    _versions: *const c_char,
+    err_msg: *mut c_char,
+) -> c_int {
+    init(err_msg, false)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamInternal_GameServer_Init_V2(
+    _ip: u32,
+    _game_port: u16,
+    _query_port: u16,
+    _server_mode: c_int,
+    _version: *const c_char,
+    _versions: *const c_char,
+    err_msg: *mut c_char,
+) -> c_int {
+    init(err_msg, true)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_Shutdown() {
+    shutdown(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamGameServer_Shutdown() {
+    shutdown(true)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_GetHSteamUser() -> i32 {
+    if with_stub(|stub| stub.client_running).unwrap_or(false) {
+        1
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_GetHSteamPipe() -> i32 {
+    if with_stub(|stub| stub.client_running).unwrap_or(false) {
+        CLIENT_PIPE
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn SteamGameServer_GetHSteamPipe() -> i32 {
+    if with_stub(|stub| stub.server_running).unwrap_or(false) {
+        SERVER_PIPE
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_RestartAppIfNecessary(_app_id: u32) -> bool {
+    match Config::from_env() {
+        Ok(config) => config.restart_required,
+        Err(_) => false,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ReleaseCurrentThreadMemory() {}
+
+// Manual dispatch
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ManualDispatch_Init() {}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ManualDispatch_RunFrame(pipe: i32) {
+    with_stub(|stub| {
+        let frame = stub.frames.entry(pipe).or_default();
+        *frame += 1;
+        let frame = *frame;
+
+        let (due, rest) = std::mem::take(&mut stub.config.callbacks)
+            .into_iter()
+            .partition::<Vec<_>, _>(|callback| callback.pipe == pipe && callback.frame <= frame);
+        stub.config.callbacks = rest;
+
+        for callback in due {
+            stub.push(
+                pipe,
+                Event::Callback {
+                    id: callback.id,
+                    data: callback.data,
+                },
+            );
+        }
+    });
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ManualDispatch_GetNextCallback(
+    pipe: i32,
+    msg: *mut CallbackMsg,
+) -> bool {
+    with_stub(|stub| {
+        let Some(event) = stub.events.get_mut(&pipe).and_then(VecDeque::pop_front) else {
+            return false;
+        };
+
+        let (id, data) = match event {
+            Event::Callback { id, data } => (id, data),
+            Event::CallCompleted { call, id, size } => {
+                let completed = ApiCallCompleted {
+                    m_hAsyncCall: call,
+                    m_iCallback: id,
+                    m_cubParam: size,
+                };
+                (API_CALL_COMPLETED, bytes(&completed))
+            }
+        };
+
+        stub.last_callback = data;
+        write(
+            msg,
+            CallbackMsg {
+                m_hSteamUser: pipe,
+                m_iCallback: id,
+                m_pubParam: stub.last_callback.as_mut_ptr(),
+                m_cubParam: stub.last_callback.len() as c_int,
+            },
+        );
+
+        true
+    })
+    .unwrap_or(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ManualDispatch_FreeLastCallback(_pipe: i32) {
+    with_stub(|stub| stub.last_callback.clear());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ManualDispatch_GetAPICallResult(
+    _pipe: i32,
+    call: u64,
+    buffer: *mut c_void,
+    size: c_int,
+    expected: c_int,
+    failed: *mut bool,
+) -> bool {
+    with_stub(|stub| stub.take_call_result(call, buffer, size, expected, failed)).unwrap_or(false)
+}
+
+fn bytes<T: Copy>(value: &T) -> Vec<u8> {
+    let ptr = value as *const T as *const u8;
+    unsafe { std::slice::from_raw_parts(ptr, std::mem::size_of::<T>()) }.to_vec()
+}
+
+// Interface accessors
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_SteamApps_v008() -> *mut c_void {
+    interface()
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_SteamFriends_v017() -> *mut c_void {
+    interface()
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_SteamUtils_v010() -> *mut c_void {
+    interface()
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_SteamGameServerUtils_v010() -> *mut c_void {
+    interface()
+}
+
+/// `ISteamAppTicket` has no flat API, steamgear calls it through the vtable.
+#[repr(C)]
+struct AppTicket {
+    vtable: &'static AppTicketVtable,
+}
+
+#[repr(C)]
+struct AppTicketVtable {
+    get_app_ownership_ticket_data: AppOwnershipTicketData,
+}
+
+/// The stub issues no tickets.
+macro_rules! ownership_ticket_data {
+    ($abi:literal) => {
+        type AppOwnershipTicketData = unsafe extern $abi fn(
+            *mut c_void,
+            u32,
+            *mut c_void,
+            u32,
+            *mut u32,
+            *mut u32,
+            *mut u32,
+            *mut u32,
+        ) -> u32;
+
+        unsafe extern $abi fn get_app_ownership_ticket_data(
+            _self: *mut c_void,
+            _app_id: u32,
+            _buffer: *mut c_void,
+            _size: u32,
+            _app_id_offset: *mut u32,
+            _steam_id_offset: *mut u32,
+            _signature_offset: *mut u32,
+            _signature_size: *mut u32,
+        ) -> u32 {
+            0
+        }
+    };
+}
+
+#[cfg(all(windows, target_arch = "x86"))]
+ownership_ticket_data!("thiscall");
+#[cfg(not(all(windows, target_arch = "x86")))]
+ownership_ticket_data!("C");
+
+static APP_TICKET: AppTicket = AppTicket {
+    vtable: &AppTicketVtable {
+        get_app_ownership_ticket_data,
+    },
+};
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamInternal_FindOrCreateUserInterface(
+    _user: i32,
+    version: *const c_char,
+) -> *mut c_void {
+    if CStr::from_ptr(version).to_bytes() == b"STEAMAPPTICKET_INTERFACE_VERSION001" {
+        &APP_TICKET as *const AppTicket as *mut c_void
+    } else {
+        std::ptr::null_mut()
+    }
+}
+
+// ISteamApps
+
+macro_rules! config_flag {
+    ($($name:ident => $field:ident,)*) => {
+        $(
+            #[no_mangle]
+            pub extern "C" fn $name(_self: *mut c_void) -> bool {
+                with_stub(|stub| stub.config.$field).unwrap_or(false)
+            }
+        )*
+    };
+}
+
+config_flag! {
+    SteamAPI_ISteamApps_BIsSubscribed => subscribed,
+    SteamAPI_ISteamApps_BIsSubscribedFromFamilySharing => family_sharing,
+    SteamAPI_ISteamApps_BIsSubscribedFromFreeWeekend => free_weekend,
+    SteamAPI_ISteamApps_BIsLowViolence => low_violence,
+    SteamAPI_ISteamApps_BIsCybercafe => cybercafe,
+    SteamAPI_ISteamApps_BIsVACBanned => vac_banned,
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_GetCurrentGameLanguage(_self: *mut c_void) -> *const c_char {
+    with_stub(|stub| {
+        let language = stub.config.language.clone();
+        stub.keep(&language)
+    })
+    .unwrap_or(c"".as_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_GetAvailableGameLanguages(
+    _self: *mut c_void,
+) -> *const c_char {
+    with_stub(|stub| {
+        let languages = stub.config.available_languages.clone();
+        stub.keep(&languages)
+    })
+    .unwrap_or(c"".as_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_BIsSubscribedApp(_self: *mut c_void, app_id: u32) -> bool {
+    with_stub(|stub| stub.config.owned_apps.contains(&app_id)).unwrap_or(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_BIsDlcInstalled(_self: *mut c_void, app_id: u32) -> bool {
+    with_stub(|stub| stub.dlc_mut(app_id).is_some_and(|dlc| dlc.installed)).unwrap_or(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_BIsAppInstalled(_self: *mut c_void, app_id: u32) -> bool {
+    with_stub(|stub| stub.config.installed_apps.contains_key(&app_id)).unwrap_or(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(
+    _self: *mut c_void,
+    app_id: u32,
+) -> u32 {
+    with_stub(|stub| stub.config.purchase_times.get(&app_id).copied())
+        .flatten()
+        .unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_GetDLCCount(_self: *mut c_void) -> c_int {
+    with_stub(|stub| stub.config.dlc.len() as c_int).unwrap_or(0)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_BGetDLCDataByIndex(
+    _self: *mut c_void,
+    index: c_int,
+    app_id: *mut u32,
+    available: *mut bool,
+    name: *mut c_char,
+    name_size: c_int,
+) -> bool {
+    with_stub(|stub| {
+        let Some(dlc) = usize::try_from(index)
+            .ok()
+            .and_then(|index| stub.config.dlc.get(index))
+        else {
+            return false;
+        };
+
+        write(app_id, dlc.id);
+        write(available, dlc.available);
+        copy_str(&dlc.name, name, name_size.max(0) as usize);
+        true
+    })
+    .unwrap_or(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_InstallDLC(_self: *mut c_void, app_id: u32) {
+    with_stub(|stub| {
+        if let Some(dlc) = stub.dlc_mut(app_id) {
+            dlc.installed = true;
+            stub.push(
+                CLIENT_PIPE,
+                Event::Callback {
+                    id: DLC_INSTALLED,
+                    data: app_id.to_ne_bytes().to_vec(),
+                },
+            );
+        }
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_UninstallDLC(_self: *mut c_void, app_id: u32) {
+    with_stub(|stub| {
+        if let Some(dlc) = stub.dlc_mut(app_id) {
+            dlc.installed = false;
+        }
+    });
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_GetCurrentBetaName(
+    _self: *mut c_void,
+    name: *mut c_char,
+    name_size: c_int,
+) -> bool {
+    with_stub(|stub| match &stub.config.beta {
+        Some(beta) => {
+            copy_str(beta, name, name_size.max(0) as usize);
+            true
+        }
+        None => false,
+    })
+    .unwrap_or(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_MarkContentCorrupt(
+    _self: *mut c_void,
+    _missing_files_only: bool,
+) -> bool {
+    with_stub(|_| true).unwrap_or(false)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_GetInstalledDepots(
+    _self: *mut c_void,
+    app_id: u32,
+    depots: *mut u32,
+    max_depots: u32,
+) -> u32 {
+    with_stub(|stub| {
+        let installed = stub
+            .config
+            .installed_depots
+            .get(&app_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let count = installed.len().min(max_depots as usize);
+
+        if !depots.is_null() {
+            std::ptr::copy_nonoverlapping(installed.as_ptr(), depots, count);
+        }
+        count as u32
+    })
+    .unwrap_or(0)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_GetAppInstallDir(
+    _self: *mut c_void,
+    app_id: u32,
+    folder: *mut c_char,
+    folder_size: u32,
+) -> u32 {
+    with_stub(|stub| match stub.config.installed_apps.get(&app_id) {
+        Some(dir) => {
+            copy_str(dir, folder, folder_size as usize);
+            // The folder name length, like `isteamapps.h` says and `FakeBackend` does
+            dir.len() as u32
+        }
+        None => 0,
+    })
+    .unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_GetAppOwner(_self: *mut c_void) -> u64 {
+    with_stub(|stub| stub.config.app_owner).unwrap_or(0)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_GetLaunchQueryParam(
+    _self: *mut c_void,
+    key: *const c_char,
+) -> *const c_char {
+    let key = CStr::from_ptr(key).to_string_lossy();
+
+    with_stub(|stub| {
+        let value = stub
+            .config
+            .launch_query_params
+            .get(key.as_ref())
+            .cloned()
+            .unwrap_or_default();
+        stub.keep(&value)
+    })
+    .unwrap_or(c"".as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_GetDlcDownloadProgress(
+    _self: *mut c_void,
+    _app_id: u32,
+    downloaded: *mut u64,
+    total: *mut u64,
+) -> bool {
+    write(downloaded, 0);
+    write(total, 0);
+    false
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_GetAppBuildId(_self: *mut c_void) -> c_int {
+    with_stub(|stub| stub.config.build_id).unwrap_or(0)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_GetFileDetails(
+    _self: *mut c_void,
+    file_name: *const c_char,
+) -> u64 {
+    let file_name = CStr::from_ptr(file_name).to_string_lossy();
+
+    with_stub(|stub| {
+        let result = match stub.config.file_details.get(file_name.as_ref()) {
+            Some(&(size, sha1)) => FileDetailsResult {
+                m_eResult: RESULT_OK,
+                m_ulFileSize: size,
+                m_FileSHA: sha1,
+                m_unFlags: 0,
+            },
+            None => FileDetailsResult {
+                m_eResult: RESULT_FILE_NOT_FOUND,
+                m_ulFileSize: 0,
+                m_FileSHA: [0; 20],
+                m_unFlags: 0,
+            },
+        };
+
+        stub.start_call(FILE_DETAILS_RESULT, bytes(&result))
+    })
+    .unwrap_or(0)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_GetLaunchCommandLine(
+    _self: *mut c_void,
+    command_line: *mut c_char,
+    size: c_int,
+) -> c_int {
+    with_stub(|stub| {
+        let line = &stub.config.launch_command_line;
+        copy_str(line, command_line, size.max(0) as usize);
+        line.len().min(size.max(1) as usize - 1) as c_int
+    })
+    .unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_RequestAppProofOfPurchaseKey(
+    _self: *mut c_void,
+    app_id: u32,
+) {
+    with_stub(|stub| stub.push_proof_of_purchase_key(app_id));
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_RequestAllProofOfPurchaseKeys(_self: *mut c_void) {
+    with_stub(|stub| {
+        let apps = stub
+            .config
+            .purchase_keys
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        for app_id in apps {
+            stub.push_proof_of_purchase_key(app_id);
+        }
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamApps_SetDlcContext(_self: *mut c_void, _app_id: u32) -> bool {
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamApps_BIsTimedTrial(
+    _self: *mut c_void,
+    allowed: *mut u32,
+    played: *mut u32,
+) -> bool {
+    match with_stub(|stub| stub.config.timed_trial).flatten() {
+        Some((seconds_allowed, seconds_played)) => {
+            write(allowed, seconds_allowed);
+            write(played, seconds_played);
+            true
+        }
+        None => false,
+    }
+}
+
+// ISteamFriends
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamFriends_GetFriendGamePlayed(
+    _self: *mut c_void,
+    _friend: u64,
+    _info: *mut c_void,
+) -> bool {
+    false
+}
+
+// ISteamUtils
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamUtils_SetWarningMessageHook(
+    _self: *mut c_void,
+    _hook: Option<unsafe extern "C" fn(c_int, *const c_char)>,
+) {
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamUtils_IsAPICallCompleted(
+    _self: *mut c_void,
+    call: u64,
+    failed: *mut bool,
+) -> bool {
+    let completed = with_stub(|stub| stub.call_results.contains_key(&call)).unwrap_or(false);
+    write(failed, !completed);
+    completed
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SteamAPI_ISteamUtils_GetAPICallResult(
+    _self: *mut c_void,
+    call: u64,
+    buffer: *mut c_void,
+    size: c_int,
+    expected: c_int,
+    failed: *mut bool,
+) -> bool {
+    with_stub(|stub| stub.take_call_result(call, buffer, size, expected, failed)).unwrap_or(false)
+}
+
+#[no_mangle]
+pub extern "C" fn SteamAPI_ISteamUtils_GetAPICallFailureReason(
+    _self: *mut c_void,
+    _call: u64,
+) -> c_int {
+    with_stub(|stub| stub.config.call_failure.unwrap_or(CALL_FAILURE_NONE))
+        .unwrap_or(CALL_FAILURE_INVALID_HANDLE)
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use smol::stream::StreamExt;
+    use steamgear::{
+        api::SteamApi,
+        core::{
+            client::SteamApiClient,
+            enums::{AppsError, Interface, NotInitialized, SteamApiInitError, SteamError},
+            structs::AppId,
+        },
+    };
+
+    const CONFIG: &str = r#"{
+        "subscribed": true,
+        "language": "german",
+        "available_languages": "english,german",
+        "owned_apps": [480, 1001],
+        "installed_apps": { "480": "/games/spacewar" },
+        "installed_depots": { "480": [481, 482] },
+        "purchase_keys": { "480": "AAAAA-BBBBB-CCCCC" },
+        "dlc": [{ "id": 1001, "name": "Soundtrack" }],
+        "build_id": 12,
+        "launch_command_line": "+connect 127.0.0.1",
+        "file_details": {
+            "game.pak": { "size": 42, "sha1": "0707070707070707070707070707070707070707" }
+        },
+        "callbacks": [{ "frame": 2, "pipe": "client", "id": 999001, "data": "01020304" }]
+    }"#;
+
+    fn stub_library() -> PathBuf {
+        let exe = std::env::current_exe().unwrap();
+        let name = format!(
+            "{}steamgear_stub{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        );
+
+        exe.ancestors()
+            .skip(1)
+            .take(2)
+            .map(|dir| dir.join(&name))
+            .find(|path| path.exists())
+            .expect("stub library wasn't built")
+    }
+
+    async fn pump<T>(client: &SteamApi<SteamApiClient>) -> T {
+        loop {
+            client.run_callbacks();
+            smol::future::yield_now().await;
+        }
+    }
+
+    #[test]
+    fn stub_end_to_end() {
+        let config =
+            std::env::temp_dir().join(format!("steamgear-stub-{}.json", std::process::id()));
+        std::env::set_var("STEAMGEAR_STUB_CONFIG", &config);
+
+        let mismatch = format!(
+            r#"{{ "init_result": 3, "init_error": "No interface for version {}" }}"#,
+            Interface::Friends.version()
+        );
+        std::fs::write(&config, mismatch).unwrap();
+        let err = SteamApi::client()
+            .library_path(stub_library())
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SteamApiInitError::VersionMismatch {
+                interface: Some(Interface::Friends),
+                ..
+            }
+        ));
+
+        std::fs::write(&config, CONFIG).unwrap();
+
+        let client = SteamApi::client()
+            .library_path(stub_library())
+            .build()
+            .unwrap();
+        let apps = client.apps();
+
+        assert!(apps.is_subscribe().unwrap());
+        assert!(!apps.is_vac_banned().unwrap());
+        assert_eq!(apps.get_current_game_language().unwrap(), "german");
+        assert_eq!(
+            apps.get_available_game_languages().unwrap(),
+            vec!["english", "german"]
+        );
+        assert!(apps.is_subscribe_app(AppId(1001)).unwrap());
+        assert_eq!(
+            apps.get_app_install_dir(AppId(480)).unwrap(),
+            PathBuf::from("/games/spacewar")
+        );
+        assert_eq!(
+            apps.get_installed_depots(AppId(480))
+                .unwrap()
+                .map(|depot| depot.0)
+                .collect::<Vec<_>>(),
+            vec![481, 482]
+        );
+        assert_eq!(apps.get_app_build_id().unwrap(), 12);
+        assert_eq!(
+            apps.get_launch_command_line().unwrap(),
+            "+connect 127.0.0.1"
+        );
+
+        assert!(apps.set_dlc_context(Some(AppId(1001))).unwrap());
+        assert_eq!(
+            apps.get_app_ownership_ticket(AppId(480)),
+            Err(AppsError::NotFound)
+        );
+
+        let dlc = apps.get_dlc_information().unwrap();
+        assert_eq!(dlc.len(), 1);
+        assert_eq!(dlc[0].dlc_name, "Soundtrack");
+
+        let scripted = client.on_raw_callback(999_001);
+
+        smol::block_on(async {
+            let installed = smol::future::or(apps.install_dlc(AppId(1001)), pump(&client));
+            assert_eq!(installed.await.unwrap().id, AppId(1001));
+
+            let key = smol::future::or(
+                apps.request_proof_of_purchase_key(AppId(480)),
+                pump(&client),
+            );
+            assert_eq!(key.await.unwrap().key.as_deref(), Ok("AAAAA-BBBBB-CCCCC"));
+            let key = smol::future::or(
+                apps.request_proof_of_purchase_key(AppId(1001)),
+                pump(&client),
+            );
+            assert_eq!(key.await.unwrap().key, Err(SteamError::Fail));
+
+            let details = smol::future::or(apps.get_file_details("game.pak"), pump(&client))
+                .await
+                .unwrap();
+            assert_eq!(details.file_size, 42);
+            assert_eq!(details.sha1, [7; 20]);
+
+            let mut scripted = std::pin::pin!(scripted);
+            let data = smol::future::or(scripted.next(), pump(&client)).await;
+            assert_eq!(data, Some(vec![1, 2, 3, 4]));
+        });
+        assert!(apps.is_dlc_installed(AppId(1001)).unwrap());
+
+        client.shutdown();
+        assert_eq!(apps.is_subscribe(), Err(NotInitialized));
+
+        std::fs::remove_file(config).unwrap();
+    }
+}
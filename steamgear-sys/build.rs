@@ -1,5 +1,3 @@
-extern crate bindgen;
-
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     use std::env;
     use std::fs::{self};
@@ -17,6 +15,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
     println!("cargo:rerun-if-env-changed=STEAM_SDK_LOCATION");
     println!("cargo:rerun-if-env-changed=lib/steam");
+    // Exposed to dependents as `DEP_STEAM_API_SDK`
+    println!("cargo:sdk={}", sdk_loc.display());
 
     let triple = env::var("TARGET").unwrap();
     let mut lib = "steam_api";
@@ -38,7 +38,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         panic!("Unsupported OS");
     };
 
-    if triple.contains("windows") {
+    let runtime_link = env::var_os("CARGO_FEATURE_RUNTIME_LINK").is_some();
+
+    if runtime_link {
+        // Loaded by steamgear at runtime, nothing to link or ship
+    } else if triple.contains("windows") {
         let dll_file = format!("{}.dll", lib);
         let lib_file = format!("{}.lib", lib);
         fs::copy(link_path.join(&dll_file), out_path.join(dll_file))?;
@@ -55,42 +59,71 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         )?;
     }
 
-    println!("cargo:rustc-link-search={}", out_path.display());
-    println!("cargo:rustc-link-lib=dylib={}", lib);
+    if !runtime_link {
+        println!("cargo:rustc-link-search={}", out_path.display());
+        println!("cargo:rustc-link-lib=dylib={}", lib);
+    }
 
-    {
-        let target_os = if triple.contains("windows") {
-            "windows"
-        } else if triple.contains("darwin") {
-            "macos"
-        } else if triple.contains("linux") {
-            "linux"
-        } else {
-            panic!("Unsupported OS");
-        };
-        let binding_path = Path::new(&format!("src/{}_bindings.rs", target_os)).to_owned();
-        let bindings = bindgen::Builder::default()
-            .header(
-                sdk_loc
-                    .join("public/steam/steam_api_flat.h")
-                    .to_string_lossy(),
-            )
-            .header(
-                sdk_loc
-                    .join("public/steam/steam_gameserver.h")
-                    .to_string_lossy(),
-            )
-            .clang_arg("-xc++")
-            .clang_arg("-std=c++11")
-            .clang_arg(format!("-I{}", sdk_loc.join("public").display()))
-            .formatter(bindgen::Formatter::Rustfmt)
-            .default_enum_style(bindgen::EnumVariation::Consts)
-            .generate()
-            .expect("Unable to generate bindings");
+    // Without the feature `src/lib.rs` includes the checked-in `src/{os}_bindings.rs`
+    #[cfg(feature = "bindgen")]
+    generate_bindings(&sdk_loc, &out_path, &triple)?;
+
+    Ok(())
+}
+
+/// Runs bindgen into `OUT_DIR/bindings.rs`.
+///
+/// With `STEAMGEAR_SYS_UPDATE_BINDINGS` set the result also replaces the checked-in
+/// bindings of the target OS, for 64-bit targets.
+#[cfg(feature = "bindgen")]
+fn generate_bindings(
+    sdk_loc: &std::path::Path,
+    out_path: &std::path::Path,
+    triple: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let target_os = if triple.contains("windows") {
+        "windows"
+    } else if triple.contains("darwin") {
+        "macos"
+    } else if triple.contains("linux") {
+        "linux"
+    } else {
+        panic!("Unsupported OS");
+    };
+
+    let headers = [
+        sdk_loc.join("public/steam/steam_api_flat.h"),
+        sdk_loc.join("public/steam/steam_gameserver.h"),
+    ];
+    for header in &headers {
+        println!("cargo:rerun-if-changed={}", header.display());
+    }
+    println!("cargo:rerun-if-env-changed=STEAMGEAR_SYS_UPDATE_BINDINGS");
+
+    let bindings = headers
+        .iter()
+        .fold(bindgen::Builder::default(), |builder, header| {
+            builder.header(header.to_string_lossy())
+        })
+        .clang_arg("-xc++")
+        .clang_arg("-std=c++11")
+        .clang_arg(format!("-I{}", sdk_loc.join("public").display()))
+        .formatter(bindgen::Formatter::Rustfmt)
+        .default_enum_style(bindgen::EnumVariation::Consts)
+        .generate()
+        .expect("Unable to generate bindings");
+
+    let binding_path = out_path.join("bindings.rs");
+    bindings
+        .write_to_file(&binding_path)
+        .expect("Couldn't write bindings!");
 
-        bindings
-            .write_to_file(binding_path)
-            .expect("Couldn't write bindings!");
+    // Only 64-bit bindings are checked in
+    let pointer_width = std::env::var("CARGO_CFG_TARGET_POINTER_WIDTH")?;
+    if std::env::var_os("STEAMGEAR_SYS_UPDATE_BINDINGS").is_some() && pointer_width == "64" {
+        let checked_in = std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR")?)
+            .join(format!("src/{}_bindings.rs", target_os));
+        std::fs::copy(&binding_path, checked_in)?;
     }
 
     Ok(())
@@ -5,11 +5,27 @@
 #![allow(clippy::missing_safety_doc)]
 #![allow(clippy::useless_transmute)]
 
-#[cfg(target_os = "windows")]
+#[cfg(feature = "bindgen")]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+// The checked-in bindings are for 64-bit targets, struct layouts differ on 32-bit ones
+#[cfg(all(not(feature = "bindgen"), target_pointer_width = "32"))]
+compile_error!("steamgear-sys has no checked-in 32-bit bindings, enable the `bindgen` feature");
+
+#[cfg(all(
+    not(feature = "bindgen"),
+    target_os = "windows",
+    target_pointer_width = "64"
+))]
 include!("windows_bindings.rs");
 
-#[cfg(target_os = "macos")]
-include!("macos_bindings.rs");
+// Nothing checked in until the CI job generating them on macOS ran, see `.github/workflows`
+#[cfg(all(not(feature = "bindgen"), target_os = "macos"))]
+compile_error!("steamgear-sys has no checked-in macOS bindings yet, enable the `bindgen` feature");
 
-#[cfg(target_os = "linux")]
+#[cfg(all(
+    not(feature = "bindgen"),
+    target_os = "linux",
+    target_pointer_width = "64"
+))]
 include!("linux_bindings.rs");
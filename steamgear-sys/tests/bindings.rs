@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "bindgen")]
+    use std::collections::BTreeSet;
+
+    /// Needs libclang, run with `--features bindgen`.
+    #[cfg(feature = "bindgen")]
+    #[test]
+    fn checked_in_bindings_match_headers() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+        let path = format!(
+            "{}/src/{}_bindings.rs",
+            env!("CARGO_MANIFEST_DIR"),
+            std::env::consts::OS
+        );
+        if cfg!(target_pointer_width = "32") {
+            // Only 64-bit bindings are checked in
+            return;
+        }
+        let checked_in = match std::fs::read_to_string(&path) {
+            Ok(checked_in) => checked_in,
+            // macOS always generates them until bindings made on a macOS host are checked in
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound && cfg!(target_os = "macos") => {
+                return
+            }
+            Err(err) => panic!("Couldn't read {}: {}", path, err),
+        };
+
+        let generated = items(generated);
+        let checked_in = items(&checked_in);
+        let differing = generated
+            .symmetric_difference(&checked_in)
+            .collect::<Vec<_>>();
+
+        // Not `assert_eq`, the diff of two binding files is unreadable
+        assert!(
+            differing.is_empty(),
+            "{} doesn't match the SDK headers in {} items, first: {:.200}\n\
+             Regenerate it by building with `--features bindgen` and STEAMGEAR_SYS_UPDATE_BINDINGS=1",
+            path,
+            differing.len(),
+            differing.first().map_or("", |item| item.as_str())
+        );
+    }
+
+    /// Every item as tokens, so formatting and bindgen's layout tests don't count.
+    #[cfg(feature = "bindgen")]
+    fn items(source: &str) -> BTreeSet<String> {
+        syn::parse_file(source)
+            .expect("Bindings don't parse")
+            .items
+            .into_iter()
+            .filter(|item| !is_layout_check(item))
+            .map(|item| quote::ToTokens::to_token_stream(&item).to_string())
+            .collect()
+    }
+
+    #[cfg(feature = "bindgen")]
+    fn is_layout_check(item: &syn::Item) -> bool {
+        match item {
+            syn::Item::Fn(function) => function
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("test")),
+            syn::Item::Const(constant) => constant.ident == "_",
+            _ => false,
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
 
 fn main() {
     println!("cargo::rerun-if-changed=steam_appid.txt");
@@ -10,4 +15,299 @@ fn main() {
         file.write_all(buffer)
             .expect("Couldn't write steam app id!");
     }
+
+    let sdk_loc = PathBuf::from(
+        std::env::var("DEP_STEAM_API_SDK").expect("steamgear-sys didn't expose the SDK location"),
+    );
+
+    generate_callbacks(&sdk_loc);
+    generate_sdk_info(&sdk_loc);
+}
+
+/// Callbacks of removed platforms that share an id with a live callback.
+const SKIPPED_CALLBACKS: &[&str] = &["PS3TrophiesInstalled_t"];
+
+struct Callback {
+    id: u64,
+    name: String,
+    variant: String,
+    aliases: Vec<String>,
+    call_result: bool,
+    has_pointers: bool,
+}
+
+/// Generates `CallbackType` and its tables from the SDK's `steam_api.json`.
+///
+/// Every struct without pointers also gets `SteamCallbackStruct`, which makes it a
+/// `CallbackStruct` that `on_callback` hands out, dispatched by id through `RawCallbacks`.
+/// Mapped wrappers like `DlcInstalled` stay hand-written. There's no client or server
+/// flag, `steam_api.json` doesn't say and Steam delivers each callback on the pipe that
+/// raised it.
+fn generate_callbacks(sdk_loc: &Path) {
+    let json_path = sdk_loc.join("public/steam/steam_api.json");
+    println!("cargo::rerun-if-changed={}", json_path.display());
+
+    let json = std::fs::read_to_string(&json_path).expect("Couldn't read steam_api.json!");
+    let api: Value = serde_json::from_str(&json).expect("Couldn't parse steam_api.json!");
+
+    let call_results: HashSet<&str> = api["interfaces"]
+        .as_array()
+        .expect("interfaces must be an array")
+        .iter()
+        .flat_map(|interface| interface["methods"].as_array().into_iter().flatten())
+        .filter_map(|method| method["callresult"].as_str())
+        .collect();
+
+    let mut callbacks: BTreeMap<u64, Callback> = BTreeMap::new();
+
+    for raw in api["callback_structs"]
+        .as_array()
+        .expect("callback_structs must be an array")
+    {
+        let name = raw["struct"].as_str().expect("struct must be a string");
+        let id = raw["callback_id"]
+            .as_u64()
+            .expect("callback_id must be a number");
+
+        if SKIPPED_CALLBACKS.contains(&name) {
+            continue;
+        }
+
+        let has_pointers = raw["fields"].as_array().into_iter().flatten().any(|field| {
+            field["fieldtype"]
+                .as_str()
+                .unwrap_or_default()
+                .contains('*')
+        });
+
+        match callbacks.get_mut(&id) {
+            // Client and server flavours of the same callback, e.g. `GSStatsUnloaded_t`
+            Some(existing) => existing.aliases.push(name.to_string()),
+            None => {
+                callbacks.insert(
+                    id,
+                    Callback {
+                        id,
+                        name: name.to_string(),
+                        variant: name.trim_end_matches("_t").replace('_', ""),
+                        aliases: vec![],
+                        call_result: call_results.contains(name),
+                        has_pointers,
+                    },
+                );
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let callbacks = callbacks.into_values().collect::<Vec<_>>();
+
+    writeln!(
+        out,
+        "/// Every callback of the linked SDK, generated from `steam_api.json`."
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]"
+    )
+    .unwrap();
+    writeln!(out, "#[repr(u32)]").unwrap();
+    writeln!(out, "pub(crate) enum CallbackType {{").unwrap();
+    for callback in &callbacks {
+        writeln!(out, "    {} = {},", callback.variant, callback.id).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl CallbackType {{").unwrap();
+
+    writeln!(
+        out,
+        "    pub(crate) const ALL: &'static [CallbackType] = &["
+    )
+    .unwrap();
+    for callback in &callbacks {
+        writeln!(out, "        CallbackType::{},", callback.variant).unwrap();
+    }
+    writeln!(out, "    ];\n").unwrap();
+
+    write_predicate(&mut out, "is_call_result", &callbacks, |cb| cb.call_result);
+
+    writeln!(out, "    /// Name of the SDK struct.").unwrap();
+    writeln!(out, "    pub(crate) fn name(&self) -> &'static str {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for callback in &callbacks {
+        writeln!(
+            out,
+            "            CallbackType::{} => \"{}\",",
+            callback.variant, callback.name
+        )
+        .unwrap();
+    }
+    writeln!(out, "        }}\n    }}\n").unwrap();
+
+    writeln!(out, "    /// Size of the SDK struct.").unwrap();
+    writeln!(out, "    pub(crate) fn raw_size(&self) -> usize {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for callback in &callbacks {
+        writeln!(
+            out,
+            "            CallbackType::{} => std::mem::size_of::<sys::{}>(),",
+            callback.variant, callback.name
+        )
+        .unwrap();
+    }
+    writeln!(out, "        }}\n    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl TryFrom<u32> for CallbackType {{").unwrap();
+    writeln!(out, "    type Error = UnknownCallback;\n").unwrap();
+    writeln!(
+        out,
+        "    fn try_from(value: u32) -> Result<Self, Self::Error> {{"
+    )
+    .unwrap();
+    writeln!(out, "        match value {{").unwrap();
+    for callback in &callbacks {
+        writeln!(
+            out,
+            "            {} => Ok(CallbackType::{}),",
+            callback.id, callback.variant
+        )
+        .unwrap();
+    }
+    writeln!(out, "            _ => Err(UnknownCallback),").unwrap();
+    writeln!(out, "        }}\n    }}\n}}\n").unwrap();
+
+    // Structs with pointers only live until the callback is freed, they can't be copied out
+    for callback in callbacks.iter().filter(|cb| !cb.has_pointers) {
+        for name in std::iter::once(&callback.name).chain(&callback.aliases) {
+            writeln!(out, "impl SteamCallbackStruct for sys::{} {{", name).unwrap();
+            writeln!(
+                out,
+                "    const TYPE: CallbackType = CallbackType::{};",
+                callback.variant
+            )
+            .unwrap();
+            writeln!(out, "}}\n").unwrap();
+        }
+    }
+
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("callbacks.rs");
+    std::fs::write(out_path, out).expect("Couldn't write callbacks.rs!");
+}
+
+/// Generates the SDK version and `*_INTERFACE_VERSION` constants from the SDK headers.
+fn generate_sdk_info(sdk_loc: &Path) {
+    let header_dir = sdk_loc.join("public/steam");
+    println!("cargo::rerun-if-changed={}", header_dir.display());
+    println!("cargo::rerun-if-env-changed=STEAM_SDK_VERSION");
+
+    let mut interfaces = BTreeMap::new();
+    for entry in std::fs::read_dir(&header_dir).expect("Couldn't read the SDK headers!") {
+        let path = entry.expect("Couldn't read the SDK headers!").path();
+        if path.extension().is_none_or(|ext| ext != "h") {
+            continue;
+        }
+
+        let header = std::fs::read(&path).expect("Couldn't read an SDK header!");
+        for line in String::from_utf8_lossy(&header).lines() {
+            let mut tokens = line.split_whitespace();
+            if tokens.next() != Some("#define") {
+                continue;
+            }
+            let (Some(name), Some(version)) = (tokens.next(), tokens.next()) else {
+                continue;
+            };
+
+            if name.ends_with("_INTERFACE_VERSION") && version.starts_with('"') {
+                interfaces.insert(name.to_string(), version.trim_matches('"').to_string());
+            }
+        }
+    }
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "/// Version of the Steamworks SDK, `\"unknown\"` if the SDK didn't say."
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub const SDK_VERSION: &str = {:?};\n",
+        sdk_version(sdk_loc)
+    )
+    .unwrap();
+
+    for (name, version) in &interfaces {
+        writeln!(out, "pub const {}: &str = {:?};", name, version).unwrap();
+    }
+
+    writeln!(
+        out,
+        "\npub(crate) const INTERFACE_VERSIONS: &[(&str, &str)] = &["
+    )
+    .unwrap();
+    for name in interfaces.keys() {
+        writeln!(out, "    ({:?}, {}),", name, name).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("sdk_info.rs");
+    std::fs::write(out_path, out).expect("Couldn't write sdk_info.rs!");
+}
+
+/// `STEAM_SDK_VERSION`, or the latest version in the changelog of the SDK's `Readme.txt`.
+fn sdk_version(sdk_loc: &Path) -> String {
+    if let Ok(version) = std::env::var("STEAM_SDK_VERSION") {
+        return version;
+    }
+
+    let readme_path = sdk_loc.join("Readme.txt");
+    println!("cargo::rerun-if-changed={}", readme_path.display());
+
+    // Changelog entries start with a line like `v1.60 19th June 2024`
+    let version = std::fs::read(&readme_path).ok().and_then(|readme| {
+        String::from_utf8_lossy(&readme)
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .find(|word| {
+                word.strip_prefix('v').is_some_and(|number| {
+                    number.contains('.') && number.chars().all(|c| c.is_ascii_digit() || c == '.')
+                })
+            })
+            .map(str::to_string)
+    });
+    version.unwrap_or_else(|| {
+        println!(
+            "cargo::warning=Couldn't tell the version of the Steamworks SDK at {}, set STEAM_SDK_VERSION",
+            sdk_loc.display()
+        );
+        "unknown".to_string()
+    })
+}
+
+fn write_predicate(
+    out: &mut String,
+    name: &str,
+    callbacks: &[Callback],
+    predicate: impl Fn(&Callback) -> bool,
+) {
+    let variants = callbacks
+        .iter()
+        .filter(|cb| predicate(cb))
+        .map(|cb| format!("CallbackType::{}", cb.variant))
+        .collect::<Vec<_>>();
+
+    writeln!(out, "    pub(crate) fn {}(&self) -> bool {{", name).unwrap();
+    if variants.is_empty() {
+        writeln!(out, "        false").unwrap();
+    } else {
+        writeln!(out, "        matches!(").unwrap();
+        writeln!(out, "            self,").unwrap();
+        writeln!(out, "            {}", variants.join("\n                | ")).unwrap();
+        writeln!(out, "        )").unwrap();
+    }
+    writeln!(out, "    }}\n").unwrap();
 }
@@ -1,23 +1,39 @@
 #![allow(private_bounds)]
 
-use std::{ops::Deref, sync::Arc};
+use std::{
+    ffi::CString,
+    net::{Ipv4Addr, SocketAddrV4},
+    ops::Deref,
+    sync::Arc,
+};
+
+#[cfg(feature = "runtime-link")]
+use std::path::PathBuf;
 
+use crate::backend::{SteamBackend, SteamworksBackend};
 use crate::core::{
     client::SteamApiClient,
-    enums::{ServerMode, SteamApiInitError},
+    enums::{Interface, ServerMode, SteamApiInitError},
     server::SteamApiServer,
     structs::AppId,
     SteamApiInterface,
 };
 
+/// Pass as query port to share the game port for server browser queries.
+pub const QUERY_PORT_SHARED: u16 = steamgear_sys::STEAMGAMESERVER_QUERY_PORT_SHARED;
+
 #[derive(Clone, Debug)]
-pub struct SteamApi<T: SteamApiInterface>(Arc<T>);
+pub struct SteamApi<T: SteamApiInterface>(pub(crate) Arc<T>);
 
 impl<T: SteamApiInterface> SteamApi<T> {
+    /// Shuts the API down for every clone, interface handles then fail with
+    /// [`NotInitialized`](crate::core::enums::NotInitialized).
+    ///
+    /// Waits for interface calls running on other threads to return first.
     pub fn shutdown(&self) {
         let count = Arc::strong_count(&self.0);
         if count > 1 {
-            tracing::warn!(
+            tracing::debug!(
                 "Called shutdown when amount clones of steam api is {}",
                 count
             );
@@ -28,22 +44,14 @@ impl<T: SteamApiInterface> SteamApi<T> {
 }
 
 impl SteamApi<SteamApiClient> {
-    pub fn new_client(app_id: Option<AppId>) -> Result<Self, SteamApiInitError> {
-        let client = SteamApiClient::init((app_id,))?;
-        Ok(Self(Arc::new(client)))
+    pub fn client() -> ClientBuilder {
+        ClientBuilder::new()
     }
 }
 
 impl SteamApi<SteamApiServer> {
-    pub fn new_server(
-        app_id: Option<AppId>,
-        addr: std::net::SocketAddrV4,
-        query_port: u16,
-        mode: ServerMode,
-        version: (u8, u8, u8, u8),
-    ) -> Result<Self, SteamApiInitError> {
-        let server = SteamApiServer::init((app_id, addr, query_port, mode, version))?;
-        Ok(Self(Arc::new(server)))
+    pub fn server() -> ServerBuilder {
+        ServerBuilder::new()
     }
 }
 
@@ -54,3 +62,223 @@ impl<T: SteamApiInterface> Deref for SteamApi<T> {
         &self.0
     }
 }
+
+/// Options for initializing the Steam client API.
+#[derive(Clone, Debug)]
+pub struct ClientBuilder {
+    pub(crate) backend: Arc<dyn SteamBackend>,
+    pub(crate) app_id: Option<AppId>,
+    pub(crate) restart_if_necessary: bool,
+    pub(crate) interfaces: Vec<Interface>,
+    #[cfg(feature = "runtime-link")]
+    pub(crate) library_path: Option<PathBuf>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            backend: Arc::new(SteamworksBackend),
+            app_id: None,
+            restart_if_necessary: false,
+            interfaces: Interface::CLIENT.to_vec(),
+            #[cfg(feature = "runtime-link")]
+            library_path: None,
+        }
+    }
+
+    /// Without an app id Steam reads `steam_appid.txt` or the launch environment.
+    pub fn app_id(mut self, app_id: AppId) -> Self {
+        self.app_id = Some(app_id);
+        self
+    }
+
+    /// Calls `SteamAPI_RestartAppIfNecessary` first, init then fails with
+    /// [`SteamApiInitError::RestartRequired`] and the game should exit.
+    pub fn restart_if_necessary(mut self, restart: bool) -> Self {
+        self.restart_if_necessary = restart;
+        self
+    }
+
+    /// Interfaces whose versions are checked during init, all client interfaces by default.
+    pub fn interfaces(mut self, interfaces: impl IntoIterator<Item = Interface>) -> Self {
+        self.interfaces = interfaces.into_iter().collect();
+        self
+    }
+
+    /// Loads `libsteam_api` from `path` instead of next to the executable.
+    /// Only the first library loaded in the process is used.
+    #[cfg(feature = "runtime-link")]
+    pub fn library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_path = Some(path.into());
+        self
+    }
+
+    pub fn backend(mut self, backend: Arc<dyn SteamBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn build(self) -> Result<SteamApi<SteamApiClient>, SteamApiInitError> {
+        check_interfaces(&self.interfaces, Interface::CLIENT)?;
+
+        #[cfg(feature = "runtime-link")]
+        if let Some(path) = &self.library_path {
+            crate::backend::flat::load(path)?;
+        }
+
+        if self.restart_if_necessary {
+            let app_id = self.app_id.ok_or(SteamApiInitError::MissingAppId)?;
+
+            if self.backend.restart_app_if_necessary(app_id) {
+                return Err(SteamApiInitError::RestartRequired);
+            }
+        }
+
+        let client = SteamApiClient::init(self)?;
+        Ok(SteamApi(Arc::new(client)))
+    }
+}
+
+/// Options for initializing a game server.
+#[derive(Clone, Debug)]
+pub struct ServerBuilder {
+    pub(crate) backend: Arc<dyn SteamBackend>,
+    pub(crate) app_id: Option<AppId>,
+    pub(crate) ip: Ipv4Addr,
+    pub(crate) game_port: u16,
+    pub(crate) query_port: u16,
+    pub(crate) mode: ServerMode,
+    pub(crate) version: String,
+    pub(crate) interfaces: Vec<Interface>,
+    #[cfg(feature = "runtime-link")]
+    pub(crate) library_path: Option<PathBuf>,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerBuilder {
+    /// Binds every address on ports 27015 and 27016 with authentication, version `1.0.0.0`.
+    pub fn new() -> Self {
+        Self {
+            backend: Arc::new(SteamworksBackend),
+            app_id: None,
+            ip: Ipv4Addr::UNSPECIFIED,
+            game_port: 27015,
+            query_port: 27016,
+            mode: ServerMode::Authentication,
+            version: "1.0.0.0".to_string(),
+            interfaces: Interface::SERVER.to_vec(),
+            #[cfg(feature = "runtime-link")]
+            library_path: None,
+        }
+    }
+
+    pub fn app_id(mut self, app_id: AppId) -> Self {
+        self.app_id = Some(app_id);
+        self
+    }
+
+    pub fn ip(mut self, ip: Ipv4Addr) -> Self {
+        self.ip = ip;
+        self
+    }
+
+    pub fn game_port(mut self, port: u16) -> Self {
+        self.game_port = port;
+        self
+    }
+
+    /// Port for server browser queries, see [`QUERY_PORT_SHARED`].
+    pub fn query_port(mut self, port: u16) -> Self {
+        self.query_port = port;
+        self
+    }
+
+    pub fn mode(mut self, mode: ServerMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Usually `x.x.x.x`, the master server uses it to flag outdated servers.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Interfaces whose versions are checked during init, all server interfaces by default.
+    pub fn interfaces(mut self, interfaces: impl IntoIterator<Item = Interface>) -> Self {
+        self.interfaces = interfaces.into_iter().collect();
+        self
+    }
+
+    /// Loads `libsteam_api` from `path` instead of next to the executable.
+    /// Only the first library loaded in the process is used.
+    #[cfg(feature = "runtime-link")]
+    pub fn library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_path = Some(path.into());
+        self
+    }
+
+    pub fn backend(mut self, backend: Arc<dyn SteamBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn build(self) -> Result<SteamApi<SteamApiServer>, SteamApiInitError> {
+        check_interfaces(&self.interfaces, Interface::SERVER)?;
+
+        #[cfg(feature = "runtime-link")]
+        if let Some(path) = &self.library_path {
+            crate::backend::flat::load(path)?;
+        }
+
+        if self.game_port == 0 || self.game_port == self.query_port {
+            return Err(SteamApiInitError::InvalidPorts {
+                game: self.game_port,
+                query: self.query_port,
+            });
+        }
+
+        if self.mode == ServerMode::Invalid {
+            return Err(SteamApiInitError::InvalidServerMode);
+        }
+
+        let server = SteamApiServer::init(self)?;
+        Ok(SteamApi(Arc::new(server)))
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddrV4 {
+        SocketAddrV4::new(self.ip, self.game_port)
+    }
+
+    pub(crate) fn version_cstring(&self) -> Result<CString, SteamApiInitError> {
+        if self.version.is_empty() {
+            return Err(SteamApiInitError::InvalidVersion);
+        }
+
+        CString::new(self.version.as_str()).map_err(|_| SteamApiInitError::InvalidVersion)
+    }
+}
+
+fn check_interfaces(
+    requested: &[Interface],
+    available: &[Interface],
+) -> Result<(), SteamApiInitError> {
+    match requested
+        .iter()
+        .find(|interface| !available.contains(interface))
+    {
+        Some(interface) => Err(SteamApiInitError::UnsupportedInterface(*interface)),
+        None => Ok(()),
+    }
+}
@@ -1,5 +1,6 @@
 use crate::core::{
-    callback::{CallbackDispatcher, CallbackType, CallbackTyped},
+    callback::{CallbackDispatcher, CallbackError, CallbackType, CallbackTyped},
+    enums::{NotInitialized, SteamError},
     structs::AppId,
 };
 
@@ -9,6 +10,7 @@ use steamgear_sys as sys;
 use super::SteamApps;
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DlcInstalled {
     pub id: AppId,
 }
@@ -26,6 +28,7 @@ impl CallbackTyped for DlcInstalled {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NewUrlLaunchParams;
 
 impl CallbackTyped for NewUrlLaunchParams {
@@ -38,15 +41,89 @@ impl CallbackTyped for NewUrlLaunchParams {
     }
 }
 
+/// A legacy CD key, or why Steam has none for the app.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofOfPurchaseKey {
+    pub app_id: AppId,
+    pub key: Result<String, SteamError>,
+}
+
+impl CallbackTyped for ProofOfPurchaseKey {
+    const TYPE: CallbackType = CallbackType::AppProofOfPurchaseKeyResponse;
+    type Raw = sys::AppProofOfPurchaseKeyResponse_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        let key = SteamError::from_code(raw.m_eResult).map(|_| {
+            let len = (raw.m_cchKeyLength as usize).min(raw.m_rgchKey.len());
+            let key = raw.m_rgchKey[..len]
+                .iter()
+                .map(|&c| c as u8)
+                .take_while(|&c| c != 0)
+                .collect::<Vec<_>>();
+
+            String::from_utf8_lossy(&key).into_owned()
+        });
+
+        ProofOfPurchaseKey {
+            app_id: AppId(raw.m_nAppID),
+            key,
+        }
+    }
+}
+
 impl SteamApps {
-    pub async fn install_dlc(&self, app_id: AppId) -> DlcInstalled {
+    pub async fn install_dlc(&self, app_id: AppId) -> Result<DlcInstalled, CallbackError> {
         let recv = self.container.dlc_installed_callback.register();
 
-        unsafe {
-            sys::SteamAPI_ISteamApps_InstallDLC(self.raw, app_id.0);
+        {
+            let _guard = self.backend.state().client.enter()?;
+            self.backend.apps_install_dlc(app_id);
         }
 
-        recv.recv().await.unwrap()
+        // Other installs may finish first
+        loop {
+            let installed = recv.recv().await.map_err(|_| CallbackError::Shutdown)?;
+            if installed.id == app_id {
+                return Ok(installed);
+            }
+        }
+    }
+
+    /// Requests the legacy CD key of the app or one of its DLC.
+    pub async fn request_proof_of_purchase_key(
+        &self,
+        app_id: AppId,
+    ) -> Result<ProofOfPurchaseKey, CallbackError> {
+        let recv = self.container.proof_of_purchase_key_callback.register();
+
+        {
+            let _guard = self.backend.state().client.enter()?;
+            self.backend.apps_request_proof_of_purchase_key(app_id);
+        }
+
+        // Keys requested elsewhere arrive here too
+        loop {
+            let key = recv.recv().await.map_err(|_| CallbackError::Shutdown)?;
+            if key.app_id == app_id {
+                return Ok(key);
+            }
+        }
+    }
+
+    /// Requests the legacy CD keys of the app and every owned DLC, one item per key.
+    ///
+    /// Steam doesn't say how many keys will arrive, the stream never ends on its own.
+    pub fn request_all_proof_of_purchase_keys(
+        &self,
+    ) -> Result<impl Stream<Item = ProofOfPurchaseKey>, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        let recv = self.container.proof_of_purchase_key_callback.register();
+        self.backend.apps_request_all_proof_of_purchase_keys();
+
+        Ok(recv)
     }
 
     pub fn on_new_launch_query_param(&self) -> impl Stream<Item = NewUrlLaunchParams> {
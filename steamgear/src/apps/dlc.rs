@@ -0,0 +1,294 @@
+//! DLC installs tracked per [`AppId`], with download progress read once per
+//! `run_callbacks` frame.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use async_channel::Sender;
+use futures_core::Stream;
+use parking_lot::Mutex;
+
+use crate::{
+    backend::SteamBackend,
+    core::{
+        callback::{forward, MULTI_DISPATCHER_CAPACITY},
+        enums::{AppsError, DlcInstallError, NotInitialized},
+        metrics::Dispatched,
+        structs::AppId,
+    },
+};
+
+use super::{
+    callbacks::DlcInstalled,
+    structs::{DlcDownloadProgress, DlcInformation},
+    SteamApps,
+};
+
+/// Weight of the newest sample in the smoothed download rate.
+const RATE_SMOOTHING: f64 = 0.3;
+
+const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DlcProgress {
+    pub downloaded: u64,
+    pub total: u64,
+    /// Between 0 and 100, 0 while Steam doesn't know the total yet.
+    pub percent: f32,
+    /// Smoothed download rate, `None` until two samples were taken.
+    pub bytes_per_second: Option<f64>,
+}
+
+/// Item of an install stream, which ends after `Installed` or `Failed`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DlcEvent {
+    Progress(DlcProgress),
+    Installed,
+    Failed(DlcInstallError),
+}
+
+/// Ownership and install state of one DLC.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DlcStatus {
+    pub info: DlcInformation,
+    pub owned: bool,
+    pub installed: bool,
+    pub download: Option<DlcDownloadProgress>,
+}
+
+/// Installs and uninstalls DLC, any number of installs can run at once.
+#[derive(Clone, Debug)]
+pub struct DlcManager {
+    apps: SteamApps,
+}
+
+impl DlcManager {
+    pub(crate) fn new(apps: SteamApps) -> Self {
+        Self { apps }
+    }
+
+    /// Starts installing `dlc_id` and returns its events.
+    ///
+    /// Installing a DLC that is already downloading subscribes to the running install.
+    /// Progress is only read while `run_callbacks` is called.
+    pub fn install(&self, dlc_id: AppId) -> Result<impl Stream<Item = DlcEvent>, NotInitialized> {
+        let _guard = self.apps.backend.state().client.enter()?;
+
+        let (sender, receiver) = async_channel::bounded(MULTI_DISPATCHER_CAPACITY);
+
+        if self.apps.backend.apps_is_dlc_installed(dlc_id) {
+            let _ = sender.force_send(DlcEvent::Installed);
+        } else if !self.apps.backend.apps_is_subscribed_app(dlc_id) {
+            let _ = sender.force_send(DlcEvent::Failed(DlcInstallError::NotOwned));
+        } else if self.apps.container.dlc_installs.track(dlc_id, sender) {
+            tracing::debug!("Installing DLC {}", dlc_id.0);
+            self.apps.backend.apps_install_dlc(dlc_id);
+        }
+
+        Ok(receiver)
+    }
+
+    /// Uninstalls `dlc_id`, an install in flight fails with [`DlcInstallError::Uninstalled`].
+    pub fn uninstall(&self, dlc_id: AppId) -> Result<(), NotInitialized> {
+        let _guard = self.apps.backend.state().client.enter()?;
+
+        self.apps.backend.apps_uninstall_dlc(dlc_id);
+        self.apps
+            .container
+            .dlc_installs
+            .finish(dlc_id, DlcEvent::Failed(DlcInstallError::Uninstalled));
+
+        Ok(())
+    }
+
+    /// Fails installs whose downloaded bytes didn't change for `timeout` with
+    /// [`DlcInstallError::Stalled`], 5 minutes by default. `None` waits forever.
+    pub fn set_stall_timeout(&self, timeout: Option<Duration>) {
+        *self.apps.container.dlc_installs.stall_timeout.lock() = timeout;
+    }
+
+    /// DLC with an install in flight.
+    pub fn installing(&self) -> Vec<AppId> {
+        self.apps.container.dlc_installs.installing()
+    }
+
+    /// Every DLC of the app with whether the user owns and has installed it.
+    pub fn snapshot(&self) -> Result<Vec<DlcStatus>, AppsError> {
+        let dlcs = self.apps.get_dlc_information()?;
+
+        let _guard = self.apps.backend.state().client.enter()?;
+
+        Ok(dlcs
+            .into_iter()
+            .map(|info| DlcStatus {
+                owned: self.apps.backend.apps_is_subscribed_app(info.dlc_id),
+                installed: self.apps.backend.apps_is_dlc_installed(info.dlc_id),
+                download: self
+                    .apps
+                    .backend
+                    .apps_dlc_download_progress(info.dlc_id)
+                    .map(|(downloaded, total)| DlcDownloadProgress { downloaded, total }),
+                info,
+            })
+            .collect())
+    }
+}
+
+/// Installs in flight, fed by `run_callbacks`.
+#[derive(Debug)]
+pub(crate) struct DlcInstalls {
+    inner: Mutex<HashMap<AppId, DlcInstallState>>,
+    stall_timeout: Mutex<Option<Duration>>,
+}
+
+impl Default for DlcInstalls {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            stall_timeout: Mutex::new(Some(DEFAULT_STALL_TIMEOUT)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DlcInstallState {
+    subscribers: Vec<Sender<DlcEvent>>,
+    last: Option<Sample>,
+    bytes_per_second: Option<f64>,
+    /// When the downloaded bytes last changed, or the install started.
+    progressed_at: Instant,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    at: Instant,
+    downloaded: u64,
+    total: u64,
+}
+
+impl DlcInstalls {
+    /// Adds a subscriber, returns whether this starts a new install.
+    fn track(&self, dlc_id: AppId, sender: Sender<DlcEvent>) -> bool {
+        let mut inner = self.inner.lock();
+        let started = !inner.contains_key(&dlc_id);
+
+        inner
+            .entry(dlc_id)
+            .or_insert_with(|| DlcInstallState {
+                subscribers: Vec::new(),
+                last: None,
+                bytes_per_second: None,
+                progressed_at: Instant::now(),
+            })
+            .subscribers
+            .push(sender);
+
+        started
+    }
+
+    fn installing(&self) -> Vec<AppId> {
+        self.inner.lock().keys().copied().collect()
+    }
+
+    /// Hands `event` to the subscribers of `dlc_id` and closes their streams.
+    fn finish(&self, dlc_id: AppId, event: DlcEvent) -> Dispatched {
+        let Some(state) = self.inner.lock().remove(&dlc_id) else {
+            return Dispatched::default();
+        };
+
+        state.send(event)
+    }
+
+    pub(crate) fn proceed(&self, installed: DlcInstalled) -> Dispatched {
+        self.finish(installed.id, DlcEvent::Installed)
+    }
+
+    /// Reads the download progress of every install, called once per frame.
+    pub(crate) fn poll(&self, backend: &dyn SteamBackend) {
+        let mut inner = self.inner.lock();
+        if inner.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let stall_timeout = *self.stall_timeout.lock();
+        inner.retain(|dlc_id, state| {
+            if let Some((downloaded, total)) = backend.apps_dlc_download_progress(*dlc_id) {
+                if let Some(progress) = state.sample(now, downloaded, total) {
+                    state.send(DlcEvent::Progress(progress));
+                }
+            }
+
+            if stall_timeout.is_some_and(|timeout| now - state.progressed_at > timeout) {
+                tracing::warn!("DLC {} install stalled", dlc_id.0);
+                state.send(DlcEvent::Failed(DlcInstallError::Stalled));
+                return false;
+            }
+
+            state.subscribers.retain(|sender| !sender.is_closed());
+            !state.subscribers.is_empty()
+        });
+    }
+
+    /// Fails every install, used on shutdown.
+    pub(crate) fn cancel(&self) {
+        for (_, state) in self.inner.lock().drain() {
+            state.send(DlcEvent::Failed(DlcInstallError::Shutdown));
+        }
+    }
+}
+
+impl DlcInstallState {
+    /// Records a progress reading, `None` if nothing changed since the last one.
+    fn sample(&mut self, at: Instant, downloaded: u64, total: u64) -> Option<DlcProgress> {
+        let sample = Sample {
+            at,
+            downloaded,
+            total,
+        };
+
+        if self.last.is_none_or(|last| last.downloaded != downloaded) {
+            self.progressed_at = at;
+        }
+
+        if let Some(last) = self.last {
+            if last.downloaded == downloaded && last.total == total {
+                return None;
+            }
+
+            let elapsed = at.duration_since(last.at).as_secs_f64();
+            if elapsed > 0.0 {
+                let rate = downloaded.saturating_sub(last.downloaded) as f64 / elapsed;
+                self.bytes_per_second = Some(match self.bytes_per_second {
+                    Some(smoothed) => smoothed + RATE_SMOOTHING * (rate - smoothed),
+                    None => rate,
+                });
+            }
+        }
+        self.last = Some(sample);
+
+        Some(DlcProgress {
+            downloaded,
+            total,
+            percent: match total {
+                0 => 0.0,
+                total => (downloaded as f64 / total as f64 * 100.0).min(100.0) as f32,
+            },
+            bytes_per_second: self.bytes_per_second,
+        })
+    }
+
+    fn send(&self, event: DlcEvent) -> Dispatched {
+        self.subscribers
+            .iter()
+            .map(|sender| forward(sender, event))
+            .fold(Dispatched::default(), |total, dispatched| {
+                total + dispatched
+            })
+    }
+}
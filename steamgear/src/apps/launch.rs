@@ -0,0 +1,321 @@
+//! Launch parameters from the Steam command line and `steam://run` links.
+//!
+//! A link like `steam://run/480//+connect%20127.0.0.1/?lobby=42` starts the game with
+//! the command line `+connect 127.0.0.1` and the query parameter `lobby`. Clicked while
+//! the game runs, Steam fires `NewUrlLaunchParameters_t` instead of starting it again,
+//! see [`SteamApps::on_launch_params`].
+
+use std::{collections::BTreeMap, ffi::CString, str::FromStr};
+
+use async_channel::{Receiver, Sender};
+use futures_core::Stream;
+use parking_lot::Mutex;
+
+use crate::{
+    backend::SteamBackend,
+    core::{
+        callback::{forward, CallbackType, DROPPED, MULTI_DISPATCHER_CAPACITY},
+        enums::{AppsError, NotInitialized, SteamUrlError},
+        metrics::{Dispatched, QueueDepth},
+        structs::AppId,
+    },
+};
+
+use super::{read_string, utf8, SteamApps};
+
+const RUN_PREFIX: &str = "steam://run/";
+
+/// Query parameters of a launch, keys are case sensitive.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct LaunchQuery(BTreeMap<String, String>);
+
+impl LaunchQuery {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Parses the value of `key`, `None` if it's missing.
+    pub fn get_as<T: FromStr>(&self, key: &str) -> Option<Result<T, T::Err>> {
+        self.get(key).map(str::parse)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.0.insert(key.into(), value.into());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parses `key=value` pairs separated by `;` or `&`, percent-encoded.
+    pub fn parse(query: &str) -> Result<Self, SteamUrlError> {
+        let mut params = Self::default();
+
+        for pair in query.split([';', '&']).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            params.insert(percent_decode(key)?, percent_decode(value)?);
+        }
+
+        Ok(params)
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for LaunchQuery {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+/// A parsed `steam://run/<appid>/<language>/<args>/?<query>` link.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SteamRunUrl {
+    pub app_id: AppId,
+    /// Empty in the usual `steam://run/<appid>//<args>` form.
+    pub language: String,
+    pub args: Vec<String>,
+    pub query: LaunchQuery,
+}
+
+impl FromStr for SteamRunUrl {
+    type Err = SteamUrlError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let rest = url
+            .get(..RUN_PREFIX.len())
+            .filter(|scheme| scheme.eq_ignore_ascii_case(RUN_PREFIX))
+            .map(|_| &url[RUN_PREFIX.len()..])
+            .ok_or(SteamUrlError::NotRunUrl)?;
+
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut segments = path.splitn(3, '/');
+
+        let app_id = segments.next().unwrap_or_default();
+        let app_id = app_id
+            .parse()
+            .map(AppId)
+            .map_err(|_| SteamUrlError::InvalidAppId(app_id.to_string()))?;
+        let language = percent_decode(segments.next().unwrap_or_default())?;
+        let args = segments.next().unwrap_or_default();
+        let args = percent_decode(args.strip_suffix('/').unwrap_or(args))?;
+
+        Ok(Self {
+            app_id,
+            language,
+            args: parse_command_line(&args),
+            query: LaunchQuery::parse(query)?,
+        })
+    }
+}
+
+/// Launch parameters of the running game.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LaunchParams {
+    /// The command line as Steam handed it out.
+    pub command_line: String,
+    pub args: Vec<String>,
+    pub query: LaunchQuery,
+}
+
+impl LaunchParams {
+    /// Splits `command_line` into arguments, a whole `steam://run` link is unpacked.
+    pub fn from_command_line(command_line: impl Into<String>) -> Self {
+        let command_line = command_line.into();
+        let trimmed = command_line.trim();
+
+        let (args, query) = match trimmed.parse::<SteamRunUrl>() {
+            Ok(url) => (url.args, url.query),
+            Err(_) => (parse_command_line(trimmed), LaunchQuery::default()),
+        };
+
+        Self {
+            command_line,
+            args,
+            query,
+        }
+    }
+
+    /// The argument after `name`, as in `+connect <address>`.
+    pub fn value_of(&self, name: &str) -> Option<&str> {
+        let position = self.args.iter().position(|arg| arg == name)?;
+        self.args.get(position + 1).map(String::as_str)
+    }
+}
+
+/// Splits a command line into arguments.
+///
+/// Whitespace separates arguments except inside single or double quotes, a backslash
+/// escapes the next quote or backslash. An unterminated quote runs to the end.
+pub fn parse_command_line(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_arg = false;
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('"' | '\'' | '\\')) => {
+                arg.extend(chars.next());
+                in_arg = true;
+            }
+            '"' | '\'' if quote == Some(c) => quote = None,
+            '"' | '\'' if quote.is_none() => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            c if c.is_whitespace() && quote.is_none() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            c => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(arg);
+    }
+
+    args
+}
+
+fn percent_decode(text: &str) -> Result<String, SteamUrlError> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or(SteamUrlError::InvalidEncoding)?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| SteamUrlError::InvalidEncoding)
+}
+
+/// Reads the command line and the query parameters `keys`.
+fn read_launch_params(
+    backend: &dyn SteamBackend,
+    keys: &[CString],
+) -> Result<LaunchParams, AppsError> {
+    let line =
+        read_string(|buffer| Some(backend.apps_launch_command_line(buffer).max(0) as usize))?;
+    let mut params = LaunchParams::from_command_line(utf8(line)?);
+
+    for key in keys {
+        let value = backend.apps_launch_query_param(key);
+        if !value.is_empty() {
+            params.query.insert(
+                key.to_string_lossy(),
+                value.into_string().map_err(|_| AppsError::InvalidUtf8)?,
+            );
+        }
+    }
+
+    Ok(params)
+}
+
+fn query_keys(keys: &[&str]) -> Vec<CString> {
+    keys.iter()
+        .filter_map(|key| CString::new(*key).ok())
+        .collect()
+}
+
+impl SteamApps {
+    /// The current launch parameters with the query parameters `keys`, Steam can't list
+    /// the keys it has.
+    pub fn launch_params(&self, keys: &[&str]) -> Result<LaunchParams, AppsError> {
+        let _guard = self.backend.state().client.enter()?;
+
+        read_launch_params(&*self.backend, &query_keys(keys))
+    }
+
+    /// The new launch parameters every time a `steam://run` link is opened while the
+    /// game runs.
+    pub fn on_launch_params(
+        &self,
+        keys: &[&str],
+    ) -> Result<impl Stream<Item = LaunchParams>, NotInitialized> {
+        drop(self.backend.state().client.enter()?);
+
+        Ok(self.container.launch_params.register(query_keys(keys)))
+    }
+}
+
+/// Subscribers of [`SteamApps::on_launch_params`], each with its own keys.
+#[derive(Debug, Default)]
+pub(crate) struct LaunchParamsDispatcher {
+    inner: Mutex<Vec<(Sender<LaunchParams>, Vec<CString>)>>,
+}
+
+impl LaunchParamsDispatcher {
+    fn register(&self, keys: Vec<CString>) -> Receiver<LaunchParams> {
+        let (sender, receiver) = async_channel::bounded(MULTI_DISPATCHER_CAPACITY);
+        self.inner.lock().push((sender, keys));
+
+        receiver
+    }
+
+    pub(crate) fn proceed(&self, backend: &dyn SteamBackend) -> Dispatched {
+        let mut guard = self.inner.lock();
+        guard.retain(|(sender, _)| !sender.is_closed());
+
+        let mut dispatched = Dispatched::default();
+        for (sender, keys) in guard.iter() {
+            dispatched = dispatched
+                + match read_launch_params(backend, keys) {
+                    Ok(params) => forward(sender, params),
+                    Err(err) => {
+                        tracing::error!("Couldn't read new launch parameters: {}", err);
+                        DROPPED
+                    }
+                };
+        }
+
+        dispatched
+    }
+
+    pub(crate) fn queue_depth(&self) -> QueueDepth {
+        let mut depth = QueueDepth::new(CallbackType::NewUrlLaunchParameters as u32);
+        for (sender, _) in self.inner.lock().iter() {
+            depth.add(sender.len(), sender.capacity().unwrap_or(0));
+        }
+        depth
+    }
+
+    pub(crate) fn close(&self) {
+        for (sender, _) in self.inner.lock().drain(..) {
+            sender.close();
+        }
+    }
+}
@@ -1,281 +1,385 @@
 pub mod callbacks;
+pub mod dlc;
+pub mod launch;
 pub mod structs;
+pub mod verify;
 
 use std::{
-    ffi::CStr,
+    ffi::{CStr, CString, OsString},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use chrono::DateTime;
-use steamgear_sys as sys;
-use structs::{DlcDownloadProgress, DlcInformation, FileDetails, FileNotFound, TrialTime};
-
-use crate::core::{
-    callback::ClientCallbackContainer,
-    structs::{AppId, DepotId, SteamId},
+use dlc::DlcManager;
+use structs::{AppOwnershipTicket, DlcDownloadProgress, DlcInformation, FileDetails, TrialTime};
+
+use crate::{
+    backend::SteamBackend,
+    core::{
+        callback::{CallResult, ClientCallbackContainer},
+        enums::{AppsError, NotInitialized, SteamError},
+        structs::{AppId, DepotId, SteamId},
+    },
 };
 
+/// Buffers for strings Steam copies out start at this size and double up to
+/// [`MAX_BUFFER`] until the value fits.
+const INITIAL_BUFFER: usize = 256;
+const MAX_BUFFER: usize = 64 * 1024;
+
+const INITIAL_TICKET: usize = 1024;
+
+const INITIAL_DEPOTS: usize = 64;
+const MAX_DEPOTS: usize = 64 * 1024;
+
 #[derive(Clone, Debug)]
 pub struct SteamApps {
-    raw: *mut sys::ISteamApps,
+    backend: Arc<dyn SteamBackend>,
     container: Arc<ClientCallbackContainer>,
 }
 
-unsafe impl Send for SteamApps {}
-unsafe impl Sync for SteamApps {}
-
 impl SteamApps {
-    pub(crate) fn new(container: Arc<ClientCallbackContainer>) -> Self {
-        unsafe {
-            SteamApps {
-                raw: sys::SteamAPI_SteamApps_v008(),
-                container,
-            }
-        }
+    pub(crate) fn new(
+        backend: Arc<dyn SteamBackend>,
+        container: Arc<ClientCallbackContainer>,
+    ) -> Self {
+        SteamApps { backend, container }
     }
 }
 
 impl SteamApps {
-    pub fn is_subscribe(&self) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsSubscribed(self.raw) }
+    /// Tracks concurrent DLC installs, see [`DlcManager`].
+    pub fn dlc(&self) -> DlcManager {
+        DlcManager::new(self.clone())
+    }
+
+    pub fn is_subscribe(&self) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_subscribed())
     }
 
-    pub fn is_subscribed_from_family_sharing(&self) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsSubscribedFromFamilySharing(self.raw) }
+    pub fn is_subscribed_from_family_sharing(&self) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_subscribed_from_family_sharing())
     }
 
-    pub fn is_low_violence(&self) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsLowViolence(self.raw) }
+    pub fn is_low_violence(&self) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_low_violence())
     }
 
-    pub fn is_cybercafe(&self) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsCybercafe(self.raw) }
+    pub fn is_cybercafe(&self) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_cybercafe())
     }
 
-    pub fn is_vac_banned(&self) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsVACBanned(self.raw) }
+    pub fn is_vac_banned(&self) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_vac_banned())
     }
 
-    pub fn get_current_game_language(&self) -> String {
-        unsafe {
-            let raw = sys::SteamAPI_ISteamApps_GetCurrentGameLanguage(self.raw);
+    pub fn get_current_game_language(&self) -> Result<String, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
 
-            let str = CStr::from_ptr(raw as *mut _).to_string_lossy().to_string();
+        let raw = self.backend.apps_current_game_language();
 
-            str
-        }
+        Ok(raw.to_string_lossy().to_string())
     }
 
-    pub fn get_available_game_languages(&self) -> Vec<String> {
-        unsafe {
-            let raw = sys::SteamAPI_ISteamApps_GetAvailableGameLanguages(self.raw);
+    pub fn get_available_game_languages(&self) -> Result<Vec<String>, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
 
-            let raw = CStr::from_ptr(raw as *mut _);
-            let langs = raw.to_str().unwrap();
+        let raw = self.backend.apps_available_game_languages();
+        let langs = raw.to_string_lossy();
 
-            langs
-                .split(',')
-                .map(|lang| lang.to_string())
-                .collect::<Vec<_>>()
-        }
+        Ok(langs
+            .split(',')
+            .map(|lang| lang.to_string())
+            .collect::<Vec<_>>())
     }
 
-    pub fn is_subscribe_app(&self, app_id: AppId) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsSubscribedApp(self.raw, app_id.0) }
+    pub fn is_subscribe_app(&self, app_id: AppId) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_subscribed_app(app_id))
     }
 
-    pub fn is_dlc_installed(&self, dlc_id: AppId) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsDlcInstalled(self.raw, dlc_id.0) }
+    pub fn is_dlc_installed(&self, dlc_id: AppId) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_dlc_installed(dlc_id))
     }
 
-    pub fn purchase_time(&self, app_id: AppId) -> u32 {
-        unsafe { sys::SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(self.raw, app_id.0) }
+    pub fn purchase_time(&self, app_id: AppId) -> Result<u32, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_earliest_purchase_unix_time(app_id))
     }
 
-    pub fn purchase_date(&self, app_id: AppId) -> DateTime<chrono::Utc> {
-        let timestamp =
-            unsafe { sys::SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(self.raw, app_id.0) };
+    pub fn purchase_date(&self, app_id: AppId) -> Result<DateTime<chrono::Utc>, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        let timestamp = self.backend.apps_earliest_purchase_unix_time(app_id);
 
-        DateTime::from_timestamp(timestamp as _, 0).expect("invalid timestamp")
+        Ok(DateTime::from_timestamp(timestamp as _, 0).expect("invalid timestamp"))
     }
 
-    pub fn is_subscribed_from_free_weekend(&self) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsSubscribedFromFreeWeekend(self.raw) }
+    pub fn is_subscribed_from_free_weekend(&self) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_subscribed_from_free_weekend())
     }
 
-    pub fn get_dlc_information(&self) -> impl Iterator<Item = DlcInformation> + '_ {
-        unsafe {
-            let dlc_count = sys::SteamAPI_ISteamApps_GetDLCCount(self.raw);
-            (0..dlc_count).filter_map(|i| {
-                let mut app_id = 0;
-                let mut available = false;
-                let mut name_buffer = [0; 128];
-                if sys::SteamAPI_ISteamApps_BGetDLCDataByIndex(
-                    self.raw,
-                    i,
-                    &mut app_id,
-                    &mut available,
-                    name_buffer.as_mut_ptr(),
-                    128,
-                ) {
-                    let dlc_name = CStr::from_ptr(name_buffer.as_mut_ptr())
-                        .to_string_lossy()
-                        .to_string();
-                    Some(DlcInformation {
-                        dlc_id: AppId(app_id),
-                        dlc_name,
-                        available,
-                    })
-                } else {
-                    None
-                }
-            })
+    pub fn get_dlc_information(&self) -> Result<Vec<DlcInformation>, AppsError> {
+        let _guard = self.backend.state().client.enter()?;
+
+        let dlc_count = self.backend.apps_dlc_count();
+        let mut dlcs = Vec::with_capacity(dlc_count.max(0) as usize);
+
+        for i in 0..dlc_count {
+            let mut data = None;
+            let name = read_string(|buffer| {
+                data = self.backend.apps_dlc_data_by_index(i, buffer);
+                data.map(|_| 0)
+            });
+
+            // Indices can go stale while the DLC list changes under us.
+            let (name, Some((dlc_id, available))) = (name, data) else {
+                continue;
+            };
+
+            dlcs.push(DlcInformation {
+                dlc_id,
+                dlc_name: utf8(name?)?,
+                available,
+            });
         }
+
+        Ok(dlcs)
     }
 
-    pub fn uninstall_dlc(&self, dlc_id: AppId) {
-        unsafe {
-            sys::SteamAPI_ISteamApps_UninstallDLC(self.raw, dlc_id.0);
-        }
+    pub fn uninstall_dlc(&self, dlc_id: AppId) -> Result<(), NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        self.backend.apps_uninstall_dlc(dlc_id);
+
+        Ok(())
     }
 
-    pub fn get_current_beta_name(&self) -> Option<String> {
-        unsafe {
-            let mut name_buffer = [0; 128];
-
-            if sys::SteamAPI_ISteamApps_GetCurrentBetaName(self.raw, name_buffer.as_mut_ptr(), 128)
-            {
-                Some(
-                    CStr::from_ptr(name_buffer.as_mut_ptr())
-                        .to_string_lossy()
-                        .to_string(),
-                )
-            } else {
-                None
-            }
+    /// Name of the beta branch the app runs on, `None` on the default branch.
+    pub fn get_current_beta_name(&self) -> Result<Option<String>, AppsError> {
+        let _guard = self.backend.state().client.enter()?;
+
+        let name = read_string(|buffer| self.backend.apps_current_beta_name(buffer).then_some(0));
+
+        match name {
+            Ok(name) => utf8(name).map(Some),
+            Err(AppsError::NotFound) => Ok(None),
+            Err(err) => Err(err),
         }
     }
 
-    pub fn mark_content_corrupt(&self, missing_files_only: bool) {
-        unsafe {
-            sys::SteamAPI_ISteamApps_MarkContentCorrupt(self.raw, missing_files_only);
-        }
+    pub fn mark_content_corrupt(&self, missing_files_only: bool) -> Result<(), NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        self.backend.apps_mark_content_corrupt(missing_files_only);
+
+        Ok(())
     }
 
-    pub fn get_installed_depots(&self, app_id: AppId) -> impl Iterator<Item = DepotId> + 'static {
-        unsafe {
-            let mut depots = [0u32; 32];
-            let count = sys::SteamAPI_ISteamApps_GetInstalledDepots(
-                self.raw,
-                app_id.0,
-                depots.as_mut_ptr(),
-                32,
-            ) as usize;
-
-            depots.into_iter().take(count).map(DepotId)
+    pub fn get_installed_depots(
+        &self,
+        app_id: AppId,
+    ) -> Result<impl Iterator<Item = DepotId> + 'static, AppsError> {
+        let _guard = self.backend.state().client.enter()?;
+
+        let mut depots = vec![0u32; INITIAL_DEPOTS];
+        loop {
+            let count = self.backend.apps_installed_depots(app_id, &mut depots) as usize;
+
+            // A full buffer may have cut the list off.
+            if count < depots.len() {
+                depots.truncate(count);
+                return Ok(depots.into_iter().map(DepotId));
+            }
+            if depots.len() >= MAX_DEPOTS {
+                return Err(AppsError::Truncated {
+                    capacity: MAX_DEPOTS,
+                });
+            }
+
+            depots.resize(depots.len() * 2, 0);
         }
     }
 
-    pub fn get_app_install_dir(&self, app_id: AppId) -> Option<PathBuf> {
-        unsafe {
-            let mut name_buffer = [0; 128];
-
-            if sys::SteamAPI_ISteamApps_GetAppInstallDir(
-                self.raw,
-                app_id.0,
-                name_buffer.as_mut_ptr(),
-                128,
-            ) > 0
-            {
-                Some(PathBuf::from(
-                    CStr::from_ptr(name_buffer.as_mut_ptr())
-                        .to_string_lossy()
-                        .to_string(),
-                ))
-            } else {
-                None
+    /// Fails with [`AppsError::NotFound`] if the app isn't installed.
+    pub fn get_app_install_dir(&self, app_id: AppId) -> Result<PathBuf, AppsError> {
+        let _guard = self.backend.state().client.enter()?;
+
+        let dir = read_string(|buffer| {
+            match self.backend.apps_app_install_dir(app_id, buffer) as usize {
+                0 => None,
+                len => Some(len),
             }
-        }
+        })?;
+
+        Ok(os_string(dir)?.into())
     }
 
-    pub fn is_app_installed(&self, app_id: AppId) -> bool {
-        unsafe { sys::SteamAPI_ISteamApps_BIsAppInstalled(self.raw, app_id.0) }
+    pub fn is_app_installed(&self, app_id: AppId) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_is_app_installed(app_id))
     }
 
-    pub fn get_app_owner(&self) -> SteamId {
-        unsafe { SteamId(sys::SteamAPI_ISteamApps_GetAppOwner(self.raw)) }
+    pub fn get_app_owner(&self) -> Result<SteamId, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_app_owner())
     }
 
-    pub fn get_launch_query_param(&self, key: impl AsRef<CStr>) -> String {
-        unsafe {
-            let key = key.as_ref();
+    pub fn get_launch_query_param(&self, key: impl AsRef<CStr>) -> Result<String, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
 
-            let result = sys::SteamAPI_ISteamApps_GetLaunchQueryParam(self.raw, key.as_ptr());
+        let result = self.backend.apps_launch_query_param(key.as_ref());
 
-            CStr::from_ptr(result as *mut _)
-                .to_string_lossy()
-                .to_string()
-        }
+        Ok(result.to_string_lossy().to_string())
     }
 
-    pub fn get_dlc_download_progress(&self, dlc_id: AppId) -> Option<DlcDownloadProgress> {
-        unsafe {
-            let mut downloaded = 0;
-            let mut total = 0;
-            if sys::SteamAPI_ISteamApps_GetDlcDownloadProgress(
-                self.raw,
-                dlc_id.0,
-                &mut downloaded,
-                &mut total,
-            ) {
-                Some(DlcDownloadProgress { downloaded, total })
-            } else {
-                None
-            }
-        }
+    pub fn get_dlc_download_progress(
+        &self,
+        dlc_id: AppId,
+    ) -> Result<Option<DlcDownloadProgress>, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self
+            .backend
+            .apps_dlc_download_progress(dlc_id)
+            .map(|(downloaded, total)| DlcDownloadProgress { downloaded, total }))
     }
 
-    pub fn get_app_build_id(&self) -> i32 {
-        unsafe { sys::SteamAPI_ISteamApps_GetAppBuildId(self.raw) }
+    pub fn get_app_build_id(&self) -> Result<i32, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.apps_app_build_id())
     }
 
-    pub async fn get_file_details(
-        &self,
-        path: impl AsRef<Path>,
-    ) -> Result<FileDetails, FileNotFound> {
+    pub fn get_file_details(&self, path: impl AsRef<Path>) -> CallResult<'_, FileDetails> {
         let path = path.as_ref();
-        let path = path.as_os_str().as_encoded_bytes().as_ptr();
+        let Ok(path) = CString::new(path.as_os_str().as_encoded_bytes()) else {
+            return CallResult::ready(Err(SteamError::FileNotFound.into()));
+        };
 
-        let call_id =
-            unsafe { sys::SteamAPI_ISteamApps_GetFileDetails(self.raw, path as *const _) };
+        let _guard = match self.backend.state().client.enter() {
+            Ok(guard) => guard,
+            Err(err) => return CallResult::ready(Err(err.into())),
+        };
 
         self.container
-            .register_call_result::<FileDetails>(call_id)
-            .await
+            .call_results
+            .start::<FileDetails>(|| self.backend.apps_file_details(&path))
+            .and_then(|details| Ok(details?))
     }
 
-    pub fn get_launch_command_line(&self) -> String {
-        unsafe {
-            let mut name_buffer = [0; 128];
+    pub fn get_launch_command_line(&self) -> Result<String, AppsError> {
+        let _guard = self.backend.state().client.enter()?;
 
-            sys::SteamAPI_ISteamApps_GetLaunchCommandLine(self.raw, name_buffer.as_mut_ptr(), 128);
+        let line = read_string(|buffer| {
+            Some(self.backend.apps_launch_command_line(buffer).max(0) as usize)
+        })?;
 
-            CStr::from_ptr(name_buffer.as_ptr() as *mut _)
-                .to_string_lossy()
-                .to_string()
-        }
+        utf8(line)
+    }
+
+    /// Tells Steam which DLC is being played, `None` for the base game.
+    pub fn set_dlc_context(&self, dlc_id: Option<AppId>) -> Result<bool, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self
+            .backend
+            .apps_set_dlc_context(dlc_id.unwrap_or(AppId(0))))
     }
 
-    pub fn is_timed_trial(&self) -> Option<TrialTime> {
-        unsafe {
-            let mut allowed = 0;
-            let mut played = 0;
-            if sys::SteamAPI_ISteamApps_BIsTimedTrial(self.raw, &mut allowed, &mut played) {
-                Some(TrialTime { allowed, played })
-            } else {
-                None
+    /// Fails with [`AppsError::NotFound`] if Steam has no ticket for the app.
+    pub fn get_app_ownership_ticket(&self, app_id: AppId) -> Result<AppOwnershipTicket, AppsError> {
+        let _guard = self.backend.state().client.enter()?;
+
+        let mut buffer = vec![0; INITIAL_TICKET];
+        loop {
+            let layout = self.backend.app_ticket_ownership_data(app_id, &mut buffer);
+            let size = layout.size as usize;
+
+            if size == 0 {
+                return Err(AppsError::NotFound);
+            }
+            if size <= buffer.len() {
+                buffer.truncate(size);
+                return AppOwnershipTicket::from_layout(buffer, layout).ok_or(AppsError::Malformed);
+            }
+            if buffer.len() >= MAX_BUFFER {
+                return Err(AppsError::Truncated {
+                    capacity: MAX_BUFFER,
+                });
             }
+
+            buffer.resize(size.min(MAX_BUFFER), 0);
         }
     }
+
+    pub fn is_timed_trial(&self) -> Result<Option<TrialTime>, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self
+            .backend
+            .apps_timed_trial()
+            .map(|(allowed, played)| TrialTime { allowed, played }))
+    }
+}
+
+/// Lets `fill` copy a string into growing buffers until it fits and returns its bytes.
+///
+/// `fill` returns the length Steam reported or `None` if there's no value. Steam never
+/// says whether it cut a string off, so a string filling the whole buffer is retried.
+fn read_string(mut fill: impl FnMut(&mut [u8]) -> Option<usize>) -> Result<Vec<u8>, AppsError> {
+    let mut size = INITIAL_BUFFER;
+    loop {
+        let mut buffer = vec![0; size];
+        let reported = fill(&mut buffer).ok_or(AppsError::NotFound)?;
+        let len = buffer.iter().position(|&b| b == 0).unwrap_or(size);
+
+        if len + 1 < size && reported < size {
+            buffer.truncate(len);
+            return Ok(buffer);
+        }
+        if size >= MAX_BUFFER {
+            return Err(AppsError::Truncated {
+                capacity: MAX_BUFFER,
+            });
+        }
+
+        size = (size * 2).max(reported + 1).min(MAX_BUFFER);
+    }
+}
+
+fn utf8(bytes: Vec<u8>) -> Result<String, AppsError> {
+    String::from_utf8(bytes).map_err(|_| AppsError::InvalidUtf8)
+}
+
+#[cfg(unix)]
+fn os_string(bytes: Vec<u8>) -> Result<OsString, AppsError> {
+    use std::os::unix::ffi::OsStringExt;
+
+    Ok(OsString::from_vec(bytes))
+}
+
+/// Steam hands out UTF-8 everywhere but on Unix, where paths are raw bytes.
+#[cfg(not(unix))]
+fn os_string(bytes: Vec<u8>) -> Result<OsString, AppsError> {
+    utf8(bytes).map(OsString::from)
 }
@@ -1,11 +1,16 @@
-use crate::core::{
-    callback::{CallbackType, CallbackTyped},
-    structs::AppId,
+use crate::{
+    backend::OwnershipTicketLayout,
+    core::{
+        callback::{CallbackType, CallbackTyped},
+        enums::SteamError,
+        structs::{AppId, SteamId},
+    },
 };
 
 use steamgear_sys as sys;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DlcInformation {
     pub dlc_id: AppId,
     pub dlc_name: String,
@@ -13,46 +18,66 @@ pub struct DlcInformation {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DlcDownloadProgress {
     pub downloaded: u64,
     pub total: u64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileDetails {
     pub file_size: u64,
     pub sha1: [u8; 20],
 }
 
-#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
-pub struct FileNotFound;
-
-impl std::fmt::Display for FileNotFound {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "File was not found")
-    }
-}
-
 impl CallbackTyped for FileDetails {
     const TYPE: CallbackType = CallbackType::FileDetailsResult;
 
     type Raw = sys::FileDetailsResult_t;
-    type Mapped = Result<Self, FileNotFound>;
+    type Mapped = Result<Self, SteamError>;
 
     fn from_raw(raw: Self::Raw) -> Self::Mapped {
-        if raw.m_eResult == sys::EResult_k_EResultOK {
-            Ok(Self {
-                file_size: raw.m_ulFileSize,
-                sha1: raw.m_FileSHA,
-            })
-        } else {
-            Err(FileNotFound)
-        }
+        SteamError::from_code(raw.m_eResult)?;
+
+        Ok(Self {
+            file_size: raw.m_ulFileSize,
+            sha1: raw.m_FileSHA,
+        })
     }
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrialTime {
     pub allowed: u32,
     pub played: u32,
 }
+
+/// An app ownership ticket with the fields Steam pointed out in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppOwnershipTicket {
+    pub ticket: Vec<u8>,
+    pub app_id: AppId,
+    pub steam_id: SteamId,
+    pub signature: Vec<u8>,
+}
+
+impl AppOwnershipTicket {
+    /// Reads the fields at the offsets of `layout`, `None` if one is out of bounds.
+    pub(crate) fn from_layout(ticket: Vec<u8>, layout: OwnershipTicketLayout) -> Option<Self> {
+        let field = |offset: u32, len: usize| ticket.get(offset as usize..)?.get(..len);
+
+        let app_id = u32::from_ne_bytes(field(layout.app_id, 4)?.try_into().ok()?);
+        let steam_id = u64::from_ne_bytes(field(layout.steam_id, 8)?.try_into().ok()?);
+        let signature = field(layout.signature, layout.signature_size as usize)?.to_vec();
+
+        Some(Self {
+            app_id: AppId(app_id),
+            steam_id: SteamId(steam_id),
+            signature,
+            ticket,
+        })
+    }
+}
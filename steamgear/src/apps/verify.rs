@@ -0,0 +1,377 @@
+//! Install verification, local SHA-1s compared with what Steam or a shipped manifest
+//! expects.
+//!
+//! Files are hashed on a worker thread. Expected values missing from the manifest are
+//! asked from Steam with `GetFileDetails`, so `run_callbacks` has to keep running
+//! while a check is in progress.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    future::Future,
+    io::{ErrorKind, Read},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+    time::Duration,
+};
+
+use async_channel::Sender;
+use futures_core::Stream;
+
+use crate::core::{
+    callback::{CallResultError, MULTI_DISPATCHER_CAPACITY},
+    enums::{AppsError, ManifestError},
+    structs::AppId,
+};
+
+use super::{structs::FileDetails, SteamApps};
+
+/// `GetFileDetails` calls kept in flight ahead of the file being hashed.
+const IN_FLIGHT: usize = 16;
+
+const READ_CHUNK: usize = 64 * 1024;
+
+/// How often a thread waiting on Steam checks whether the stream was dropped.
+const CANCEL_CHECK: Duration = Duration::from_millis(100);
+
+/// Files to verify, relative to the install directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Manifest {
+    entries: Vec<ManifestEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    /// `None` asks Steam.
+    pub expected: Option<FileDetails>,
+}
+
+impl Manifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A manifest that asks Steam about every file in `paths`.
+    pub fn from_paths<P: Into<PathBuf>>(paths: impl IntoIterator<Item = P>) -> Self {
+        Self {
+            entries: paths
+                .into_iter()
+                .map(|path| ManifestEntry {
+                    path: path.into(),
+                    expected: None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn push(&mut self, path: impl Into<PathBuf>, expected: Option<FileDetails>) {
+        self.entries.push(ManifestEntry {
+            path: path.into(),
+            expected,
+        });
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    /// Parses one `<sha1 hex> <size> <path>` line per file, `#` starts a comment line.
+    pub fn parse(text: &str) -> Result<Self, ManifestError> {
+        let mut manifest = Self::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let entry = parse_line(line).ok_or(ManifestError { line: index + 1 })?;
+            manifest.entries.push(entry);
+        }
+
+        Ok(manifest)
+    }
+}
+
+fn parse_line(line: &str) -> Option<ManifestEntry> {
+    let (sha1, rest) = line.split_once(char::is_whitespace)?;
+    let (size, path) = rest.trim_start().split_once(char::is_whitespace)?;
+    let path = path.trim_start();
+
+    if sha1.len() != 40 || path.is_empty() {
+        return None;
+    }
+
+    let mut digest = [0; 20];
+    for (byte, hex) in digest.iter_mut().zip(sha1.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+    }
+
+    Some(ManifestEntry {
+        path: path.into(),
+        expected: Some(FileDetails {
+            file_size: size.parse().ok()?,
+            sha1: digest,
+        }),
+    })
+}
+
+/// A file that doesn't match, or couldn't be checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileMismatch {
+    pub path: PathBuf,
+    pub problem: FileProblem,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FileProblem {
+    Missing,
+    Size {
+        expected: u64,
+        actual: u64,
+    },
+    Sha1 {
+        expected: [u8; 20],
+        actual: [u8; 20],
+    },
+    /// Steam couldn't say what the file should be, e.g. it isn't part of the app.
+    Unknown(CallResultError),
+    /// The file exists but couldn't be read.
+    Unreadable(String),
+}
+
+impl FileProblem {
+    /// Whether the file is damaged, as opposed to unchecked.
+    pub fn is_damaged(&self) -> bool {
+        matches!(self, Self::Missing | Self::Size { .. } | Self::Sha1 { .. })
+    }
+}
+
+/// A pending install verification, see [`SteamApps::verify_install`].
+#[derive(Clone, Debug)]
+pub struct IntegrityCheck {
+    apps: SteamApps,
+    app_id: AppId,
+    manifest: Manifest,
+    repair: bool,
+}
+
+impl IntegrityCheck {
+    pub(crate) fn new(apps: SteamApps, app_id: AppId, manifest: Manifest) -> Self {
+        Self {
+            apps,
+            app_id,
+            manifest,
+            repair: false,
+        }
+    }
+
+    /// Calls `mark_content_corrupt` when the check found damaged files, so Steam
+    /// repairs the install on the next launch.
+    pub fn repair(mut self, repair: bool) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    /// Starts hashing and returns the problems found, the stream ends when every file
+    /// was checked. Dropping the stream stops the check.
+    pub fn start(self) -> Result<impl Stream<Item = FileMismatch>, AppsError> {
+        let dir = self.apps.get_app_install_dir(self.app_id)?;
+        let (sender, receiver) = async_channel::bounded(MULTI_DISPATCHER_CAPACITY);
+
+        std::thread::Builder::new()
+            .name("steamgear-verify".to_string())
+            .spawn(move || self.run(&dir, &sender))
+            .map_err(|err| {
+                tracing::error!("Couldn't spawn verify thread: {}", err);
+                AppsError::Spawn
+            })?;
+
+        Ok(receiver)
+    }
+
+    fn run(self, dir: &Path, sender: &Sender<FileMismatch>) {
+        let entries = self.manifest.entries;
+        let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut lookups = VecDeque::new();
+        let mut damaged = 0;
+        let mut missing_files_only = true;
+
+        for (index, entry) in entries.iter().enumerate() {
+            // Keep the next calls in flight while this file is hashed
+            let ahead = entries
+                .iter()
+                .skip(index + lookups.len())
+                .take(IN_FLIGHT - lookups.len());
+            for ahead in ahead {
+                lookups.push_back(match ahead.expected {
+                    Some(details) => Lookup::Done(Ok(details)),
+                    None => Lookup::start(self.apps.get_file_details(&ahead.path), &mut cx),
+                });
+            }
+
+            let local = hash_file(&dir.join(&entry.path));
+            let Some(lookup) = lookups.pop_front() else {
+                break;
+            };
+
+            let Some(lookup) = lookup.wait(&mut cx, sender) else {
+                tracing::debug!("Verify of app {} dropped", self.app_id.0);
+                return;
+            };
+
+            let problem = match lookup {
+                Ok(expected) => compare(expected, local),
+                Err(err @ (CallResultError::NotInitialized(_) | CallResultError::Shutdown)) => {
+                    tracing::debug!("Stopped verifying app {}: {}", self.app_id.0, err);
+                    return;
+                }
+                Err(err) => Some(FileProblem::Unknown(err)),
+            };
+
+            let Some(problem) = problem else {
+                continue;
+            };
+
+            if problem.is_damaged() {
+                damaged += 1;
+                missing_files_only &= problem == FileProblem::Missing;
+            }
+
+            let mismatch = FileMismatch {
+                path: entry.path.clone(),
+                problem,
+            };
+            if sender.send_blocking(mismatch).is_err() {
+                tracing::debug!("Verify of app {} dropped", self.app_id.0);
+                return;
+            }
+        }
+
+        tracing::info!(
+            "Verified {} files of app {}, {} damaged",
+            entries.len(),
+            self.app_id.0,
+            damaged
+        );
+
+        if self.repair && damaged > 0 {
+            if let Err(err) = self.apps.mark_content_corrupt(missing_files_only) {
+                tracing::warn!("Couldn't mark app {} corrupt: {}", self.app_id.0, err);
+            }
+        }
+    }
+}
+
+fn compare(expected: FileDetails, local: Result<FileDetails, FileProblem>) -> Option<FileProblem> {
+    let actual = match local {
+        Ok(actual) => actual,
+        Err(problem) => return Some(problem),
+    };
+
+    if actual.file_size != expected.file_size {
+        Some(FileProblem::Size {
+            expected: expected.file_size,
+            actual: actual.file_size,
+        })
+    } else if actual.sha1 != expected.sha1 {
+        Some(FileProblem::Sha1 {
+            expected: expected.sha1,
+            actual: actual.sha1,
+        })
+    } else {
+        None
+    }
+}
+
+fn hash_file(path: &Path) -> Result<FileDetails, FileProblem> {
+    let unreadable = |err: std::io::Error| match err.kind() {
+        ErrorKind::NotFound => FileProblem::Missing,
+        _ => FileProblem::Unreadable(err.to_string()),
+    };
+
+    let mut file = File::open(path).map_err(unreadable)?;
+    let mut sha1 = sha1_smol::Sha1::new();
+    let mut buffer = vec![0; READ_CHUNK];
+    let mut file_size = 0;
+
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(unreadable(err)),
+        };
+
+        sha1.update(&buffer[..read]);
+        file_size += read as u64;
+    }
+
+    Ok(FileDetails {
+        file_size,
+        sha1: sha1.digest().bytes(),
+    })
+}
+
+/// A `GetFileDetails` call, polled once on start so the request goes out.
+enum Lookup<F: Future> {
+    Pending(Pin<Box<F>>),
+    Done(F::Output),
+}
+
+impl<F: Future> Lookup<F> {
+    fn start(future: F, cx: &mut Context) -> Self {
+        let mut future = Box::pin(future);
+
+        match future.as_mut().poll(cx) {
+            Poll::Ready(output) => Self::Done(output),
+            Poll::Pending => Self::Pending(future),
+        }
+    }
+
+    /// Parks the thread until the call completes, `None` once the stream fed by
+    /// `sender` was dropped.
+    fn wait<T>(self, cx: &mut Context, sender: &Sender<T>) -> Option<F::Output> {
+        let mut future = match self {
+            Self::Pending(future) => future,
+            Self::Done(output) => return Some(output),
+        };
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                return Some(output);
+            }
+
+            if sender.is_closed() {
+                return None;
+            }
+
+            std::thread::park_timeout(CANCEL_CHECK);
+        }
+    }
+}
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+impl SteamApps {
+    /// Checks the files of `manifest` under the install directory of `app_id`.
+    pub fn verify_install(&self, app_id: AppId, manifest: Manifest) -> IntegrityCheck {
+        IntegrityCheck::new(self.clone(), app_id, manifest)
+    }
+}
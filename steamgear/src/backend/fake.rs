@@ -0,0 +1,855 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ffi::{CStr, CString},
+    path::{Path, PathBuf},
+};
+
+use parking_lot::Mutex;
+use steamgear_sys as sys;
+
+use crate::{
+    apps::structs::{FileDetails, TrialTime},
+    core::{
+        enums::{ApiCallFailure, DenyReason, Interface, ServerMode, SteamApiInitError, SteamError},
+        structs::{AppId, DepotId, SteamId},
+    },
+    friends::structs::FriendGameInfo,
+};
+
+use super::{
+    copy_to_buffer, BackendEvent, BackendState, CallResultMsg, CallbackMsg, OwnershipTicketLayout,
+    RecordedEvent, RecordedPipe, Recording, SteamBackend,
+};
+
+const CLIENT_PIPE: sys::HSteamPipe = 1;
+const SERVER_PIPE: sys::HSteamPipe = 2;
+
+/// In-process backend that needs no Steam client.
+///
+/// Every setter takes `&self`, so a test can keep an `Arc<FakeBackend>` around and
+/// script it while a [`SteamApi`](crate::api::SteamApi) built on top of it is running.
+/// Callbacks and call results are queued and handed out by `run_callbacks`.
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    state: BackendState,
+    inner: Mutex<FakeState>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    init_error: Option<SteamApiInitError>,
+    restart_required: bool,
+    requested_interfaces: Vec<Interface>,
+    client_running: bool,
+    server_running: bool,
+
+    subscribed: bool,
+    family_sharing: bool,
+    free_weekend: bool,
+    low_violence: bool,
+    cybercafe: bool,
+    vac_banned: bool,
+    game_language: String,
+    available_languages: Vec<String>,
+    owned_apps: HashSet<AppId>,
+    installed_apps: HashMap<AppId, PathBuf>,
+    installed_depots: HashMap<AppId, Vec<DepotId>>,
+    purchase_times: HashMap<AppId, u32>,
+    dlcs: Vec<FakeDlc>,
+    hold_dlc_installs: bool,
+    beta_name: Option<String>,
+    content_corrupt: Option<bool>,
+    app_owner: SteamId,
+    launch_query_params: HashMap<String, String>,
+    launch_command_line: String,
+    build_id: i32,
+    timed_trial: Option<TrialTime>,
+    file_details: HashMap<PathBuf, FileDetails>,
+    friend_games: HashMap<SteamId, FriendGameInfo>,
+    purchase_keys: HashMap<AppId, Result<String, SteamError>>,
+    dlc_context: Option<AppId>,
+    ownership_tickets: HashMap<AppId, (SteamId, Vec<u8>)>,
+
+    next_call: sys::SteamAPICall_t,
+    call_results: FakeCallResults,
+    client_events: VecDeque<BackendEvent>,
+    server_events: VecDeque<BackendEvent>,
+
+    client_frame: u64,
+    server_frame: u64,
+    replay_events: VecDeque<RecordedEvent>,
+    replay_calls: VecDeque<sys::SteamAPICall_t>,
+    replayed_calls: HashSet<sys::SteamAPICall_t>,
+}
+
+/// How the fake completes the async calls it starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FakeCallResults {
+    #[default]
+    Complete,
+    Fail(ApiCallFailure),
+    /// Never complete, to exercise timeouts and cancellation.
+    Hang,
+}
+
+#[derive(Debug)]
+struct FakeDlc {
+    id: AppId,
+    name: String,
+    available: bool,
+    installed: bool,
+    progress: Option<(u64, u64)>,
+}
+
+impl FakeBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the next `client_init`/`server_init` fail with `error`.
+    pub fn set_init_error(&self, error: Option<SteamApiInitError>) {
+        self.inner.lock().init_error = error;
+    }
+
+    /// Makes `restart_app_if_necessary` report that Steam relaunches the game.
+    pub fn set_restart_required(&self, restart: bool) {
+        self.inner.lock().restart_required = restart;
+    }
+
+    /// Interfaces passed to the last successful init.
+    pub fn requested_interfaces(&self) -> Vec<Interface> {
+        self.inner.lock().requested_interfaces.clone()
+    }
+
+    pub fn is_client_running(&self) -> bool {
+        self.inner.lock().client_running
+    }
+
+    pub fn is_server_running(&self) -> bool {
+        self.inner.lock().server_running
+    }
+
+    /// Number of `run_frame` calls on the client pipe.
+    pub fn client_frames(&self) -> u64 {
+        self.inner.lock().client_frame
+    }
+
+    pub fn set_subscribed(&self, value: bool) {
+        self.inner.lock().subscribed = value;
+    }
+
+    pub fn set_subscribed_from_family_sharing(&self, value: bool) {
+        self.inner.lock().family_sharing = value;
+    }
+
+    pub fn set_subscribed_from_free_weekend(&self, value: bool) {
+        self.inner.lock().free_weekend = value;
+    }
+
+    pub fn set_low_violence(&self, value: bool) {
+        self.inner.lock().low_violence = value;
+    }
+
+    pub fn set_cybercafe(&self, value: bool) {
+        self.inner.lock().cybercafe = value;
+    }
+
+    pub fn set_vac_banned(&self, value: bool) {
+        self.inner.lock().vac_banned = value;
+    }
+
+    pub fn set_game_language(&self, language: &str) {
+        self.inner.lock().game_language = language.to_string();
+    }
+
+    pub fn set_available_game_languages(&self, languages: &[&str]) {
+        self.inner.lock().available_languages =
+            languages.iter().map(|lang| lang.to_string()).collect();
+    }
+
+    pub fn add_owned_app(&self, app_id: AppId) {
+        self.inner.lock().owned_apps.insert(app_id);
+    }
+
+    pub fn set_purchase_time(&self, app_id: AppId, unix_time: u32) {
+        self.inner.lock().purchase_times.insert(app_id, unix_time);
+    }
+
+    pub fn install_app(&self, app_id: AppId, dir: impl Into<PathBuf>) {
+        self.inner.lock().installed_apps.insert(app_id, dir.into());
+    }
+
+    pub fn set_installed_depots(&self, app_id: AppId, depots: Vec<DepotId>) {
+        self.inner.lock().installed_depots.insert(app_id, depots);
+    }
+
+    pub fn add_dlc(&self, dlc_id: AppId, name: &str, available: bool) {
+        self.inner.lock().dlcs.push(FakeDlc {
+            id: dlc_id,
+            name: name.to_string(),
+            available,
+            installed: false,
+            progress: None,
+        });
+    }
+
+    pub fn set_dlc_installed(&self, dlc_id: AppId, installed: bool) {
+        if let Some(dlc) = self.inner.lock().dlc_mut(dlc_id) {
+            dlc.installed = installed;
+        }
+    }
+
+    pub fn set_dlc_download_progress(&self, dlc_id: AppId, progress: Option<(u64, u64)>) {
+        if let Some(dlc) = self.inner.lock().dlc_mut(dlc_id) {
+            dlc.progress = progress;
+        }
+    }
+
+    /// Leaves DLC installs downloading until [`FakeBackend::finish_dlc_install`].
+    pub fn hold_dlc_installs(&self, hold: bool) {
+        self.inner.lock().hold_dlc_installs = hold;
+    }
+
+    /// Completes a held DLC install.
+    pub fn finish_dlc_install(&self, dlc_id: AppId) {
+        self.inner.lock().install_dlc(dlc_id);
+    }
+
+    pub fn set_beta_name(&self, name: Option<&str>) {
+        self.inner.lock().beta_name = name.map(str::to_string);
+    }
+
+    /// Returns the `missing_files_only` flag of the last `mark_content_corrupt` call.
+    pub fn content_marked_corrupt(&self) -> Option<bool> {
+        self.inner.lock().content_corrupt
+    }
+
+    pub fn set_app_owner(&self, owner: SteamId) {
+        self.inner.lock().app_owner = owner;
+    }
+
+    pub fn set_launch_query_param(&self, key: &str, value: &str) {
+        self.inner
+            .lock()
+            .launch_query_params
+            .insert(key.to_string(), value.to_string());
+    }
+
+    pub fn set_launch_command_line(&self, command_line: &str) {
+        self.inner.lock().launch_command_line = command_line.to_string();
+    }
+
+    pub fn set_app_build_id(&self, build_id: i32) {
+        self.inner.lock().build_id = build_id;
+    }
+
+    pub fn set_timed_trial(&self, trial: Option<TrialTime>) {
+        self.inner.lock().timed_trial = trial;
+    }
+
+    /// Scripts the answer of `get_file_details` for `path`, unknown paths fail with `FileNotFound`.
+    pub fn set_file_details(&self, path: impl AsRef<Path>, details: FileDetails) {
+        self.inner
+            .lock()
+            .file_details
+            .insert(path.as_ref().to_path_buf(), details);
+    }
+
+    /// Scripts the answer of proof-of-purchase key requests for `app_id`, unknown apps fail.
+    pub fn set_proof_of_purchase_key(&self, app_id: AppId, key: Result<&str, SteamError>) {
+        self.inner
+            .lock()
+            .purchase_keys
+            .insert(app_id, key.map(str::to_string));
+    }
+
+    /// Returns the app of the last `set_dlc_context` call, 0 for the base game.
+    pub fn dlc_context(&self) -> Option<AppId> {
+        self.inner.lock().dlc_context
+    }
+
+    /// Issues ownership tickets for `app_id`, laid out as `app_id`, `steam_id`, `signature`.
+    pub fn set_app_ownership_ticket(&self, app_id: AppId, steam_id: SteamId, signature: &[u8]) {
+        self.inner
+            .lock()
+            .ownership_tickets
+            .insert(app_id, (steam_id, signature.to_vec()));
+    }
+
+    /// Marks `friend` as playing, `None` makes them leave the game.
+    pub fn set_friend_game_played(&self, friend: SteamId, info: Option<FriendGameInfo>) {
+        let mut inner = self.inner.lock();
+        match info {
+            Some(info) => inner.friend_games.insert(friend, info),
+            None => inner.friend_games.remove(&friend),
+        };
+    }
+
+    /// Queues a raw client callback.
+    pub fn push_callback(&self, id: u32, data: Vec<u8>) {
+        self.inner
+            .lock()
+            .client_events
+            .push_back(BackendEvent::Callback(CallbackMsg { id, data }));
+    }
+
+    /// Queues a raw server callback.
+    pub fn push_server_callback(&self, id: u32, data: Vec<u8>) {
+        self.inner
+            .lock()
+            .server_events
+            .push_back(BackendEvent::Callback(CallbackMsg { id, data }));
+    }
+
+    /// Queues a raw call result for the client.
+    pub fn push_call_result(
+        &self,
+        call: sys::SteamAPICall_t,
+        id: u32,
+        data: Result<Vec<u8>, ApiCallFailure>,
+    ) {
+        self.inner
+            .lock()
+            .client_events
+            .push_back(BackendEvent::CallResult(CallResultMsg { call, id, data }));
+    }
+
+    pub fn set_call_results(&self, mode: FakeCallResults) {
+        self.inner.lock().call_results = mode;
+    }
+
+    /// Reserves a call handle, to be completed later with [`FakeBackend::push_call_result`].
+    pub fn next_call_handle(&self) -> sys::SteamAPICall_t {
+        self.inner.lock().next_call()
+    }
+
+    pub fn push_steam_shutdown(&self) {
+        let raw = sys::SteamShutdown_t { _address: 0 };
+        self.push_callback(sys::SteamShutdown_t_k_iCallback as u32, raw_bytes(&raw));
+    }
+
+    pub fn push_dlc_installed(&self, dlc_id: AppId) {
+        let raw = sys::DlcInstalled_t { m_nAppID: dlc_id.0 };
+        self.push_callback(sys::DlcInstalled_t_k_iCallback as u32, raw_bytes(&raw));
+    }
+
+    pub fn push_new_url_launch_params(&self) {
+        let raw = sys::NewUrlLaunchParameters_t { _address: 0 };
+        self.push_callback(
+            sys::NewUrlLaunchParameters_t_k_iCallback as u32,
+            raw_bytes(&raw),
+        );
+    }
+
+    /// A fake that plays `path` back, see [`FakeBackend::load_recording`].
+    pub fn replay(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let backend = Self::new();
+        backend.load_recording(&Recording::load(path)?);
+
+        Ok(backend)
+    }
+
+    /// Queues the events of `recording`, each pipe hands out the events of a recorded
+    /// frame on its next `run_callbacks` calls, in the recorded order.
+    ///
+    /// Async calls started afterwards get the recorded call handles in order, so the
+    /// recorded call results complete them instead of the fake.
+    pub fn load_recording(&self, recording: &Recording) {
+        let mut inner = self.inner.lock();
+        let (client_frame, server_frame) = (inner.client_frame, inner.server_frame);
+
+        for recorded in &recording.events {
+            if let BackendEvent::CallResult(result) = &recorded.event {
+                inner.replay_calls.push_back(result.call);
+            }
+
+            let frame = match recorded.pipe {
+                RecordedPipe::Client => client_frame + recorded.frame,
+                RecordedPipe::Server => server_frame + recorded.frame,
+            };
+
+            inner.replay_events.push_back(RecordedEvent {
+                frame,
+                ..recorded.clone()
+            });
+        }
+    }
+
+    pub fn push_servers_connected(&self) {
+        let raw = sys::SteamServersConnected_t { _address: 0 };
+        self.push_server_callback(
+            sys::SteamServersConnected_t_k_iCallback as u32,
+            raw_bytes(&raw),
+        );
+    }
+
+    pub fn push_server_connect_failure(&self, error: SteamError, still_retrying: bool) {
+        let mut raw: sys::SteamServerConnectFailure_t = unsafe { std::mem::zeroed() };
+        raw.m_eResult = error.code();
+        raw.m_bStillRetrying = still_retrying;
+        self.push_server_callback(
+            sys::SteamServerConnectFailure_t_k_iCallback as u32,
+            raw_bytes(&raw),
+        );
+    }
+
+    pub fn push_client_deny(&self, steam_id: SteamId, reason: DenyReason, text: &str) {
+        let mut optional_text = [0u8; 128];
+        copy_to_buffer(text.as_bytes(), &mut optional_text);
+
+        let mut raw: sys::GSClientDeny_t = unsafe { std::mem::zeroed() };
+        raw.m_SteamID = steam_id.to_raw();
+        raw.m_eDenyReason = reason as _;
+        raw.m_rgchOptionalText = optional_text.map(|c| c as _);
+        self.push_server_callback(sys::GSClientDeny_t_k_iCallback as u32, raw_bytes(&raw));
+    }
+}
+
+impl FakeState {
+    fn dlc_mut(&mut self, dlc_id: AppId) -> Option<&mut FakeDlc> {
+        self.dlcs.iter_mut().find(|dlc| dlc.id == dlc_id)
+    }
+
+    fn install_dlc(&mut self, dlc_id: AppId) {
+        if let Some(dlc) = self.dlc_mut(dlc_id) {
+            dlc.installed = true;
+            dlc.progress = None;
+
+            let raw = sys::DlcInstalled_t { m_nAppID: dlc_id.0 };
+            self.client_events
+                .push_back(BackendEvent::Callback(CallbackMsg {
+                    id: sys::DlcInstalled_t_k_iCallback as u32,
+                    data: raw_bytes(&raw),
+                }));
+        }
+    }
+
+    fn push_proof_of_purchase_key(&mut self, app_id: AppId) {
+        let mut raw: sys::AppProofOfPurchaseKeyResponse_t = unsafe { std::mem::zeroed() };
+        raw.m_nAppID = app_id.0;
+
+        match self.purchase_keys.get(&app_id) {
+            Some(Ok(key)) => {
+                raw.m_eResult = sys::EResult_k_EResultOK;
+                raw.m_cchKeyLength = key.len().min(raw.m_rgchKey.len() - 1) as u32;
+                for (dst, src) in raw.m_rgchKey.iter_mut().zip(key.bytes()) {
+                    *dst = src as _;
+                }
+            }
+            Some(Err(err)) => raw.m_eResult = err.code(),
+            None => raw.m_eResult = sys::EResult_k_EResultFail,
+        }
+
+        self.client_events
+            .push_back(BackendEvent::Callback(CallbackMsg {
+                id: sys::AppProofOfPurchaseKeyResponse_t_k_iCallback as u32,
+                data: raw_bytes(&raw),
+            }));
+    }
+
+    fn next_call(&mut self) -> sys::SteamAPICall_t {
+        if let Some(call) = self.replay_calls.pop_front() {
+            self.replayed_calls.insert(call);
+            return call;
+        }
+
+        self.next_call += 1;
+        self.next_call
+    }
+
+    fn complete_call(&mut self, call: sys::SteamAPICall_t, id: u32, data: Vec<u8>) {
+        if self.replayed_calls.contains(&call) {
+            // The recording completes it
+            return;
+        }
+
+        let data = match self.call_results {
+            FakeCallResults::Complete => Ok(data),
+            FakeCallResults::Fail(failure) => Err(failure),
+            FakeCallResults::Hang => return,
+        };
+
+        self.client_events
+            .push_back(BackendEvent::CallResult(CallResultMsg { call, id, data }));
+    }
+}
+
+impl SteamBackend for FakeBackend {
+    fn state(&self) -> &BackendState {
+        &self.state
+    }
+
+    fn client_init(
+        &self,
+        _app_id: Option<AppId>,
+        interfaces: &[Interface],
+    ) -> Result<(), SteamApiInitError> {
+        let mut inner = self.inner.lock();
+        if let Some(error) = inner.init_error.clone() {
+            return Err(error);
+        }
+
+        inner.client_running = true;
+        inner.requested_interfaces = interfaces.to_vec();
+        Ok(())
+    }
+
+    fn client_shutdown(&self) {
+        self.inner.lock().client_running = false;
+    }
+
+    fn client_pipe(&self) -> sys::HSteamPipe {
+        CLIENT_PIPE
+    }
+
+    fn server_init(
+        &self,
+        _app_id: Option<AppId>,
+        _addr: std::net::SocketAddrV4,
+        _query_port: u16,
+        _mode: ServerMode,
+        _version: &CStr,
+        interfaces: &[Interface],
+    ) -> Result<(), SteamApiInitError> {
+        let mut inner = self.inner.lock();
+        if let Some(error) = inner.init_error.clone() {
+            return Err(error);
+        }
+
+        inner.server_running = true;
+        inner.requested_interfaces = interfaces.to_vec();
+        Ok(())
+    }
+
+    fn server_shutdown(&self) {
+        self.inner.lock().server_running = false;
+    }
+
+    fn server_pipe(&self) -> sys::HSteamPipe {
+        SERVER_PIPE
+    }
+
+    fn restart_app_if_necessary(&self, _app_id: AppId) -> bool {
+        self.inner.lock().restart_required
+    }
+
+    fn release_current_thread_memory(&self) {}
+
+    fn run_frame(&self, pipe: sys::HSteamPipe) {
+        let mut inner = self.inner.lock();
+        let inner = &mut *inner;
+
+        let (frame, replay_pipe, events) = match pipe {
+            CLIENT_PIPE => {
+                inner.client_frame += 1;
+                (
+                    inner.client_frame,
+                    RecordedPipe::Client,
+                    &mut inner.client_events,
+                )
+            }
+            SERVER_PIPE => {
+                inner.server_frame += 1;
+                (
+                    inner.server_frame,
+                    RecordedPipe::Server,
+                    &mut inner.server_events,
+                )
+            }
+            _ => return,
+        };
+
+        inner.replay_events.retain(|recorded| {
+            if recorded.pipe != replay_pipe || recorded.frame > frame {
+                return true;
+            }
+
+            events.push_back(recorded.event.clone());
+            false
+        });
+    }
+
+    fn next_event(&self, pipe: sys::HSteamPipe) -> Option<BackendEvent> {
+        let mut inner = self.inner.lock();
+
+        match pipe {
+            CLIENT_PIPE => inner.client_events.pop_front(),
+            SERVER_PIPE => inner.server_events.pop_front(),
+            _ => None,
+        }
+    }
+
+    fn apps_is_subscribed(&self) -> bool {
+        self.inner.lock().subscribed
+    }
+
+    fn apps_is_subscribed_from_family_sharing(&self) -> bool {
+        self.inner.lock().family_sharing
+    }
+
+    fn apps_is_subscribed_from_free_weekend(&self) -> bool {
+        self.inner.lock().free_weekend
+    }
+
+    fn apps_is_low_violence(&self) -> bool {
+        self.inner.lock().low_violence
+    }
+
+    fn apps_is_cybercafe(&self) -> bool {
+        self.inner.lock().cybercafe
+    }
+
+    fn apps_is_vac_banned(&self) -> bool {
+        self.inner.lock().vac_banned
+    }
+
+    fn apps_current_game_language(&self) -> CString {
+        CString::new(self.inner.lock().game_language.clone()).unwrap_or_default()
+    }
+
+    fn apps_available_game_languages(&self) -> CString {
+        CString::new(self.inner.lock().available_languages.join(",")).unwrap_or_default()
+    }
+
+    fn apps_is_subscribed_app(&self, app_id: AppId) -> bool {
+        self.inner.lock().owned_apps.contains(&app_id)
+    }
+
+    fn apps_is_dlc_installed(&self, dlc_id: AppId) -> bool {
+        self.inner
+            .lock()
+            .dlc_mut(dlc_id)
+            .is_some_and(|dlc| dlc.installed)
+    }
+
+    fn apps_earliest_purchase_unix_time(&self, app_id: AppId) -> u32 {
+        self.inner
+            .lock()
+            .purchase_times
+            .get(&app_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn apps_dlc_count(&self) -> i32 {
+        self.inner.lock().dlcs.len() as i32
+    }
+
+    fn apps_dlc_data_by_index(&self, index: i32, name: &mut [u8]) -> Option<(AppId, bool)> {
+        let inner = self.inner.lock();
+        let dlc = inner.dlcs.get(usize::try_from(index).ok()?)?;
+
+        copy_to_buffer(dlc.name.as_bytes(), name);
+
+        Some((dlc.id, dlc.available))
+    }
+
+    fn apps_install_dlc(&self, dlc_id: AppId) {
+        let mut inner = self.inner.lock();
+
+        if !inner.hold_dlc_installs {
+            inner.install_dlc(dlc_id);
+        }
+    }
+
+    fn apps_uninstall_dlc(&self, dlc_id: AppId) {
+        if let Some(dlc) = self.inner.lock().dlc_mut(dlc_id) {
+            dlc.installed = false;
+        }
+    }
+
+    fn apps_current_beta_name(&self, name: &mut [u8]) -> bool {
+        match &self.inner.lock().beta_name {
+            Some(beta) => {
+                copy_to_buffer(beta.as_bytes(), name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn apps_mark_content_corrupt(&self, missing_files_only: bool) -> bool {
+        self.inner.lock().content_corrupt = Some(missing_files_only);
+        true
+    }
+
+    fn apps_installed_depots(&self, app_id: AppId, depots: &mut [u32]) -> u32 {
+        let inner = self.inner.lock();
+        let Some(installed) = inner.installed_depots.get(&app_id) else {
+            return 0;
+        };
+
+        let count = installed.len().min(depots.len());
+        for (dst, src) in depots.iter_mut().zip(installed.iter()) {
+            *dst = src.0;
+        }
+
+        count as u32
+    }
+
+    fn apps_app_install_dir(&self, app_id: AppId, folder: &mut [u8]) -> u32 {
+        match self.inner.lock().installed_apps.get(&app_id) {
+            Some(dir) => copy_to_buffer(dir.as_os_str().as_encoded_bytes(), folder) as u32,
+            None => 0,
+        }
+    }
+
+    fn apps_is_app_installed(&self, app_id: AppId) -> bool {
+        self.inner.lock().installed_apps.contains_key(&app_id)
+    }
+
+    fn apps_app_owner(&self) -> SteamId {
+        self.inner.lock().app_owner
+    }
+
+    fn apps_launch_query_param(&self, key: &CStr) -> CString {
+        let inner = self.inner.lock();
+        let value = inner
+            .launch_query_params
+            .get(key.to_string_lossy().as_ref())
+            .cloned()
+            .unwrap_or_default();
+
+        CString::new(value).unwrap_or_default()
+    }
+
+    fn apps_dlc_download_progress(&self, dlc_id: AppId) -> Option<(u64, u64)> {
+        self.inner.lock().dlc_mut(dlc_id)?.progress
+    }
+
+    fn apps_app_build_id(&self) -> i32 {
+        self.inner.lock().build_id
+    }
+
+    fn apps_file_details(&self, file_name: &CStr) -> sys::SteamAPICall_t {
+        let mut inner = self.inner.lock();
+        let call = inner.next_call();
+
+        let path = PathBuf::from(file_name.to_string_lossy().as_ref());
+        let mut raw: sys::FileDetailsResult_t = unsafe { std::mem::zeroed() };
+
+        match inner.file_details.get(&path) {
+            Some(details) => {
+                raw.m_eResult = sys::EResult_k_EResultOK;
+                raw.m_ulFileSize = details.file_size;
+                raw.m_FileSHA = details.sha1;
+            }
+            None => {
+                raw.m_eResult = sys::EResult_k_EResultFileNotFound;
+            }
+        }
+
+        inner.complete_call(
+            call,
+            sys::FileDetailsResult_t_k_iCallback as u32,
+            raw_bytes(&raw),
+        );
+
+        call
+    }
+
+    fn apps_launch_command_line(&self, command_line: &mut [u8]) -> i32 {
+        let inner = self.inner.lock();
+        copy_to_buffer(inner.launch_command_line.as_bytes(), command_line) as i32
+    }
+
+    fn apps_timed_trial(&self) -> Option<(u32, u32)> {
+        self.inner
+            .lock()
+            .timed_trial
+            .map(|trial| (trial.allowed, trial.played))
+    }
+
+    fn apps_request_proof_of_purchase_key(&self, app_id: AppId) {
+        self.inner.lock().push_proof_of_purchase_key(app_id);
+    }
+
+    fn apps_request_all_proof_of_purchase_keys(&self) {
+        let mut inner = self.inner.lock();
+
+        let mut apps = inner.purchase_keys.keys().copied().collect::<Vec<_>>();
+        apps.sort_by_key(|app_id| app_id.0);
+        for app_id in apps {
+            inner.push_proof_of_purchase_key(app_id);
+        }
+    }
+
+    fn apps_set_dlc_context(&self, app_id: AppId) -> bool {
+        self.inner.lock().dlc_context = Some(app_id);
+        true
+    }
+
+    fn app_ticket_ownership_data(&self, app_id: AppId, buffer: &mut [u8]) -> OwnershipTicketLayout {
+        let inner = self.inner.lock();
+        let Some((steam_id, signature)) = inner.ownership_tickets.get(&app_id) else {
+            return OwnershipTicketLayout::default();
+        };
+
+        let mut ticket = app_id.0.to_ne_bytes().to_vec();
+        ticket.extend_from_slice(&steam_id.0.to_ne_bytes());
+        ticket.extend_from_slice(signature);
+
+        if ticket.len() <= buffer.len() {
+            buffer[..ticket.len()].copy_from_slice(&ticket);
+        }
+
+        OwnershipTicketLayout {
+            size: ticket.len() as u32,
+            app_id: 0,
+            steam_id: 4,
+            signature: 12,
+            signature_size: signature.len() as u32,
+        }
+    }
+
+    fn friends_game_played(&self, friend: SteamId) -> Option<FriendGameInfo> {
+        self.inner.lock().friend_games.get(&friend).copied()
+    }
+
+    fn utils_set_warning_message_hook(&self, _enabled: bool) {}
+
+    fn utils_is_api_call_completed(&self, call: sys::SteamAPICall_t) -> Option<bool> {
+        let inner = self.inner.lock();
+        // Like Steam, handles that were never handed out fail
+        let issued = (1..=inner.next_call).contains(&call) || inner.replayed_calls.contains(&call);
+        if !issued {
+            return None;
+        }
+
+        let pending = inner
+            .client_events
+            .iter()
+            .chain(inner.replay_events.iter().map(|recorded| &recorded.event))
+            .any(|event| matches!(event, BackendEvent::CallResult(result) if result.call == call));
+
+        Some(!pending)
+    }
+
+    fn utils_api_call_result(
+        &self,
+        call: sys::SteamAPICall_t,
+        callback_id: u32,
+        size: usize,
+    ) -> Option<Vec<u8>> {
+        let mut inner = self.inner.lock();
+        let position = inner.client_events.iter().position(
+            |event| matches!(event, BackendEvent::CallResult(result) if result.call == call),
+        )?;
+
+        match inner.client_events.remove(position) {
+            Some(BackendEvent::CallResult(result)) if result.id == callback_id => {
+                result.data.ok().filter(|data| data.len() == size)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the in-memory representation of a raw Steam struct.
+///
+/// Every byte is read, padding included. Build structs with padding from
+/// `mem::zeroed` and assign their fields rather than from a struct literal.
+pub(crate) fn raw_bytes<T: Copy>(raw: &T) -> Vec<u8> {
+    let ptr = raw as *const T as *const u8;
+    unsafe { std::slice::from_raw_parts(ptr, std::mem::size_of::<T>()) }.to_vec()
+}
@@ -0,0 +1,158 @@
+//! The flat API functions the Steamworks backend calls.
+//!
+//! They are linked at build time, or with the `runtime-link` feature resolved from
+//! `libsteam_api` when the client or server is initialized.
+
+#![allow(non_snake_case)]
+
+#[cfg(feature = "runtime-link")]
+use std::{
+    ffi::{c_char, c_int, c_void},
+    path::Path,
+    sync::OnceLock,
+};
+
+#[cfg(feature = "runtime-link")]
+use steamgear_sys::*;
+
+#[cfg(feature = "runtime-link")]
+use crate::core::enums::SteamApiInitError;
+
+macro_rules! flat_api {
+    ($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        #[cfg(not(feature = "runtime-link"))]
+        pub(crate) use steamgear_sys::{$($name),*};
+
+        #[cfg(feature = "runtime-link")]
+        struct FlatApi {
+            $($name: unsafe extern "C" fn($($ty),*) $(-> $ret)?,)*
+            _library: libloading::Library,
+        }
+
+        #[cfg(feature = "runtime-link")]
+        impl FlatApi {
+            unsafe fn resolve(library: libloading::Library) -> Result<Self, libloading::Error> {
+                Ok(Self {
+                    $($name: *library.get(concat!(stringify!($name), "\0").as_bytes())?,)*
+                    _library: library,
+                })
+            }
+        }
+
+        $(
+            #[cfg(feature = "runtime-link")]
+            pub(crate) unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                (api().$name)($($arg),*)
+            }
+        )*
+    };
+}
+
+flat_api! {
+    fn SteamAPI_GetHSteamPipe() -> HSteamPipe;
+    fn SteamAPI_GetHSteamUser() -> HSteamUser;
+    fn SteamAPI_ISteamApps_BGetDLCDataByIndex(self_: *mut ISteamApps, iDLC: c_int, pAppID: *mut AppId_t, pbAvailable: *mut bool, pchName: *mut c_char, cchNameBufferSize: c_int) -> bool;
+    fn SteamAPI_ISteamApps_BIsAppInstalled(self_: *mut ISteamApps, appID: AppId_t) -> bool;
+    fn SteamAPI_ISteamApps_BIsCybercafe(self_: *mut ISteamApps) -> bool;
+    fn SteamAPI_ISteamApps_BIsDlcInstalled(self_: *mut ISteamApps, appID: AppId_t) -> bool;
+    fn SteamAPI_ISteamApps_BIsLowViolence(self_: *mut ISteamApps) -> bool;
+    fn SteamAPI_ISteamApps_BIsSubscribed(self_: *mut ISteamApps) -> bool;
+    fn SteamAPI_ISteamApps_BIsSubscribedApp(self_: *mut ISteamApps, appID: AppId_t) -> bool;
+    fn SteamAPI_ISteamApps_BIsSubscribedFromFamilySharing(self_: *mut ISteamApps) -> bool;
+    fn SteamAPI_ISteamApps_BIsSubscribedFromFreeWeekend(self_: *mut ISteamApps) -> bool;
+    fn SteamAPI_ISteamApps_BIsTimedTrial(self_: *mut ISteamApps, punSecondsAllowed: *mut uint32, punSecondsPlayed: *mut uint32) -> bool;
+    fn SteamAPI_ISteamApps_BIsVACBanned(self_: *mut ISteamApps) -> bool;
+    fn SteamAPI_ISteamApps_GetAppBuildId(self_: *mut ISteamApps) -> c_int;
+    fn SteamAPI_ISteamApps_GetAppInstallDir(self_: *mut ISteamApps, appID: AppId_t, pchFolder: *mut c_char, cchFolderBufferSize: uint32) -> uint32;
+    fn SteamAPI_ISteamApps_GetAppOwner(self_: *mut ISteamApps) -> uint64_steamid;
+    fn SteamAPI_ISteamApps_GetAvailableGameLanguages(self_: *mut ISteamApps) -> *const c_char;
+    fn SteamAPI_ISteamApps_GetCurrentBetaName(self_: *mut ISteamApps, pchName: *mut c_char, cchNameBufferSize: c_int) -> bool;
+    fn SteamAPI_ISteamApps_GetCurrentGameLanguage(self_: *mut ISteamApps) -> *const c_char;
+    fn SteamAPI_ISteamApps_GetDLCCount(self_: *mut ISteamApps) -> c_int;
+    fn SteamAPI_ISteamApps_GetDlcDownloadProgress(self_: *mut ISteamApps, nAppID: AppId_t, punBytesDownloaded: *mut uint64, punBytesTotal: *mut uint64) -> bool;
+    fn SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(self_: *mut ISteamApps, nAppID: AppId_t) -> uint32;
+    fn SteamAPI_ISteamApps_GetFileDetails(self_: *mut ISteamApps, pszFileName: *const c_char) -> SteamAPICall_t;
+    fn SteamAPI_ISteamApps_GetInstalledDepots(self_: *mut ISteamApps, appID: AppId_t, pvecDepots: *mut DepotId_t, cMaxDepots: uint32) -> uint32;
+    fn SteamAPI_ISteamApps_GetLaunchCommandLine(self_: *mut ISteamApps, pszCommandLine: *mut c_char, cubCommandLine: c_int) -> c_int;
+    fn SteamAPI_ISteamApps_GetLaunchQueryParam(self_: *mut ISteamApps, pchKey: *const c_char) -> *const c_char;
+    fn SteamAPI_ISteamApps_InstallDLC(self_: *mut ISteamApps, nAppID: AppId_t);
+    fn SteamAPI_ISteamApps_MarkContentCorrupt(self_: *mut ISteamApps, bMissingFilesOnly: bool) -> bool;
+    fn SteamAPI_ISteamApps_RequestAllProofOfPurchaseKeys(self_: *mut ISteamApps);
+    fn SteamAPI_ISteamApps_RequestAppProofOfPurchaseKey(self_: *mut ISteamApps, nAppID: AppId_t);
+    fn SteamAPI_ISteamApps_SetDlcContext(self_: *mut ISteamApps, nAppID: AppId_t) -> bool;
+    fn SteamAPI_ISteamApps_UninstallDLC(self_: *mut ISteamApps, nAppID: AppId_t);
+    fn SteamAPI_ISteamFriends_GetFriendGamePlayed(self_: *mut ISteamFriends, steamIDFriend: uint64_steamid, pFriendGameInfo: *mut FriendGameInfo_t) -> bool;
+    fn SteamAPI_ISteamUtils_GetAPICallFailureReason(self_: *mut ISteamUtils, hSteamAPICall: SteamAPICall_t) -> ESteamAPICallFailure;
+    fn SteamAPI_ISteamUtils_GetAPICallResult(self_: *mut ISteamUtils, hSteamAPICall: SteamAPICall_t, pCallback: *mut c_void, cubCallback: c_int, iCallbackExpected: c_int, pbFailed: *mut bool) -> bool;
+    fn SteamAPI_ISteamUtils_IsAPICallCompleted(self_: *mut ISteamUtils, hSteamAPICall: SteamAPICall_t, pbFailed: *mut bool) -> bool;
+    fn SteamAPI_ISteamUtils_SetWarningMessageHook(self_: *mut ISteamUtils, pFunction: SteamAPIWarningMessageHook_t);
+    fn SteamAPI_ManualDispatch_FreeLastCallback(hSteamPipe: HSteamPipe);
+    fn SteamAPI_ManualDispatch_GetAPICallResult(hSteamPipe: HSteamPipe, hSteamAPICall: SteamAPICall_t, pCallback: *mut c_void, cubCallback: c_int, iCallbackExpected: c_int, pbFailed: *mut bool) -> bool;
+    fn SteamAPI_ManualDispatch_GetNextCallback(hSteamPipe: HSteamPipe, pCallbackMsg: *mut CallbackMsg_t) -> bool;
+    fn SteamAPI_ManualDispatch_Init();
+    fn SteamAPI_ManualDispatch_RunFrame(hSteamPipe: HSteamPipe);
+    fn SteamAPI_ReleaseCurrentThreadMemory();
+    fn SteamAPI_RestartAppIfNecessary(unOwnAppID: uint32) -> bool;
+    fn SteamAPI_Shutdown();
+    fn SteamAPI_SteamApps_v008() -> *mut ISteamApps;
+    fn SteamAPI_SteamFriends_v017() -> *mut ISteamFriends;
+    fn SteamAPI_SteamGameServerUtils_v010() -> *mut ISteamUtils;
+    fn SteamAPI_SteamUtils_v010() -> *mut ISteamUtils;
+    fn SteamGameServer_GetHSteamPipe() -> HSteamPipe;
+    fn SteamGameServer_Shutdown();
+    fn SteamInternal_FindOrCreateUserInterface(hSteamUser: HSteamUser, pszVersion: *const c_char) -> *mut c_void;
+    fn SteamInternal_GameServer_Init_V2(unIP: uint32, usGamePort: uint16, usQueryPort: uint16, eServerMode: EServerMode, pchVersionString: *const c_char, pszInternalCheckInterfaceVersions: *const c_char, pOutErrMsg: *mut SteamErrMsg) -> ESteamAPIInitResult;
+    fn SteamInternal_SteamAPI_Init(pszInternalCheckInterfaceVersions: *const c_char, pOutErrMsg: *mut SteamErrMsg) -> ESteamAPIInitResult;
+}
+
+#[cfg(feature = "runtime-link")]
+static FLAT_API: OnceLock<FlatApi> = OnceLock::new();
+
+#[cfg(feature = "runtime-link")]
+fn api() -> &'static FlatApi {
+    FLAT_API
+        .get()
+        .expect("libsteam_api is used before it was loaded")
+}
+
+/// Loads the library from `path` unless one is already loaded.
+#[cfg(feature = "runtime-link")]
+pub(crate) fn load(path: &Path) -> Result<(), SteamApiInitError> {
+    if FLAT_API.get().is_some() {
+        return Ok(());
+    }
+
+    let api =
+        unsafe { libloading::Library::new(path).and_then(|library| FlatApi::resolve(library)) }
+            .map_err(|err| {
+                SteamApiInitError::LibraryLoad(format!("{}: {}", path.display(), err))
+            })?;
+
+    tracing::debug!("Loaded {}", path.display());
+    // Another thread may have won the race, its library is just as good
+    let _ = FLAT_API.set(api);
+    Ok(())
+}
+
+/// Loads the library next to the executable, or through the system search path.
+#[cfg(feature = "runtime-link")]
+pub(crate) fn load_default() -> Result<(), SteamApiInitError> {
+    if FLAT_API.get().is_some() {
+        return Ok(());
+    }
+
+    let name = if cfg!(all(windows, target_pointer_width = "64")) {
+        libloading::library_filename("steam_api64")
+    } else {
+        libloading::library_filename("steam_api")
+    };
+
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(&name)));
+
+    match beside_exe {
+        Some(path) if path.exists() => load(&path),
+        _ => load(Path::new(&name)),
+    }
+}
@@ -0,0 +1,271 @@
+pub mod fake;
+pub(crate) mod flat;
+pub mod recording;
+pub mod steam;
+
+use std::{
+    ffi::{CStr, CString},
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+    thread::ThreadId,
+};
+
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+
+use steamgear_sys as sys;
+
+use crate::{
+    core::{
+        enums::{ApiCallFailure, Interface, NotInitialized, ServerMode, SteamApiInitError},
+        structs::{AppId, SteamId},
+        SteamApiState,
+    },
+    friends::structs::FriendGameInfo,
+};
+
+pub use fake::{FakeBackend, FakeCallResults};
+pub use recording::{RecordedEvent, RecordedPipe, Recording};
+pub use steam::SteamworksBackend;
+
+/// Everything steamgear needs from the Steamworks flat API.
+///
+/// The wrappers never call into `steamgear_sys` directly, they go through a backend.
+/// [`SteamworksBackend`] forwards to the real `libsteam_api`, [`FakeBackend`] is an
+/// in-process implementation that can be scripted from tests.
+///
+/// Buffer-filling methods follow the C API: the output is NUL-terminated and
+/// truncated to the buffer size.
+pub trait SteamBackend: std::fmt::Debug + Send + Sync {
+    /// Lifecycle bookkeeping shared by every handle created on top of this backend.
+    fn state(&self) -> &BackendState;
+
+    // Lifecycle
+    fn client_init(
+        &self,
+        app_id: Option<AppId>,
+        interfaces: &[Interface],
+    ) -> Result<(), SteamApiInitError>;
+    fn client_shutdown(&self);
+    fn client_pipe(&self) -> sys::HSteamPipe;
+
+    fn server_init(
+        &self,
+        app_id: Option<AppId>,
+        addr: std::net::SocketAddrV4,
+        query_port: u16,
+        mode: ServerMode,
+        version: &CStr,
+        interfaces: &[Interface],
+    ) -> Result<(), SteamApiInitError>;
+    fn server_shutdown(&self);
+    fn server_pipe(&self) -> sys::HSteamPipe;
+
+    fn restart_app_if_necessary(&self, app_id: AppId) -> bool;
+    fn release_current_thread_memory(&self);
+
+    // Manual dispatch
+    fn run_frame(&self, pipe: sys::HSteamPipe);
+    fn next_event(&self, pipe: sys::HSteamPipe) -> Option<BackendEvent>;
+
+    // ISteamApps
+    fn apps_is_subscribed(&self) -> bool;
+    fn apps_is_subscribed_from_family_sharing(&self) -> bool;
+    fn apps_is_subscribed_from_free_weekend(&self) -> bool;
+    fn apps_is_low_violence(&self) -> bool;
+    fn apps_is_cybercafe(&self) -> bool;
+    fn apps_is_vac_banned(&self) -> bool;
+    fn apps_current_game_language(&self) -> CString;
+    fn apps_available_game_languages(&self) -> CString;
+    fn apps_is_subscribed_app(&self, app_id: AppId) -> bool;
+    fn apps_is_dlc_installed(&self, dlc_id: AppId) -> bool;
+    fn apps_earliest_purchase_unix_time(&self, app_id: AppId) -> u32;
+    fn apps_dlc_count(&self) -> i32;
+    fn apps_dlc_data_by_index(&self, index: i32, name: &mut [u8]) -> Option<(AppId, bool)>;
+    fn apps_install_dlc(&self, dlc_id: AppId);
+    fn apps_uninstall_dlc(&self, dlc_id: AppId);
+    fn apps_current_beta_name(&self, name: &mut [u8]) -> bool;
+    fn apps_mark_content_corrupt(&self, missing_files_only: bool) -> bool;
+    fn apps_installed_depots(&self, app_id: AppId, depots: &mut [u32]) -> u32;
+    fn apps_app_install_dir(&self, app_id: AppId, folder: &mut [u8]) -> u32;
+    fn apps_is_app_installed(&self, app_id: AppId) -> bool;
+    fn apps_app_owner(&self) -> SteamId;
+    fn apps_launch_query_param(&self, key: &CStr) -> CString;
+    fn apps_dlc_download_progress(&self, dlc_id: AppId) -> Option<(u64, u64)>;
+    fn apps_app_build_id(&self) -> i32;
+    fn apps_file_details(&self, file_name: &CStr) -> sys::SteamAPICall_t;
+    fn apps_launch_command_line(&self, command_line: &mut [u8]) -> i32;
+    fn apps_timed_trial(&self) -> Option<(u32, u32)>;
+    fn apps_request_proof_of_purchase_key(&self, app_id: AppId);
+    fn apps_request_all_proof_of_purchase_keys(&self);
+    fn apps_set_dlc_context(&self, app_id: AppId) -> bool;
+
+    // ISteamAppTicket
+    fn app_ticket_ownership_data(&self, app_id: AppId, buffer: &mut [u8]) -> OwnershipTicketLayout;
+
+    // ISteamFriends
+    fn friends_game_played(&self, friend: SteamId) -> Option<FriendGameInfo>;
+
+    // ISteamUtils
+    fn utils_set_warning_message_hook(&self, enabled: bool);
+    fn utils_is_api_call_completed(&self, call: sys::SteamAPICall_t) -> Option<bool>;
+    fn utils_api_call_result(
+        &self,
+        call: sys::SteamAPICall_t,
+        callback_id: u32,
+        size: usize,
+    ) -> Option<Vec<u8>>;
+}
+
+/// A callback copied out of the backend, `data` holds the raw callback struct.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallbackMsg {
+    pub id: u32,
+    pub data: Vec<u8>,
+}
+
+/// A completed async call, `data` holds the raw call result struct.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallResultMsg {
+    pub call: sys::SteamAPICall_t,
+    pub id: u32,
+    pub data: Result<Vec<u8>, ApiCallFailure>,
+}
+
+/// Where `GetAppOwnershipTicketData` put the ticket fields, as offsets into the buffer.
+///
+/// `size` is the size of the whole ticket, 0 if there is none.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OwnershipTicketLayout {
+    pub size: u32,
+    pub app_id: u32,
+    pub steam_id: u32,
+    pub signature: u32,
+    pub signature_size: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BackendEvent {
+    Callback(CallbackMsg),
+    CallResult(CallResultMsg),
+}
+
+/// Lifecycle bookkeeping, the client and the game server start and stop independently.
+#[derive(Debug)]
+pub struct BackendState {
+    pub(crate) client: LifecycleState,
+    pub(crate) server: LifecycleState,
+}
+
+impl BackendState {
+    pub const fn new() -> Self {
+        Self {
+            client: LifecycleState::new(),
+            server: LifecycleState::new(),
+        }
+    }
+}
+
+impl Default for BackendState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct LifecycleState {
+    pub(crate) status: AtomicU8,
+    /// Held shared by interface calls and exclusively while the interfaces are torn down.
+    pub(crate) calls: RwLock<()>,
+    /// The thread inside `run_callbacks`.
+    pumping: Mutex<Option<ThreadId>>,
+    /// Shutdown asked for from inside `run_callbacks`, done when the frame ends.
+    shutdown_pending: AtomicBool,
+}
+
+impl LifecycleState {
+    const fn new() -> Self {
+        Self {
+            status: AtomicU8::new(SteamApiState::Stopped as u8),
+            calls: parking_lot::const_rwlock(()),
+            pumping: parking_lot::const_mutex(None),
+            shutdown_pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Moves from `Init` to `RunCallbacks`, `None` if stopped or another frame is running.
+    pub(crate) fn begin_frame(&self) -> Option<FrameGuard<'_>> {
+        self.status
+            .compare_exchange(
+                SteamApiState::Init as u8,
+                SteamApiState::RunCallbacks as u8,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            )
+            .ok()?;
+
+        *self.pumping.lock() = Some(std::thread::current().id());
+
+        Some(FrameGuard { state: self })
+    }
+
+    /// Whether the current thread is inside `run_callbacks`, where shutdown can't wait
+    /// for the frame to end.
+    pub(crate) fn defer_shutdown(&self) -> bool {
+        if *self.pumping.lock() != Some(std::thread::current().id()) {
+            return false;
+        }
+
+        self.shutdown_pending.store(true, Ordering::Release);
+        true
+    }
+
+    pub(crate) fn shutdown_pending(&self) -> bool {
+        self.shutdown_pending.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn take_shutdown_pending(&self) -> bool {
+        self.shutdown_pending.swap(false, Ordering::AcqRel)
+    }
+
+    /// Checks that the API is running and keeps shutdown from completing while the guard lives.
+    pub(crate) fn enter(&self) -> Result<RwLockReadGuard<'_, ()>, NotInitialized> {
+        let guard = self.calls.read_recursive();
+
+        if self.status.load(Ordering::Acquire) == SteamApiState::Stopped as u8 {
+            return Err(NotInitialized);
+        }
+
+        Ok(guard)
+    }
+}
+
+/// Moves back from `RunCallbacks` to `Init` when the frame ends, also on unwind.
+pub(crate) struct FrameGuard<'a> {
+    state: &'a LifecycleState,
+}
+
+impl Drop for FrameGuard<'_> {
+    fn drop(&mut self) {
+        *self.state.pumping.lock() = None;
+
+        let _ = self.state.status.compare_exchange(
+            SteamApiState::RunCallbacks as u8,
+            SteamApiState::Init as u8,
+            Ordering::SeqCst,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+/// Copies a string into a C buffer the way Steam does and returns the full string length.
+pub(crate) fn copy_to_buffer(src: &[u8], dst: &mut [u8]) -> usize {
+    if let Some(capacity) = dst.len().checked_sub(1) {
+        let len = src.len().min(capacity);
+        dst[..len].copy_from_slice(&src[..len]);
+        dst[len] = 0;
+    }
+
+    src.len()
+}
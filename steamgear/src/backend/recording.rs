@@ -0,0 +1,246 @@
+//! Capture of the events `run_callbacks` hands out, replayed with [`FakeBackend::replay`].
+//!
+//! The file starts with a magic header followed by one record per event, integers are
+//! little endian.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use steamgear_sys as sys;
+
+use crate::core::enums::ApiCallFailure;
+
+use super::{BackendEvent, CallResultMsg, CallbackMsg};
+
+#[cfg(doc)]
+use super::FakeBackend;
+
+const MAGIC: &[u8; 8] = b"SGREC\0\0\x01";
+
+const KIND_CALLBACK: u8 = 0;
+const KIND_CALL_RESULT: u8 = 1;
+const KIND_CALL_FAILURE: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecordedPipe {
+    Client,
+    Server,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedEvent {
+    pub pipe: RecordedPipe,
+    /// The `run_callbacks` call that handed the event out, starting at 1.
+    pub frame: u64,
+    /// Time since the recording started.
+    pub timestamp: Duration,
+    pub event: BackendEvent,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Recording {
+    pub events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a steamgear recording"));
+        }
+
+        let mut events = Vec::new();
+        while let Some(event) = read_event(&mut reader)? {
+            events.push(event);
+        }
+
+        Ok(Self { events })
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        for event in &self.events {
+            write_event(&mut writer, event)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends the events of one pipe to a file as `run_callbacks` hands them out.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    writer: BufWriter<File>,
+    pipe: RecordedPipe,
+    started: Instant,
+    frame: u64,
+}
+
+impl Recorder {
+    pub(crate) fn create(path: &Path, pipe: RecordedPipe) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+
+        Ok(Self {
+            writer,
+            pipe,
+            started: Instant::now(),
+            frame: 0,
+        })
+    }
+
+    pub(crate) fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn record(&mut self, event: &BackendEvent) -> io::Result<()> {
+        write_event(
+            &mut self.writer,
+            &RecordedEvent {
+                pipe: self.pipe,
+                frame: self.frame,
+                timestamp: self.started.elapsed(),
+                event: event.clone(),
+            },
+        )
+    }
+}
+
+/// Records `event` if a recorder is set, a failing recorder is dropped.
+pub(crate) fn record(recorder: &mut Option<Recorder>, event: &BackendEvent) {
+    let Some(active) = recorder else {
+        return;
+    };
+
+    if let Err(err) = active.record(event) {
+        tracing::error!("Stopped recording callbacks: {}", err);
+        *recorder = None;
+    }
+}
+
+fn write_event(writer: &mut impl Write, recorded: &RecordedEvent) -> io::Result<()> {
+    let (kind, id, call, data) = match &recorded.event {
+        BackendEvent::Callback(callback) => (KIND_CALLBACK, callback.id, 0, &callback.data[..]),
+        BackendEvent::CallResult(result) => match &result.data {
+            Ok(data) => (KIND_CALL_RESULT, result.id, result.call, &data[..]),
+            Err(failure) => (
+                KIND_CALL_FAILURE,
+                result.id,
+                result.call,
+                &[failure_code(*failure)][..],
+            ),
+        },
+    };
+
+    let pipe = match recorded.pipe {
+        RecordedPipe::Client => 0u8,
+        RecordedPipe::Server => 1u8,
+    };
+
+    writer.write_all(&[kind, pipe])?;
+    writer.write_all(&recorded.frame.to_le_bytes())?;
+    writer.write_all(&(recorded.timestamp.as_micros() as u64).to_le_bytes())?;
+    writer.write_all(&id.to_le_bytes())?;
+    writer.write_all(&call.to_le_bytes())?;
+    writer.write_all(&(data.len() as u32).to_le_bytes())?;
+    writer.write_all(data)
+}
+
+fn read_event(reader: &mut impl Read) -> io::Result<Option<RecordedEvent>> {
+    let mut header = [0; 2];
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let [kind, pipe] = header;
+
+    let pipe = match pipe {
+        0 => RecordedPipe::Client,
+        1 => RecordedPipe::Server,
+        _ => return Err(invalid_data("unknown pipe")),
+    };
+
+    let frame = u64::from_le_bytes(read_array(reader)?);
+    let timestamp = Duration::from_micros(u64::from_le_bytes(read_array(reader)?));
+    let id = u32::from_le_bytes(read_array(reader)?);
+    let call: sys::SteamAPICall_t = u64::from_le_bytes(read_array(reader)?);
+    let len = u32::from_le_bytes(read_array(reader)?) as usize;
+
+    let mut data = vec![0; len];
+    reader.read_exact(&mut data)?;
+
+    let event = match kind {
+        KIND_CALLBACK => BackendEvent::Callback(CallbackMsg { id, data }),
+        KIND_CALL_RESULT => BackendEvent::CallResult(CallResultMsg {
+            call,
+            id,
+            data: Ok(data),
+        }),
+        KIND_CALL_FAILURE => BackendEvent::CallResult(CallResultMsg {
+            call,
+            id,
+            data: Err(failure_from_code(data.first().copied().unwrap_or(u8::MAX))),
+        }),
+        _ => return Err(invalid_data("unknown event kind")),
+    };
+
+    Ok(Some(RecordedEvent {
+        pipe,
+        frame,
+        timestamp,
+        event,
+    }))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn failure_code(failure: ApiCallFailure) -> u8 {
+    match failure {
+        ApiCallFailure::SteamGone => 0,
+        ApiCallFailure::NetworkFailure => 1,
+        ApiCallFailure::InvalidHandle => 2,
+        ApiCallFailure::MismatchedCallback => 3,
+        ApiCallFailure::Unknown => u8::MAX,
+    }
+}
+
+fn failure_from_code(code: u8) -> ApiCallFailure {
+    match code {
+        0 => ApiCallFailure::SteamGone,
+        1 => ApiCallFailure::NetworkFailure,
+        2 => ApiCallFailure::InvalidHandle,
+        3 => ApiCallFailure::MismatchedCallback,
+        _ => ApiCallFailure::Unknown,
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,568 @@
+use std::{
+    ffi::{c_void, CStr, CString},
+    net::{Ipv4Addr, SocketAddrV4},
+};
+
+use steamgear_sys as sys;
+
+use crate::{
+    core::{
+        enums::{ApiCallFailure, Interface, ServerMode, SteamApiInitError},
+        structs::{AppId, GameId, SteamId},
+    },
+    friends::structs::FriendGameInfo,
+    sdk,
+};
+
+use super::{
+    flat, BackendEvent, BackendState, CallResultMsg, CallbackMsg, OwnershipTicketLayout,
+    SteamBackend,
+};
+
+static STEAM_INIT_STATUS: BackendState = BackendState::new();
+
+// C++ virtual calls, `this` comes first
+#[cfg(all(windows, target_arch = "x86"))]
+type GetAppOwnershipTicketData = unsafe extern "thiscall" fn(
+    *mut c_void,
+    u32,
+    *mut c_void,
+    u32,
+    *mut u32,
+    *mut u32,
+    *mut u32,
+    *mut u32,
+) -> u32;
+#[cfg(not(all(windows, target_arch = "x86")))]
+type GetAppOwnershipTicketData = unsafe extern "C" fn(
+    *mut c_void,
+    u32,
+    *mut c_void,
+    u32,
+    *mut u32,
+    *mut u32,
+    *mut u32,
+    *mut u32,
+) -> u32;
+
+/// The vtable of `ISteamAppTicket`, `GetAppOwnershipTicketData` is its only method.
+#[repr(C)]
+struct AppTicketVtable {
+    get_app_ownership_ticket_data: GetAppOwnershipTicketData,
+}
+
+/// Backend that forwards every call to `libsteam_api`.
+///
+/// With the `runtime-link` feature the library is loaded on init, from the builder's
+/// `library_path` or else next to the executable.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SteamworksBackend;
+
+impl SteamworksBackend {
+    fn apps() -> *mut sys::ISteamApps {
+        unsafe { flat::SteamAPI_SteamApps_v008() }
+    }
+
+    fn friends() -> *mut sys::ISteamFriends {
+        unsafe { flat::SteamAPI_SteamFriends_v017() }
+    }
+
+    fn utils() -> *mut sys::ISteamUtils {
+        unsafe { flat::SteamAPI_SteamUtils_v010() }
+    }
+
+    /// `ISteamAppTicket` has no flat API, it's called through its vtable.
+    fn app_ticket() -> *mut *const AppTicketVtable {
+        let version = CString::new(sdk::STEAMAPPTICKET_INTERFACE_VERSION).unwrap();
+
+        unsafe {
+            flat::SteamInternal_FindOrCreateUserInterface(
+                flat::SteamAPI_GetHSteamUser(),
+                version.as_ptr(),
+            ) as *mut *const AppTicketVtable
+        }
+    }
+
+    fn set_app_id(app_id: Option<AppId>) {
+        if let Some(app_id) = app_id {
+            let app_id = app_id.0.to_string();
+            std::env::set_var("SteamAppId", &app_id);
+            std::env::set_var("SteamGameId", &app_id);
+        }
+    }
+
+    /// Concatenated NUL-terminated versions, ended by an empty string.
+    fn interface_versions(interfaces: &[Interface]) -> Vec<u8> {
+        interfaces
+            .iter()
+            .flat_map(|interface| interface.version().bytes().chain([0]))
+            .chain([0])
+            .collect()
+    }
+}
+
+impl SteamBackend for SteamworksBackend {
+    fn state(&self) -> &BackendState {
+        &STEAM_INIT_STATUS
+    }
+
+    fn client_init(
+        &self,
+        app_id: Option<AppId>,
+        interfaces: &[Interface],
+    ) -> Result<(), SteamApiInitError> {
+        #[cfg(feature = "runtime-link")]
+        flat::load_default()?;
+
+        Self::set_app_id(app_id);
+
+        let versions = Self::interface_versions(interfaces);
+        let versions = versions.as_ptr() as *const ::std::os::raw::c_char;
+
+        let mut err_msg: sys::SteamErrMsg = [0; 1024];
+
+        let result = unsafe { flat::SteamInternal_SteamAPI_Init(versions, &mut err_msg) };
+
+        match result {
+            sys::ESteamAPIInitResult_k_ESteamAPIInitResult_OK => {
+                unsafe { flat::SteamAPI_ManualDispatch_Init() };
+                Ok(())
+            }
+            _ => Err(SteamApiInitError::from_raw(result, err_msg, interfaces)),
+        }
+    }
+
+    fn client_shutdown(&self) {
+        unsafe { flat::SteamAPI_Shutdown() }
+    }
+
+    fn client_pipe(&self) -> sys::HSteamPipe {
+        unsafe { flat::SteamAPI_GetHSteamPipe() }
+    }
+
+    fn server_init(
+        &self,
+        app_id: Option<AppId>,
+        addr: std::net::SocketAddrV4,
+        query_port: u16,
+        mode: ServerMode,
+        version: &CStr,
+        interfaces: &[Interface],
+    ) -> Result<(), SteamApiInitError> {
+        #[cfg(feature = "runtime-link")]
+        flat::load_default()?;
+
+        Self::set_app_id(app_id);
+
+        let versions = Self::interface_versions(interfaces);
+        let versions = versions.as_ptr() as *const ::std::os::raw::c_char;
+
+        let mut err_msg: sys::SteamErrMsg = [0; 1024];
+
+        let ip = u32::from_be_bytes(addr.ip().octets());
+
+        let result = unsafe {
+            flat::SteamInternal_GameServer_Init_V2(
+                ip,
+                addr.port(),
+                query_port,
+                mode as _,
+                version.as_ptr(),
+                versions,
+                &mut err_msg,
+            )
+        };
+
+        match result {
+            sys::ESteamAPIInitResult_k_ESteamAPIInitResult_OK => {
+                unsafe { flat::SteamAPI_ManualDispatch_Init() };
+                Ok(())
+            }
+            _ => Err(SteamApiInitError::from_raw(result, err_msg, interfaces)),
+        }
+    }
+
+    fn server_shutdown(&self) {
+        unsafe { flat::SteamGameServer_Shutdown() }
+    }
+
+    fn server_pipe(&self) -> sys::HSteamPipe {
+        unsafe { flat::SteamGameServer_GetHSteamPipe() }
+    }
+
+    fn restart_app_if_necessary(&self, app_id: AppId) -> bool {
+        // Init reports the missing library
+        #[cfg(feature = "runtime-link")]
+        if flat::load_default().is_err() {
+            return false;
+        }
+
+        unsafe { flat::SteamAPI_RestartAppIfNecessary(app_id.0) }
+    }
+
+    fn release_current_thread_memory(&self) {
+        unsafe { flat::SteamAPI_ReleaseCurrentThreadMemory() }
+    }
+
+    fn run_frame(&self, pipe: sys::HSteamPipe) {
+        unsafe { flat::SteamAPI_ManualDispatch_RunFrame(pipe) }
+    }
+
+    fn next_event(&self, pipe: sys::HSteamPipe) -> Option<BackendEvent> {
+        unsafe {
+            let mut callback: sys::CallbackMsg_t = std::mem::zeroed();
+
+            if !flat::SteamAPI_ManualDispatch_GetNextCallback(pipe, &mut callback) {
+                return None;
+            }
+
+            let event = if callback.m_iCallback as u32
+                == sys::SteamAPICallCompleted_t_k_iCallback as u32
+            {
+                let apicall = std::ptr::read_unaligned(
+                    callback.m_pubParam as *const sys::SteamAPICallCompleted_t,
+                );
+
+                let mut data = vec![0u8; apicall.m_cubParam as usize];
+                let mut failed = false;
+
+                let ok = flat::SteamAPI_ManualDispatch_GetAPICallResult(
+                    pipe,
+                    apicall.m_hAsyncCall,
+                    data.as_mut_ptr() as *mut _,
+                    data.len() as _,
+                    apicall.m_iCallback,
+                    &mut failed,
+                );
+
+                let data = if ok && !failed {
+                    Ok(data)
+                } else {
+                    let utils = if pipe == self.server_pipe() {
+                        flat::SteamAPI_SteamGameServerUtils_v010()
+                    } else {
+                        Self::utils()
+                    };
+
+                    Err(ApiCallFailure::from_raw(
+                        flat::SteamAPI_ISteamUtils_GetAPICallFailureReason(
+                            utils,
+                            apicall.m_hAsyncCall,
+                        ),
+                    ))
+                };
+
+                BackendEvent::CallResult(CallResultMsg {
+                    call: apicall.m_hAsyncCall,
+                    id: apicall.m_iCallback as u32,
+                    data,
+                })
+            } else {
+                let data =
+                    std::slice::from_raw_parts(callback.m_pubParam, callback.m_cubParam as usize);
+
+                BackendEvent::Callback(CallbackMsg {
+                    id: callback.m_iCallback as u32,
+                    data: data.to_vec(),
+                })
+            };
+
+            flat::SteamAPI_ManualDispatch_FreeLastCallback(pipe);
+
+            Some(event)
+        }
+    }
+
+    fn apps_is_subscribed(&self) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsSubscribed(Self::apps()) }
+    }
+
+    fn apps_is_subscribed_from_family_sharing(&self) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsSubscribedFromFamilySharing(Self::apps()) }
+    }
+
+    fn apps_is_subscribed_from_free_weekend(&self) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsSubscribedFromFreeWeekend(Self::apps()) }
+    }
+
+    fn apps_is_low_violence(&self) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsLowViolence(Self::apps()) }
+    }
+
+    fn apps_is_cybercafe(&self) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsCybercafe(Self::apps()) }
+    }
+
+    fn apps_is_vac_banned(&self) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsVACBanned(Self::apps()) }
+    }
+
+    fn apps_current_game_language(&self) -> CString {
+        unsafe {
+            let raw = flat::SteamAPI_ISteamApps_GetCurrentGameLanguage(Self::apps());
+            CStr::from_ptr(raw).to_owned()
+        }
+    }
+
+    fn apps_available_game_languages(&self) -> CString {
+        unsafe {
+            let raw = flat::SteamAPI_ISteamApps_GetAvailableGameLanguages(Self::apps());
+            CStr::from_ptr(raw).to_owned()
+        }
+    }
+
+    fn apps_is_subscribed_app(&self, app_id: AppId) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsSubscribedApp(Self::apps(), app_id.0) }
+    }
+
+    fn apps_is_dlc_installed(&self, dlc_id: AppId) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsDlcInstalled(Self::apps(), dlc_id.0) }
+    }
+
+    fn apps_earliest_purchase_unix_time(&self, app_id: AppId) -> u32 {
+        unsafe { flat::SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(Self::apps(), app_id.0) }
+    }
+
+    fn apps_dlc_count(&self) -> i32 {
+        unsafe { flat::SteamAPI_ISteamApps_GetDLCCount(Self::apps()) }
+    }
+
+    fn apps_dlc_data_by_index(&self, index: i32, name: &mut [u8]) -> Option<(AppId, bool)> {
+        unsafe {
+            let mut app_id = 0;
+            let mut available = false;
+
+            flat::SteamAPI_ISteamApps_BGetDLCDataByIndex(
+                Self::apps(),
+                index,
+                &mut app_id,
+                &mut available,
+                name.as_mut_ptr() as *mut _,
+                name.len() as _,
+            )
+            .then_some((AppId(app_id), available))
+        }
+    }
+
+    fn apps_install_dlc(&self, dlc_id: AppId) {
+        unsafe { flat::SteamAPI_ISteamApps_InstallDLC(Self::apps(), dlc_id.0) }
+    }
+
+    fn apps_uninstall_dlc(&self, dlc_id: AppId) {
+        unsafe { flat::SteamAPI_ISteamApps_UninstallDLC(Self::apps(), dlc_id.0) }
+    }
+
+    fn apps_current_beta_name(&self, name: &mut [u8]) -> bool {
+        unsafe {
+            flat::SteamAPI_ISteamApps_GetCurrentBetaName(
+                Self::apps(),
+                name.as_mut_ptr() as *mut _,
+                name.len() as _,
+            )
+        }
+    }
+
+    fn apps_mark_content_corrupt(&self, missing_files_only: bool) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_MarkContentCorrupt(Self::apps(), missing_files_only) }
+    }
+
+    fn apps_installed_depots(&self, app_id: AppId, depots: &mut [u32]) -> u32 {
+        unsafe {
+            flat::SteamAPI_ISteamApps_GetInstalledDepots(
+                Self::apps(),
+                app_id.0,
+                depots.as_mut_ptr(),
+                depots.len() as _,
+            )
+        }
+    }
+
+    fn apps_app_install_dir(&self, app_id: AppId, folder: &mut [u8]) -> u32 {
+        unsafe {
+            flat::SteamAPI_ISteamApps_GetAppInstallDir(
+                Self::apps(),
+                app_id.0,
+                folder.as_mut_ptr() as *mut _,
+                folder.len() as _,
+            )
+        }
+    }
+
+    fn apps_is_app_installed(&self, app_id: AppId) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_BIsAppInstalled(Self::apps(), app_id.0) }
+    }
+
+    fn apps_app_owner(&self) -> SteamId {
+        unsafe { SteamId(flat::SteamAPI_ISteamApps_GetAppOwner(Self::apps())) }
+    }
+
+    fn apps_launch_query_param(&self, key: &CStr) -> CString {
+        unsafe {
+            let raw = flat::SteamAPI_ISteamApps_GetLaunchQueryParam(Self::apps(), key.as_ptr());
+            CStr::from_ptr(raw).to_owned()
+        }
+    }
+
+    fn apps_dlc_download_progress(&self, dlc_id: AppId) -> Option<(u64, u64)> {
+        unsafe {
+            let mut downloaded = 0;
+            let mut total = 0;
+
+            flat::SteamAPI_ISteamApps_GetDlcDownloadProgress(
+                Self::apps(),
+                dlc_id.0,
+                &mut downloaded,
+                &mut total,
+            )
+            .then_some((downloaded, total))
+        }
+    }
+
+    fn apps_app_build_id(&self) -> i32 {
+        unsafe { flat::SteamAPI_ISteamApps_GetAppBuildId(Self::apps()) }
+    }
+
+    fn apps_file_details(&self, file_name: &CStr) -> sys::SteamAPICall_t {
+        unsafe { flat::SteamAPI_ISteamApps_GetFileDetails(Self::apps(), file_name.as_ptr()) }
+    }
+
+    fn apps_launch_command_line(&self, command_line: &mut [u8]) -> i32 {
+        unsafe {
+            flat::SteamAPI_ISteamApps_GetLaunchCommandLine(
+                Self::apps(),
+                command_line.as_mut_ptr() as *mut _,
+                command_line.len() as _,
+            )
+        }
+    }
+
+    fn apps_timed_trial(&self) -> Option<(u32, u32)> {
+        unsafe {
+            let mut allowed = 0;
+            let mut played = 0;
+
+            flat::SteamAPI_ISteamApps_BIsTimedTrial(Self::apps(), &mut allowed, &mut played)
+                .then_some((allowed, played))
+        }
+    }
+
+    fn apps_request_proof_of_purchase_key(&self, app_id: AppId) {
+        unsafe { flat::SteamAPI_ISteamApps_RequestAppProofOfPurchaseKey(Self::apps(), app_id.0) }
+    }
+
+    fn apps_request_all_proof_of_purchase_keys(&self) {
+        unsafe { flat::SteamAPI_ISteamApps_RequestAllProofOfPurchaseKeys(Self::apps()) }
+    }
+
+    fn apps_set_dlc_context(&self, app_id: AppId) -> bool {
+        unsafe { flat::SteamAPI_ISteamApps_SetDlcContext(Self::apps(), app_id.0) }
+    }
+
+    fn app_ticket_ownership_data(&self, app_id: AppId, buffer: &mut [u8]) -> OwnershipTicketLayout {
+        let ticket = Self::app_ticket();
+        if ticket.is_null() {
+            return OwnershipTicketLayout::default();
+        }
+
+        let mut layout = OwnershipTicketLayout::default();
+        layout.size = unsafe {
+            ((**ticket).get_app_ownership_ticket_data)(
+                ticket as *mut c_void,
+                app_id.0,
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer.len() as u32,
+                &mut layout.app_id,
+                &mut layout.steam_id,
+                &mut layout.signature,
+                &mut layout.signature_size,
+            )
+        };
+
+        layout
+    }
+
+    fn friends_game_played(&self, friend: SteamId) -> Option<FriendGameInfo> {
+        unsafe {
+            let mut info: sys::FriendGameInfo_t = std::mem::zeroed();
+
+            if !flat::SteamAPI_ISteamFriends_GetFriendGamePlayed(
+                Self::friends(),
+                friend.0,
+                &mut info,
+            ) {
+                return None;
+            }
+
+            let lobby = SteamId::from_raw(info.m_steamIDLobby);
+
+            Some(FriendGameInfo {
+                game_id: GameId(info.m_gameID.__bindgen_anon_1.m_ulGameID),
+                game_addr: SocketAddrV4::new(Ipv4Addr::from(info.m_unGameIP), info.m_usGamePort),
+                query_port: info.m_usQueryPort,
+                lobby: lobby.is_valid().then_some(lobby),
+            })
+        }
+    }
+
+    fn utils_set_warning_message_hook(&self, enabled: bool) {
+        let hook: sys::SteamAPIWarningMessageHook_t = if enabled {
+            Some(_internal_warning_message_hook)
+        } else {
+            None
+        };
+
+        unsafe { flat::SteamAPI_ISteamUtils_SetWarningMessageHook(Self::utils(), hook) }
+    }
+
+    fn utils_is_api_call_completed(&self, call: sys::SteamAPICall_t) -> Option<bool> {
+        let mut failed = false;
+
+        let result = unsafe {
+            flat::SteamAPI_ISteamUtils_IsAPICallCompleted(Self::utils(), call, &mut failed)
+        };
+
+        (!failed).then_some(result)
+    }
+
+    fn utils_api_call_result(
+        &self,
+        call: sys::SteamAPICall_t,
+        callback_id: u32,
+        size: usize,
+    ) -> Option<Vec<u8>> {
+        let mut data = vec![0u8; size];
+        let mut failed = false;
+
+        let result = unsafe {
+            flat::SteamAPI_ISteamUtils_GetAPICallResult(
+                Self::utils(),
+                call,
+                data.as_mut_ptr() as *mut _,
+                size as _,
+                callback_id as _,
+                &mut failed,
+            )
+        };
+
+        (!failed && result).then_some(data)
+    }
+}
+
+unsafe extern "C" fn _internal_warning_message_hook(
+    severity: std::os::raw::c_int,
+    message: *const std::os::raw::c_char,
+) {
+    let message = CStr::from_ptr(message);
+
+    match severity {
+        0 => {
+            tracing::info!("{:?}", message);
+        }
+        1 => {
+            tracing::warn!("{:?}", message);
+        }
+        _ => {
+            unreachable!()
+        }
+    }
+}
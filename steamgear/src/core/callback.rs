@@ -1,13 +1,35 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
 use async_channel::{Receiver, Sender};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use thiserror::Error;
 
 use dashmap::DashMap;
 
 use steamgear_sys as sys;
 
+use super::enums::{ApiCallFailure, NotInitialized, SteamError};
+pub(crate) use super::generated::CallbackType;
+use super::metrics::{Dispatched, QueueDepth};
 use crate::{
-    apps::callbacks::{DlcInstalled, NewUrlLaunchParams},
+    apps::{
+        callbacks::{DlcInstalled, NewUrlLaunchParams, ProofOfPurchaseKey},
+        dlc::DlcInstalls,
+        launch::LaunchParamsDispatcher,
+    },
+    backend::CallResultMsg,
+    game_server::callbacks::{
+        ClientApprove, ClientDeny, ClientKick, PolicyResponse, StatsReceived,
+        SteamServerConnectFailure, SteamServersConnected, SteamServersDisconnected,
+        ValidateAuthTicketResponse,
+    },
     utils::callbacks::SteamShutdown,
 };
 
@@ -18,45 +40,390 @@ pub(crate) trait CallbackTyped: Clone + Send + 'static {
 
     fn from_raw(raw: Self::Raw) -> Self::Mapped;
 
-    unsafe fn from_ptr(ptr: *mut u8) -> Self::Raw {
-        assert_eq!(
-            std::mem::align_of::<Self::Raw>(),
-            std::mem::align_of_val(&ptr)
-        );
+    fn from_bytes(data: &[u8]) -> Option<Self::Raw> {
+        if data.len() != std::mem::size_of::<Self::Raw>() {
+            tracing::error!(
+                "Callback {} has size {}, expected {}",
+                std::any::type_name::<Self>(),
+                data.len(),
+                std::mem::size_of::<Self::Raw>()
+            );
+            return None;
+        }
 
-        let raw_type: Self::Raw = *(ptr as *const Self::Raw);
+        let raw_type: Self::Raw =
+            unsafe { std::ptr::read_unaligned(data.as_ptr() as *const Self::Raw) };
 
-        raw_type
+        Some(raw_type)
     }
 }
 
+#[derive(Debug)]
+pub(crate) struct PendingCallResult {
+    sender: Sender<Result<CallResultMsg, CallResultError>>,
+    deadline: Option<Instant>,
+    /// Tells registrations of a reused call handle apart.
+    generation: u64,
+}
+
+/// Call results awaited on one pipe.
+#[derive(Debug, Default)]
+pub(crate) struct CallResults {
+    pending: DashMap<sys::SteamAPICall_t, PendingCallResult>,
+    /// Read while a call starts and registers, so `complete` can't slip in between.
+    starting: RwLock<()>,
+    next_generation: AtomicU64,
+    pub(crate) timeout: Mutex<Option<Duration>>,
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct ClientCallbackContainer {
-    pub(crate) call_results: DashMap<sys::SteamAPICall_t, Sender<sys::CallbackMsg_t>>,
+    pub(crate) call_results: CallResults,
+    pub(crate) raw_callbacks: RawCallbacks,
 
     pub(crate) steam_shutdown_callback: MultiDispatcher<SteamShutdown>,
 
     // Steam Apps Callbacks
-    pub(crate) dlc_installed_callback: OneshotDispatcher<DlcInstalled>,
+    pub(crate) dlc_installed_callback: MultiDispatcher<DlcInstalled>,
+    pub(crate) dlc_installs: DlcInstalls,
+    pub(crate) proof_of_purchase_key_callback: MultiDispatcher<ProofOfPurchaseKey>,
     pub(crate) new_url_launch_params_callback: MultiDispatcher<NewUrlLaunchParams>,
+    pub(crate) launch_params: LaunchParamsDispatcher,
 }
 
 unsafe impl Send for ClientCallbackContainer {}
 unsafe impl Sync for ClientCallbackContainer {}
 
 impl ClientCallbackContainer {
-    pub(crate) async fn register_call_result<T: CallbackTyped>(
+    pub(crate) fn queue_depths(&self) -> Vec<QueueDepth> {
+        let mut depths = vec![
+            self.steam_shutdown_callback.queue_depth(),
+            self.dlc_installed_callback.queue_depth(),
+            self.proof_of_purchase_key_callback.queue_depth(),
+            self.new_url_launch_params_callback.queue_depth(),
+            self.launch_params.queue_depth(),
+        ];
+        depths.extend(self.raw_callbacks.queue_depths());
+        depths
+    }
+
+    /// Closes the typed callback streams, used on shutdown.
+    pub(crate) fn close(&self) {
+        self.steam_shutdown_callback.close();
+        self.dlc_installed_callback.close();
+        self.proof_of_purchase_key_callback.close();
+        self.new_url_launch_params_callback.close();
+        self.launch_params.close();
+        self.raw_callbacks.close();
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ServerCallbackContainer {
+    pub(crate) call_results: CallResults,
+    pub(crate) raw_callbacks: RawCallbacks,
+
+    pub(crate) steam_shutdown_callback: MultiDispatcher<SteamShutdown>,
+
+    // Steam Game Server Callbacks
+    pub(crate) servers_connected_callback: MultiDispatcher<SteamServersConnected>,
+    pub(crate) server_connect_failure_callback: MultiDispatcher<SteamServerConnectFailure>,
+    pub(crate) servers_disconnected_callback: MultiDispatcher<SteamServersDisconnected>,
+    pub(crate) validate_auth_ticket_callback: MultiDispatcher<ValidateAuthTicketResponse>,
+    pub(crate) client_approve_callback: MultiDispatcher<ClientApprove>,
+    pub(crate) client_deny_callback: MultiDispatcher<ClientDeny>,
+    pub(crate) client_kick_callback: MultiDispatcher<ClientKick>,
+    pub(crate) policy_response_callback: MultiDispatcher<PolicyResponse>,
+    pub(crate) stats_received_callback: MultiDispatcher<StatsReceived>,
+}
+
+unsafe impl Send for ServerCallbackContainer {}
+unsafe impl Sync for ServerCallbackContainer {}
+
+impl ServerCallbackContainer {
+    pub(crate) fn queue_depths(&self) -> Vec<QueueDepth> {
+        let mut depths = vec![
+            self.steam_shutdown_callback.queue_depth(),
+            self.servers_connected_callback.queue_depth(),
+            self.server_connect_failure_callback.queue_depth(),
+            self.servers_disconnected_callback.queue_depth(),
+            self.validate_auth_ticket_callback.queue_depth(),
+            self.client_approve_callback.queue_depth(),
+            self.client_deny_callback.queue_depth(),
+            self.client_kick_callback.queue_depth(),
+            self.policy_response_callback.queue_depth(),
+            self.stats_received_callback.queue_depth(),
+        ];
+        depths.extend(self.raw_callbacks.queue_depths());
+        depths
+    }
+
+    /// Closes the typed callback streams, used on shutdown.
+    pub(crate) fn close(&self) {
+        self.steam_shutdown_callback.close();
+        self.servers_connected_callback.close();
+        self.server_connect_failure_callback.close();
+        self.servers_disconnected_callback.close();
+        self.validate_auth_ticket_callback.close();
+        self.client_approve_callback.close();
+        self.client_deny_callback.close();
+        self.client_kick_callback.close();
+        self.policy_response_callback.close();
+        self.stats_received_callback.close();
+        self.raw_callbacks.close();
+    }
+}
+
+impl CallResults {
+    /// Starts a call with `start` and registers it before `run_callbacks` on another
+    /// thread can complete it.
+    pub(crate) fn start<T: CallbackTyped>(
+        &self,
+        start: impl FnOnce() -> sys::SteamAPICall_t,
+    ) -> CallResult<'_, T::Mapped>
+    where
+        T::Mapped: Send,
+    {
+        let registration = {
+            let _starting = self.starting.read();
+            self.insert(start())
+        };
+
+        self.awaiting(registration, T::TYPE as u32, |data| {
+            let raw_data = T::from_bytes(&data).ok_or(CallResultError::InvalidSize {
+                size: data.len(),
+                expected: std::mem::size_of::<T::Raw>(),
+            })?;
+
+            Ok(T::from_raw(raw_data))
+        })
+    }
+
+    fn awaiting<'a, T: Send + 'a>(
+        &'a self,
+        registration: Result<Registration<'a>, CallResultError>,
+        callback_id: u32,
+        read: impl FnOnce(Vec<u8>) -> Result<T, CallResultError> + Send + 'a,
+    ) -> CallResult<'a, T> {
+        let entry = registration
+            .as_ref()
+            .ok()
+            .map(|registration| (self, registration.guard.id, registration.guard.generation));
+
+        CallResult {
+            inner: Box::pin(async move { read(Self::wait(registration?, callback_id).await?) }),
+            entry,
+            deadline: None,
+        }
+    }
+
+    /// Waits for call `id` and checks that it completed with callback `callback_id`.
+    pub(crate) fn register_raw(
         &self,
         id: sys::SteamAPICall_t,
-    ) -> T::Mapped {
+        callback_id: u32,
+    ) -> CallResult<'_, Vec<u8>> {
+        let registration = {
+            let _starting = self.starting.read();
+            self.insert(id)
+        };
+
+        self.awaiting(registration, callback_id, Ok)
+    }
+
+    fn insert(&self, id: sys::SteamAPICall_t) -> Result<Registration<'_>, CallResultError> {
+        if id == sys::k_uAPICallInvalid {
+            return Err(CallResultError::Failed(ApiCallFailure::InvalidHandle));
+        }
+
         let (sender, receiver) = async_channel::bounded(1);
-        self.call_results.insert(id, sender);
-        let result = receiver.recv().await.expect("Client dropped");
+        let deadline = self.timeout.lock().map(|timeout| Instant::now() + timeout);
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        self.pending.insert(
+            id,
+            PendingCallResult {
+                sender,
+                deadline,
+                generation,
+            },
+        );
 
-        assert_eq!(std::mem::size_of::<T::Raw>(), result.m_cubParam as usize);
+        Ok(Registration {
+            receiver,
+            guard: CallResultGuard {
+                results: self,
+                id,
+                generation,
+            },
+        })
+    }
 
-        let raw_data = unsafe { T::from_ptr(result.m_pubParam) };
-        T::from_raw(raw_data)
+    async fn wait(
+        registration: Registration<'_>,
+        callback_id: u32,
+    ) -> Result<Vec<u8>, CallResultError> {
+        let result = registration
+            .receiver
+            .recv()
+            .await
+            .map_err(|_| CallResultError::Shutdown)??;
+
+        if result.id != callback_id {
+            return Err(CallResultError::Failed(ApiCallFailure::MismatchedCallback));
+        }
+
+        result.data.map_err(CallResultError::Failed)
+    }
+
+    /// Same as [`CallResults::register_raw`], read as the user supplied struct `T`.
+    pub(crate) fn register_struct<T: CallbackStruct>(
+        &self,
+        id: sys::SteamAPICall_t,
+    ) -> CallResult<'_, T> {
+        self.register_raw(id, T::ID).and_then(|data| {
+            read_struct(&data).ok_or(CallResultError::InvalidSize {
+                size: data.len(),
+                expected: std::mem::size_of::<T>(),
+            })
+        })
+    }
+
+    pub(crate) fn complete(&self, result: CallResultMsg) {
+        let id = result.call;
+        let _starting = self.starting.write();
+
+        if let Some((_, pending)) = self.pending.remove(&id) {
+            match pending.sender.try_send(Ok(result)) {
+                Ok(_) => {
+                    tracing::debug!("Sent call result with id: {}", id)
+                }
+                Err(_) => {
+                    tracing::debug!(
+                        "CallResult with id {} have received, but receiver is broken",
+                        id
+                    )
+                }
+            }
+        }
+    }
+
+    /// Fails every pending call result whose deadline is before `now`.
+    pub(crate) fn expire(&self, now: Instant) {
+        self.pending.retain(|id, pending| match pending.deadline {
+            Some(deadline) if deadline <= now => {
+                tracing::debug!("CallResult with id {} timed out", id);
+                let _ = pending.sender.try_send(Err(CallResultError::Timeout));
+                false
+            }
+            _ => true,
+        });
+    }
+
+    pub(crate) fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Fails every pending call result, used on shutdown.
+    pub(crate) fn cancel(&self) {
+        self.pending.clear();
+    }
+}
+
+struct Registration<'a> {
+    receiver: Receiver<Result<CallResultMsg, CallResultError>>,
+    // Removes the entry if the future is dropped before completion
+    guard: CallResultGuard<'a>,
+}
+
+struct CallResultGuard<'a> {
+    results: &'a CallResults,
+    id: sys::SteamAPICall_t,
+    generation: u64,
+}
+
+impl Drop for CallResultGuard<'_> {
+    fn drop(&mut self) {
+        // A newer registration of the same handle stays
+        self.results
+            .pending
+            .remove_if(&self.id, |_, pending| pending.generation == self.generation);
+    }
+}
+
+/// A call result being awaited, registered when the call was made.
+///
+/// Timeouts are enforced by `run_callbacks`. Without it nothing wakes the future, a
+/// poll after the deadline still resolves with [`CallResultError::Timeout`].
+#[must_use = "futures do nothing unless polled"]
+pub struct CallResult<'a, T> {
+    inner: Pin<Box<dyn Future<Output = Result<T, CallResultError>> + Send + 'a>>,
+    entry: Option<(&'a CallResults, sys::SteamAPICall_t, u64)>,
+    deadline: Option<Instant>,
+}
+
+impl<'a, T: Send + 'a> CallResult<'a, T> {
+    pub(crate) fn ready(result: Result<T, CallResultError>) -> Self {
+        Self {
+            inner: Box::pin(std::future::ready(result)),
+            entry: None,
+            deadline: None,
+        }
+    }
+
+    pub(crate) fn and_then<U: Send + 'a>(
+        self,
+        f: impl FnOnce(T) -> Result<U, CallResultError> + Send + 'a,
+    ) -> CallResult<'a, U> {
+        let inner = self.inner;
+
+        CallResult {
+            inner: Box::pin(async move { f(inner.await?) }),
+            entry: self.entry,
+            deadline: self.deadline,
+        }
+    }
+
+    /// Fails with [`CallResultError::Timeout`] after `timeout`, in place of the client's
+    /// default timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        let deadline = Instant::now() + timeout;
+
+        if let Some((results, id, generation)) = self.entry {
+            if let Some(mut pending) = results.pending.get_mut(&id) {
+                if pending.generation == generation {
+                    pending.deadline = Some(deadline);
+                }
+            }
+        }
+
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
+impl<T> Future for CallResult<'_, T> {
+    type Output = Result<T, CallResultError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = self.inner.as_mut().poll(cx) {
+            return Poll::Ready(output);
+        }
+
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Poll::Ready(Err(CallResultError::Timeout))
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for CallResult<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallResult")
+            .field("call", &self.entry.map(|(_, id, _)| id))
+            .field("deadline", &self.deadline)
+            .finish()
     }
 }
 
@@ -67,7 +434,37 @@ pub(crate) trait CallbackDispatcher: Send + Sync {
         Self: 'a;
 
     fn register(&self) -> Self::Output<'_>;
-    fn proceed(&self, value: Self::Item);
+    fn proceed(&self, value: Self::Item) -> Dispatched;
+    fn queue_depth(&self) -> QueueDepth;
+
+    /// Reads the callback from `data` and hands it out, a malformed one counts as dropped.
+    fn proceed_bytes(&self, data: &[u8]) -> Dispatched
+    where
+        Self::Item: CallbackTyped<Mapped = Self::Item>,
+    {
+        match Self::Item::from_bytes(data) {
+            Some(raw) => self.proceed(Self::Item::from_raw(raw)),
+            None => DROPPED,
+        }
+    }
+}
+
+const DELIVERED: Dispatched = Dispatched {
+    delivered: 1,
+    dropped: 0,
+};
+
+pub(crate) const DROPPED: Dispatched = Dispatched {
+    delivered: 0,
+    dropped: 1,
+};
+
+fn queue_depth<T: CallbackTyped>(senders: &[&Sender<T>]) -> QueueDepth {
+    let mut depth = QueueDepth::new(T::TYPE as u32);
+    for sender in senders {
+        depth.add(sender.len(), sender.capacity().unwrap_or(0));
+    }
+    depth
 }
 
 #[derive(Debug)]
@@ -102,24 +499,32 @@ impl<T: CallbackTyped> CallbackDispatcher for SingleDispatcher<T> {
         receiver
     }
 
-    fn proceed(&self, value: Self::Item) {
+    fn proceed(&self, value: Self::Item) -> Dispatched {
         let storage = &self.inner;
         let mut guard = storage.lock();
 
-        if let Some(sender) = &mut *guard {
-            match sender.send_blocking(value) {
-                Ok(_) => {
-                    tracing::debug!("Sent callback: {}", std::any::type_name::<Self>())
-                }
-                Err(_) => {
-                    tracing::error!(
-                        "Callback {} have received, but receiver is broken",
-                        std::any::type_name::<Self>()
-                    )
-                }
+        let Some(sender) = &mut *guard else {
+            return Dispatched::default();
+        };
+
+        match sender.send_blocking(value) {
+            Ok(_) => {
+                tracing::debug!("Sent callback: {}", std::any::type_name::<Self>());
+                DELIVERED
+            }
+            Err(_) => {
+                tracing::error!(
+                    "Callback {} have received, but receiver is broken",
+                    std::any::type_name::<Self>()
+                );
+                DROPPED
             }
         }
     }
+
+    fn queue_depth(&self) -> QueueDepth {
+        queue_depth(self.inner.lock().iter().collect::<Vec<_>>().as_slice())
+    }
 }
 
 #[derive(Debug)]
@@ -154,36 +559,61 @@ impl<T: CallbackTyped> CallbackDispatcher for OneshotDispatcher<T> {
         receiver
     }
 
-    fn proceed(&self, value: Self::Item) {
+    fn proceed(&self, value: Self::Item) -> Dispatched {
         let storage = &self.inner;
         let mut guard = storage.lock();
 
-        if let Some(sender) = guard.take() {
-            match sender.send_blocking(value) {
-                Ok(_) => {
-                    tracing::debug!("Sent callback: {}", std::any::type_name::<Self>())
-                }
-                Err(_) => {
-                    tracing::error!(
-                        "Callback {} have received, but receiver is broken",
-                        std::any::type_name::<Self>()
-                    )
-                }
+        let Some(sender) = guard.take() else {
+            return Dispatched::default();
+        };
+
+        match sender.send_blocking(value) {
+            Ok(_) => {
+                tracing::debug!("Sent callback: {}", std::any::type_name::<Self>());
+                DELIVERED
+            }
+            Err(_) => {
+                tracing::error!(
+                    "Callback {} have received, but receiver is broken",
+                    std::any::type_name::<Self>()
+                );
+                DROPPED
             }
         }
     }
+
+    fn queue_depth(&self) -> QueueDepth {
+        queue_depth(self.inner.lock().iter().collect::<Vec<_>>().as_slice())
+    }
 }
 
+/// Buffer size of every [`MultiDispatcher`] subscriber.
+pub(crate) const MULTI_DISPATCHER_CAPACITY: usize = 32;
+
+/// Hands every callback to every live subscriber.
+///
+/// Each subscriber gets its own buffer of [`MULTI_DISPATCHER_CAPACITY`] callbacks.
+/// Dispatch never blocks: when a subscriber lags behind and its buffer is full,
+/// the oldest buffered callback is dropped for that subscriber only.
+/// Subscribers whose stream was dropped are removed on the next dispatch.
 #[derive(Debug)]
 pub(crate) struct MultiDispatcher<T: CallbackTyped> {
-    inner: (Sender<T>, Receiver<T>),
+    inner: Mutex<Vec<Sender<T>>>,
 }
 
 impl<T: CallbackTyped> Default for MultiDispatcher<T> {
     fn default() -> Self {
-        let (sender, receiver) = async_channel::bounded(32);
         Self {
-            inner: (sender, receiver),
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<T: CallbackTyped> MultiDispatcher<T> {
+    /// Closes every receiver, queued values can still be received.
+    pub(crate) fn close(&self) {
+        for sender in self.inner.lock().drain(..) {
+            sender.close();
         }
     }
 }
@@ -193,62 +623,261 @@ impl<T: CallbackTyped> CallbackDispatcher for MultiDispatcher<T> {
     type Output<'a> = Receiver<Self::Item>;
 
     fn register(&self) -> Self::Output<'_> {
-        let (_, recv) = &self.inner;
-        recv.clone()
+        let (sender, receiver) = async_channel::bounded(MULTI_DISPATCHER_CAPACITY);
+        self.inner.lock().push(sender);
+
+        receiver
     }
 
-    fn proceed(&self, value: Self::Item) {
-        let (sender, _) = &self.inner;
+    fn proceed(&self, value: Self::Item) -> Dispatched {
+        let mut guard = self.inner.lock();
+        guard.retain(|sender| !sender.is_closed());
 
-        if sender.receiver_count() > 1 {
-            match sender.send_blocking(value) {
-                Ok(_) => {
-                    tracing::debug!("Sent callback: {}", std::any::type_name::<Self>())
-                }
-                Err(_) => {
-                    tracing::error!(
-                        "Callback {} have received, but receiver is broken",
-                        std::any::type_name::<Self>()
-                    )
-                }
-            }
-        }
+        guard
+            .iter()
+            .map(|sender| forward(sender, value.clone()))
+            .fold(Dispatched::default(), |total, dispatched| {
+                total + dispatched
+            })
+    }
+
+    fn queue_depth(&self) -> QueueDepth {
+        queue_depth(self.inner.lock().iter().collect::<Vec<_>>().as_slice())
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(u32)]
-pub(crate) enum CallbackType {
-    SteamShutdown = sys::SteamShutdown_t_k_iCallback as u32,
-    FileDetailsResult = sys::FileDetailsResult_t_k_iCallback as u32,
-    DlcInstalled = sys::DlcInstalled_t_k_iCallback as u32,
-    NewUrlLaunchParameters = sys::NewUrlLaunchParameters_t_k_iCallback as u32,
+type RawForward = Box<dyn Fn(&[u8]) -> Dispatched + Send + Sync>;
+
+/// A raw subscriber, type erased so subscribers of different structs share an id.
+struct RawSubscriber {
+    forward: RawForward,
+    queue: Box<dyn RawQueue>,
+}
+
+trait RawQueue: Send + Sync {
+    fn is_closed(&self) -> bool;
+    fn len(&self) -> usize;
+    fn capacity(&self) -> usize;
+    fn close(&self);
 }
 
-impl CallbackType {
-    // Do not use placeholder
-    pub(crate) fn is_for_client(&self) -> bool {
-        match self {
-            CallbackType::SteamShutdown => true,
-            CallbackType::FileDetailsResult => true,
-            CallbackType::DlcInstalled => true,
-            CallbackType::NewUrlLaunchParameters => true,
+impl<T: Send> RawQueue for Sender<T> {
+    fn is_closed(&self) -> bool {
+        Sender::is_closed(self)
+    }
+
+    fn len(&self) -> usize {
+        Sender::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        Sender::capacity(self).unwrap_or(0)
+    }
+
+    fn close(&self) {
+        Sender::close(self);
+    }
+}
+
+/// Subscribers of callbacks by id, they see every callback, wrapped by the crate or not.
+#[derive(Default)]
+pub(crate) struct RawCallbacks {
+    inner: Mutex<HashMap<u32, Vec<RawSubscriber>>>,
+}
+
+impl std::fmt::Debug for RawCallbacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawCallbacks")
+            .field("ids", &self.inner.lock().keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl RawCallbacks {
+    pub(crate) fn register_bytes(&self, id: u32) -> Receiver<Vec<u8>> {
+        let (sender, receiver) = async_channel::bounded(MULTI_DISPATCHER_CAPACITY);
+        self.subscribe(
+            id,
+            RawSubscriber {
+                forward: Box::new({
+                    let sender = sender.clone();
+                    move |data| forward(&sender, data.to_vec())
+                }),
+                queue: Box::new(sender),
+            },
+        );
+
+        receiver
+    }
+
+    pub(crate) fn register_struct<T: CallbackStruct>(&self) -> Receiver<T> {
+        let (sender, receiver) = async_channel::bounded(MULTI_DISPATCHER_CAPACITY);
+        self.subscribe(
+            T::ID,
+            RawSubscriber {
+                forward: Box::new({
+                    let sender = sender.clone();
+                    move |data| match read_struct(data) {
+                        Some(value) => forward(&sender, value),
+                        None => {
+                            tracing::error!(
+                                "Callback {} has size {}, expected {} for {}",
+                                T::ID,
+                                data.len(),
+                                std::mem::size_of::<T>(),
+                                std::any::type_name::<T>()
+                            );
+                            DROPPED
+                        }
+                    }
+                }),
+                queue: Box::new(sender),
+            },
+        );
+
+        receiver
+    }
+
+    pub(crate) fn close(&self) {
+        for subscriber in self
+            .inner
+            .lock()
+            .drain()
+            .flat_map(|(_, subscribers)| subscribers)
+        {
+            subscriber.queue.close();
         }
     }
 
-    // Do not use placeholder
-    pub(crate) fn is_for_server(&self) -> bool {
-        match self {
-            CallbackType::SteamShutdown => true,
-            CallbackType::FileDetailsResult => false,
-            CallbackType::DlcInstalled => false,
-            CallbackType::NewUrlLaunchParameters => false,
+    fn subscribe(&self, id: u32, subscriber: RawSubscriber) {
+        self.inner.lock().entry(id).or_default().push(subscriber);
+    }
+
+    /// Nothing is delivered or dropped when nobody is subscribed to `id`.
+    pub(crate) fn proceed(&self, id: u32, data: &[u8]) -> Dispatched {
+        let mut guard = self.inner.lock();
+        let Some(subscribers) = guard.get_mut(&id) else {
+            return Dispatched::default();
+        };
+
+        subscribers.retain(|subscriber| !subscriber.queue.is_closed());
+        if subscribers.is_empty() {
+            guard.remove(&id);
+            return Dispatched::default();
+        }
+
+        subscribers
+            .iter()
+            .map(|subscriber| (subscriber.forward)(data))
+            .fold(Dispatched::default(), |total, dispatched| {
+                total + dispatched
+            })
+    }
+
+    pub(crate) fn queue_depths(&self) -> Vec<QueueDepth> {
+        self.inner
+            .lock()
+            .iter()
+            .map(|(id, subscribers)| {
+                let mut depth = QueueDepth::new(*id);
+                for subscriber in subscribers.iter().filter(|s| !s.queue.is_closed()) {
+                    depth.add(subscriber.queue.len(), subscriber.queue.capacity());
+                }
+                depth
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn forward<T>(sender: &Sender<T>, value: T) -> Dispatched {
+    match sender.force_send(value) {
+        Ok(None) => {
+            tracing::debug!("Sent callback: {}", std::any::type_name::<T>());
+            DELIVERED
+        }
+        Ok(Some(_)) => {
+            tracing::warn!(
+                "Subscriber of {} is lagging, dropped the oldest callback",
+                std::any::type_name::<T>()
+            );
+            DELIVERED + DROPPED
+        }
+        Err(_) => {
+            tracing::error!(
+                "Callback {} have received, but receiver is broken",
+                std::any::type_name::<T>()
+            );
+            DROPPED
         }
     }
 }
 
-#[derive(Clone, Error, Debug)]
+fn read_struct<T: CallbackStruct>(data: &[u8]) -> Option<T> {
+    if data.len() != std::mem::size_of::<T>() {
+        return None;
+    }
+
+    Some(unsafe { std::ptr::read_unaligned(data.as_ptr() as *const T) })
+}
+
+/// A callback struct handed out as is, by [`SteamApiClient::on_callback`](super::client::SteamApiClient::on_callback)
+/// and friends. Implemented for every SDK callback struct without pointers.
+///
+/// # Safety
+///
+/// The type must have the layout the SDK uses for callback `ID`, usually `#[repr(C)]`
+/// with the SDK's packing, and any bytes Steam writes must be a valid value.
+pub unsafe trait CallbackStruct: Copy + Send + 'static {
+    const ID: u32;
+}
+
+unsafe impl<T: SteamCallbackStruct> CallbackStruct for T {
+    const ID: u32 = T::TYPE as u32;
+}
+
+/// A plain SDK callback struct, implemented by the generated tables.
+pub(crate) trait SteamCallbackStruct: Copy + Send + 'static {
+    const TYPE: CallbackType;
+}
+
+/// Callback without a high-level wrapper, handed out as the SDK struct.
+#[derive(Clone, Copy)]
+pub(crate) struct RawCallback<R>(pub(crate) R);
+
+impl<R: SteamCallbackStruct> CallbackTyped for RawCallback<R> {
+    const TYPE: CallbackType = R::TYPE;
+    type Raw = R;
+    type Mapped = R;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        raw
+    }
+}
+
+#[derive(Clone, Copy, Error, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CallbackError {
     #[error("This callback is pending elsewhere, the current request is canceled")]
     Canceled,
+    #[error(transparent)]
+    NotInitialized(#[from] NotInitialized),
+    #[error("Steam API was shut down while waiting")]
+    Shutdown,
+}
+
+#[derive(Clone, Copy, Error, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CallResultError {
+    #[error("{0}")]
+    Failed(ApiCallFailure),
+    #[error("{0}")]
+    Steam(#[from] SteamError),
+    #[error(transparent)]
+    NotInitialized(#[from] NotInitialized),
+    #[error("Call result didn't complete in time")]
+    Timeout,
+    #[error("Steam API was shut down before the call result completed")]
+    Shutdown,
+    #[error("Call result has size {size}, expected {expected}")]
+    InvalidSize { size: usize, expected: usize },
 }
@@ -3,10 +3,21 @@ use std::fmt::Display;
 use steamgear_sys as sys;
 use thiserror::Error;
 
-use super::{callback::CallbackType, enums::SteamApiInitError};
+use crate::sdk;
+
+use super::enums::{
+    AccountType, ApiCallFailure, AuthSessionResponse, DenyReason, GameIdType, Interface,
+    SteamApiInitError, SteamError, Universe,
+};
+use super::structs::SteamId;
 
 impl SteamApiInitError {
-    pub(crate) fn from_raw(raw: sys::ESteamAPIInitResult, msg: sys::SteamErrMsg) -> Self {
+    /// `interfaces` are the requested ones, Steam names the mismatched one in `msg`.
+    pub(crate) fn from_raw(
+        raw: sys::ESteamAPIInitResult,
+        msg: sys::SteamErrMsg,
+        interfaces: &[Interface],
+    ) -> Self {
         let msg = unsafe {
             let cstr = std::ffi::CStr::from_ptr(msg.as_ptr() as *const std::ffi::c_char);
             cstr.to_string_lossy().to_string()
@@ -20,31 +31,547 @@ impl SteamApiInitError {
                 Self::NoSteamClient(msg)
             }
             sys::ESteamAPIInitResult_k_ESteamAPIInitResult_VersionMismatch => {
-                Self::VersionMismatch(msg)
+                let interface = interfaces
+                    .iter()
+                    .find(|interface| msg.contains(interface.version()))
+                    .copied();
+                Self::VersionMismatch { interface, msg }
             }
-            _ => unreachable!(),
+            code => Self::Unknown { code, msg },
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, Error)]
-pub(crate) struct UnknownCallback;
+impl Interface {
+    /// Version string of the SDK steamgear was built against.
+    pub fn version(&self) -> &'static str {
+        match self {
+            Self::Utils => sdk::STEAMUTILS_INTERFACE_VERSION,
+            Self::NetworkingUtils => sdk::STEAMNETWORKINGUTILS_INTERFACE_VERSION,
+            Self::Apps => sdk::STEAMAPPS_INTERFACE_VERSION,
+            Self::Controller => sdk::STEAMCONTROLLER_INTERFACE_VERSION,
+            Self::Friends => sdk::STEAMFRIENDS_INTERFACE_VERSION,
+            Self::GameSearch => sdk::STEAMGAMESEARCH_INTERFACE_VERSION,
+            Self::HtmlSurface => sdk::STEAMHTMLSURFACE_INTERFACE_VERSION,
+            Self::Http => sdk::STEAMHTTP_INTERFACE_VERSION,
+            Self::Input => sdk::STEAMINPUT_INTERFACE_VERSION,
+            Self::Inventory => sdk::STEAMINVENTORY_INTERFACE_VERSION,
+            Self::MatchmakingServers => sdk::STEAMMATCHMAKINGSERVERS_INTERFACE_VERSION,
+            Self::Matchmaking => sdk::STEAMMATCHMAKING_INTERFACE_VERSION,
+            Self::MusicRemote => sdk::STEAMMUSICREMOTE_INTERFACE_VERSION,
+            Self::Music => sdk::STEAMMUSIC_INTERFACE_VERSION,
+            Self::NetworkingMessages => sdk::STEAMNETWORKINGMESSAGES_INTERFACE_VERSION,
+            Self::NetworkingSockets => sdk::STEAMNETWORKINGSOCKETS_INTERFACE_VERSION,
+            Self::Networking => sdk::STEAMNETWORKING_INTERFACE_VERSION,
+            Self::ParentalSettings => sdk::STEAMPARENTALSETTINGS_INTERFACE_VERSION,
+            Self::Parties => sdk::STEAMPARTIES_INTERFACE_VERSION,
+            Self::RemotePlay => sdk::STEAMREMOTEPLAY_INTERFACE_VERSION,
+            Self::RemoteStorage => sdk::STEAMREMOTESTORAGE_INTERFACE_VERSION,
+            Self::Screenshots => sdk::STEAMSCREENSHOTS_INTERFACE_VERSION,
+            Self::Ugc => sdk::STEAMUGC_INTERFACE_VERSION,
+            Self::UserStats => sdk::STEAMUSERSTATS_INTERFACE_VERSION,
+            Self::User => sdk::STEAMUSER_INTERFACE_VERSION,
+            Self::Video => sdk::STEAMVIDEO_INTERFACE_VERSION,
+            Self::GameServer => sdk::STEAMGAMESERVER_INTERFACE_VERSION,
+            Self::GameServerStats => sdk::STEAMGAMESERVERSTATS_INTERFACE_VERSION,
+        }
+    }
+}
 
-impl Display for UnknownCallback {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Unknown callback type")
+impl SteamId {
+    pub(crate) fn from_raw(raw: sys::CSteamID) -> Self {
+        Self(unsafe { raw.m_steamid.m_unAll64Bits })
+    }
+
+    pub(crate) fn to_raw(self) -> sys::CSteamID {
+        sys::CSteamID {
+            m_steamid: sys::CSteamID_SteamID_t {
+                m_unAll64Bits: self.0,
+            },
+        }
     }
 }
 
-impl TryFrom<u32> for CallbackType {
-    type Error = UnknownCallback;
+impl GameIdType {
+    pub(crate) fn from_raw(raw: sys::CGameID_EGameIDType) -> Option<Self> {
+        match raw {
+            sys::CGameID_EGameIDType_k_EGameIDTypeApp => Some(Self::App),
+            sys::CGameID_EGameIDType_k_EGameIDTypeGameMod => Some(Self::Mod),
+            sys::CGameID_EGameIDType_k_EGameIDTypeShortcut => Some(Self::Shortcut),
+            sys::CGameID_EGameIDType_k_EGameIDTypeP2P => Some(Self::P2P),
+            _ => None,
+        }
+    }
+}
 
-    fn try_from(value: u32) -> Result<Self, Self::Error> {
-        match value.try_into().unwrap() {
-            sys::SteamShutdown_t_k_iCallback => Ok(CallbackType::SteamShutdown),
-            sys::DlcInstalled_t_k_iCallback => Ok(CallbackType::DlcInstalled),
-            sys::NewUrlLaunchParameters_t_k_iCallback => Ok(CallbackType::NewUrlLaunchParameters),
-            _ => Err(UnknownCallback),
+impl Universe {
+    pub(crate) fn from_raw(raw: sys::EUniverse) -> Self {
+        match raw {
+            sys::EUniverse_k_EUniversePublic => Self::Public,
+            sys::EUniverse_k_EUniverseBeta => Self::Beta,
+            sys::EUniverse_k_EUniverseInternal => Self::Internal,
+            sys::EUniverse_k_EUniverseDev => Self::Dev,
+            _ => Self::Invalid,
+        }
+    }
+}
+
+impl DenyReason {
+    pub(crate) fn from_raw(raw: sys::EDenyReason) -> Self {
+        match raw {
+            sys::EDenyReason_k_EDenyInvalidVersion => Self::InvalidVersion,
+            sys::EDenyReason_k_EDenyGeneric => Self::Generic,
+            sys::EDenyReason_k_EDenyNotLoggedOn => Self::NotLoggedOn,
+            sys::EDenyReason_k_EDenyNoLicense => Self::NoLicense,
+            sys::EDenyReason_k_EDenyCheater => Self::Cheater,
+            sys::EDenyReason_k_EDenyLoggedInElseWhere => Self::LoggedInElseWhere,
+            sys::EDenyReason_k_EDenyUnknownText => Self::UnknownText,
+            sys::EDenyReason_k_EDenyIncompatibleAnticheat => Self::IncompatibleAnticheat,
+            sys::EDenyReason_k_EDenyMemoryCorruption => Self::MemoryCorruption,
+            sys::EDenyReason_k_EDenyIncompatibleSoftware => Self::IncompatibleSoftware,
+            sys::EDenyReason_k_EDenySteamConnectionLost => Self::SteamConnectionLost,
+            sys::EDenyReason_k_EDenySteamConnectionError => Self::SteamConnectionError,
+            sys::EDenyReason_k_EDenySteamResponseTimedOut => Self::SteamResponseTimedOut,
+            sys::EDenyReason_k_EDenySteamValidationStalled => Self::SteamValidationStalled,
+            sys::EDenyReason_k_EDenySteamOwnerLeftGuestUser => Self::SteamOwnerLeftGuestUser,
+            _ => Self::Invalid,
         }
     }
 }
+
+impl AuthSessionResponse {
+    pub(crate) fn from_raw(raw: sys::EAuthSessionResponse) -> Self {
+        match raw {
+            sys::EAuthSessionResponse_k_EAuthSessionResponseOK => Self::Ok,
+            sys::EAuthSessionResponse_k_EAuthSessionResponseUserNotConnectedToSteam => {
+                Self::UserNotConnectedToSteam
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponseNoLicenseOrExpired => {
+                Self::NoLicenseOrExpired
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponseVACBanned => Self::VacBanned,
+            sys::EAuthSessionResponse_k_EAuthSessionResponseLoggedInElseWhere => {
+                Self::LoggedInElseWhere
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponseVACCheckTimedOut => {
+                Self::VacCheckTimedOut
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketCanceled => {
+                Self::AuthTicketCanceled
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketInvalidAlreadyUsed => {
+                Self::AuthTicketInvalidAlreadyUsed
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketInvalid => {
+                Self::AuthTicketInvalid
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponsePublisherIssuedBan => {
+                Self::PublisherIssuedBan
+            }
+            sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketNetworkIdentityFailure => {
+                Self::AuthTicketNetworkIdentityFailure
+            }
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl AccountType {
+    pub(crate) fn from_raw(raw: sys::EAccountType) -> Self {
+        match raw {
+            sys::EAccountType_k_EAccountTypeIndividual => Self::Individual,
+            sys::EAccountType_k_EAccountTypeMultiseat => Self::Multiseat,
+            sys::EAccountType_k_EAccountTypeGameServer => Self::GameServer,
+            sys::EAccountType_k_EAccountTypeAnonGameServer => Self::AnonGameServer,
+            sys::EAccountType_k_EAccountTypePending => Self::Pending,
+            sys::EAccountType_k_EAccountTypeContentServer => Self::ContentServer,
+            sys::EAccountType_k_EAccountTypeClan => Self::Clan,
+            sys::EAccountType_k_EAccountTypeChat => Self::Chat,
+            sys::EAccountType_k_EAccountTypeConsoleUser => Self::ConsoleUser,
+            sys::EAccountType_k_EAccountTypeAnonUser => Self::AnonUser,
+            _ => Self::Invalid,
+        }
+    }
+
+    /// Letter used by the Steam3 text form, e.g. `U` in `[U:1:2468]`.
+    pub(crate) fn steam3_letter(&self) -> char {
+        match self {
+            Self::Invalid => 'I',
+            Self::Individual => 'U',
+            Self::Multiseat => 'M',
+            Self::GameServer => 'G',
+            Self::AnonGameServer => 'A',
+            Self::Pending => 'P',
+            Self::ContentServer => 'C',
+            Self::Clan => 'g',
+            Self::Chat => 'T',
+            Self::ConsoleUser => 'I',
+            Self::AnonUser => 'a',
+        }
+    }
+
+    pub(crate) fn from_steam3_letter(letter: char) -> Option<Self> {
+        Some(match letter {
+            'I' => Self::Invalid,
+            'U' => Self::Individual,
+            'M' => Self::Multiseat,
+            'G' => Self::GameServer,
+            'A' => Self::AnonGameServer,
+            'P' => Self::Pending,
+            'C' => Self::ContentServer,
+            'g' => Self::Clan,
+            'T' | 'c' | 'L' => Self::Chat,
+            'a' => Self::AnonUser,
+            _ => return None,
+        })
+    }
+}
+
+impl ApiCallFailure {
+    pub(crate) fn from_raw(raw: sys::ESteamAPICallFailure) -> Self {
+        match raw {
+            sys::ESteamAPICallFailure_k_ESteamAPICallFailureSteamGone => Self::SteamGone,
+            sys::ESteamAPICallFailure_k_ESteamAPICallFailureNetworkFailure => Self::NetworkFailure,
+            sys::ESteamAPICallFailure_k_ESteamAPICallFailureInvalidHandle => Self::InvalidHandle,
+            sys::ESteamAPICallFailure_k_ESteamAPICallFailureMismatchedCallback => {
+                Self::MismatchedCallback
+            }
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl SteamError {
+    /// Maps a raw `EResult`, `OK` is the only success.
+    pub fn from_code(code: sys::EResult) -> Result<(), Self> {
+        Err(match code {
+            sys::EResult_k_EResultOK => return Ok(()),
+            sys::EResult_k_EResultNone => Self::None,
+            sys::EResult_k_EResultFail => Self::Fail,
+            sys::EResult_k_EResultNoConnection => Self::NoConnection,
+            sys::EResult_k_EResultInvalidPassword => Self::InvalidPassword,
+            sys::EResult_k_EResultLoggedInElsewhere => Self::LoggedInElsewhere,
+            sys::EResult_k_EResultInvalidProtocolVer => Self::InvalidProtocolVer,
+            sys::EResult_k_EResultInvalidParam => Self::InvalidParam,
+            sys::EResult_k_EResultFileNotFound => Self::FileNotFound,
+            sys::EResult_k_EResultBusy => Self::Busy,
+            sys::EResult_k_EResultInvalidState => Self::InvalidState,
+            sys::EResult_k_EResultInvalidName => Self::InvalidName,
+            sys::EResult_k_EResultInvalidEmail => Self::InvalidEmail,
+            sys::EResult_k_EResultDuplicateName => Self::DuplicateName,
+            sys::EResult_k_EResultAccessDenied => Self::AccessDenied,
+            sys::EResult_k_EResultTimeout => Self::Timeout,
+            sys::EResult_k_EResultBanned => Self::Banned,
+            sys::EResult_k_EResultAccountNotFound => Self::AccountNotFound,
+            sys::EResult_k_EResultInvalidSteamID => Self::InvalidSteamID,
+            sys::EResult_k_EResultServiceUnavailable => Self::ServiceUnavailable,
+            sys::EResult_k_EResultNotLoggedOn => Self::NotLoggedOn,
+            sys::EResult_k_EResultPending => Self::Pending,
+            sys::EResult_k_EResultEncryptionFailure => Self::EncryptionFailure,
+            sys::EResult_k_EResultInsufficientPrivilege => Self::InsufficientPrivilege,
+            sys::EResult_k_EResultLimitExceeded => Self::LimitExceeded,
+            sys::EResult_k_EResultRevoked => Self::Revoked,
+            sys::EResult_k_EResultExpired => Self::Expired,
+            sys::EResult_k_EResultAlreadyRedeemed => Self::AlreadyRedeemed,
+            sys::EResult_k_EResultDuplicateRequest => Self::DuplicateRequest,
+            sys::EResult_k_EResultAlreadyOwned => Self::AlreadyOwned,
+            sys::EResult_k_EResultIPNotFound => Self::IPNotFound,
+            sys::EResult_k_EResultPersistFailed => Self::PersistFailed,
+            sys::EResult_k_EResultLockingFailed => Self::LockingFailed,
+            sys::EResult_k_EResultLogonSessionReplaced => Self::LogonSessionReplaced,
+            sys::EResult_k_EResultConnectFailed => Self::ConnectFailed,
+            sys::EResult_k_EResultHandshakeFailed => Self::HandshakeFailed,
+            sys::EResult_k_EResultIOFailure => Self::IOFailure,
+            sys::EResult_k_EResultRemoteDisconnect => Self::RemoteDisconnect,
+            sys::EResult_k_EResultShoppingCartNotFound => Self::ShoppingCartNotFound,
+            sys::EResult_k_EResultBlocked => Self::Blocked,
+            sys::EResult_k_EResultIgnored => Self::Ignored,
+            sys::EResult_k_EResultNoMatch => Self::NoMatch,
+            sys::EResult_k_EResultAccountDisabled => Self::AccountDisabled,
+            sys::EResult_k_EResultServiceReadOnly => Self::ServiceReadOnly,
+            sys::EResult_k_EResultAccountNotFeatured => Self::AccountNotFeatured,
+            sys::EResult_k_EResultAdministratorOK => Self::AdministratorOK,
+            sys::EResult_k_EResultContentVersion => Self::ContentVersion,
+            sys::EResult_k_EResultTryAnotherCM => Self::TryAnotherCM,
+            sys::EResult_k_EResultPasswordRequiredToKickSession => {
+                Self::PasswordRequiredToKickSession
+            }
+            sys::EResult_k_EResultAlreadyLoggedInElsewhere => Self::AlreadyLoggedInElsewhere,
+            sys::EResult_k_EResultSuspended => Self::Suspended,
+            sys::EResult_k_EResultCancelled => Self::Cancelled,
+            sys::EResult_k_EResultDataCorruption => Self::DataCorruption,
+            sys::EResult_k_EResultDiskFull => Self::DiskFull,
+            sys::EResult_k_EResultRemoteCallFailed => Self::RemoteCallFailed,
+            sys::EResult_k_EResultPasswordUnset => Self::PasswordUnset,
+            sys::EResult_k_EResultExternalAccountUnlinked => Self::ExternalAccountUnlinked,
+            sys::EResult_k_EResultPSNTicketInvalid => Self::PSNTicketInvalid,
+            sys::EResult_k_EResultExternalAccountAlreadyLinked => {
+                Self::ExternalAccountAlreadyLinked
+            }
+            sys::EResult_k_EResultRemoteFileConflict => Self::RemoteFileConflict,
+            sys::EResult_k_EResultIllegalPassword => Self::IllegalPassword,
+            sys::EResult_k_EResultSameAsPreviousValue => Self::SameAsPreviousValue,
+            sys::EResult_k_EResultAccountLogonDenied => Self::AccountLogonDenied,
+            sys::EResult_k_EResultCannotUseOldPassword => Self::CannotUseOldPassword,
+            sys::EResult_k_EResultInvalidLoginAuthCode => Self::InvalidLoginAuthCode,
+            sys::EResult_k_EResultAccountLogonDeniedNoMail => Self::AccountLogonDeniedNoMail,
+            sys::EResult_k_EResultHardwareNotCapableOfIPT => Self::HardwareNotCapableOfIPT,
+            sys::EResult_k_EResultIPTInitError => Self::IPTInitError,
+            sys::EResult_k_EResultParentalControlRestricted => Self::ParentalControlRestricted,
+            sys::EResult_k_EResultFacebookQueryError => Self::FacebookQueryError,
+            sys::EResult_k_EResultExpiredLoginAuthCode => Self::ExpiredLoginAuthCode,
+            sys::EResult_k_EResultIPLoginRestrictionFailed => Self::IPLoginRestrictionFailed,
+            sys::EResult_k_EResultAccountLockedDown => Self::AccountLockedDown,
+            sys::EResult_k_EResultAccountLogonDeniedVerifiedEmailRequired => {
+                Self::AccountLogonDeniedVerifiedEmailRequired
+            }
+            sys::EResult_k_EResultNoMatchingURL => Self::NoMatchingURL,
+            sys::EResult_k_EResultBadResponse => Self::BadResponse,
+            sys::EResult_k_EResultRequirePasswordReEntry => Self::RequirePasswordReEntry,
+            sys::EResult_k_EResultValueOutOfRange => Self::ValueOutOfRange,
+            sys::EResult_k_EResultUnexpectedError => Self::UnexpectedError,
+            sys::EResult_k_EResultDisabled => Self::Disabled,
+            sys::EResult_k_EResultInvalidCEGSubmission => Self::InvalidCEGSubmission,
+            sys::EResult_k_EResultRestrictedDevice => Self::RestrictedDevice,
+            sys::EResult_k_EResultRegionLocked => Self::RegionLocked,
+            sys::EResult_k_EResultRateLimitExceeded => Self::RateLimitExceeded,
+            sys::EResult_k_EResultAccountLoginDeniedNeedTwoFactor => {
+                Self::AccountLoginDeniedNeedTwoFactor
+            }
+            sys::EResult_k_EResultItemDeleted => Self::ItemDeleted,
+            sys::EResult_k_EResultAccountLoginDeniedThrottle => Self::AccountLoginDeniedThrottle,
+            sys::EResult_k_EResultTwoFactorCodeMismatch => Self::TwoFactorCodeMismatch,
+            sys::EResult_k_EResultTwoFactorActivationCodeMismatch => {
+                Self::TwoFactorActivationCodeMismatch
+            }
+            sys::EResult_k_EResultAccountAssociatedToMultiplePartners => {
+                Self::AccountAssociatedToMultiplePartners
+            }
+            sys::EResult_k_EResultNotModified => Self::NotModified,
+            sys::EResult_k_EResultNoMobileDevice => Self::NoMobileDevice,
+            sys::EResult_k_EResultTimeNotSynced => Self::TimeNotSynced,
+            sys::EResult_k_EResultSmsCodeFailed => Self::SmsCodeFailed,
+            sys::EResult_k_EResultAccountLimitExceeded => Self::AccountLimitExceeded,
+            sys::EResult_k_EResultAccountActivityLimitExceeded => {
+                Self::AccountActivityLimitExceeded
+            }
+            sys::EResult_k_EResultPhoneActivityLimitExceeded => Self::PhoneActivityLimitExceeded,
+            sys::EResult_k_EResultRefundToWallet => Self::RefundToWallet,
+            sys::EResult_k_EResultEmailSendFailure => Self::EmailSendFailure,
+            sys::EResult_k_EResultNotSettled => Self::NotSettled,
+            sys::EResult_k_EResultNeedCaptcha => Self::NeedCaptcha,
+            sys::EResult_k_EResultGSLTDenied => Self::GSLTDenied,
+            sys::EResult_k_EResultGSOwnerDenied => Self::GSOwnerDenied,
+            sys::EResult_k_EResultInvalidItemType => Self::InvalidItemType,
+            sys::EResult_k_EResultIPBanned => Self::IPBanned,
+            sys::EResult_k_EResultGSLTExpired => Self::GSLTExpired,
+            sys::EResult_k_EResultInsufficientFunds => Self::InsufficientFunds,
+            sys::EResult_k_EResultTooManyPending => Self::TooManyPending,
+            sys::EResult_k_EResultNoSiteLicensesFound => Self::NoSiteLicensesFound,
+            sys::EResult_k_EResultWGNetworkSendExceeded => Self::WGNetworkSendExceeded,
+            sys::EResult_k_EResultAccountNotFriends => Self::AccountNotFriends,
+            sys::EResult_k_EResultLimitedUserAccount => Self::LimitedUserAccount,
+            sys::EResult_k_EResultCantRemoveItem => Self::CantRemoveItem,
+            sys::EResult_k_EResultAccountDeleted => Self::AccountDeleted,
+            sys::EResult_k_EResultExistingUserCancelledLicense => {
+                Self::ExistingUserCancelledLicense
+            }
+            sys::EResult_k_EResultCommunityCooldown => Self::CommunityCooldown,
+            sys::EResult_k_EResultNoLauncherSpecified => Self::NoLauncherSpecified,
+            sys::EResult_k_EResultMustAgreeToSSA => Self::MustAgreeToSSA,
+            sys::EResult_k_EResultLauncherMigrated => Self::LauncherMigrated,
+            sys::EResult_k_EResultSteamRealmMismatch => Self::SteamRealmMismatch,
+            sys::EResult_k_EResultInvalidSignature => Self::InvalidSignature,
+            sys::EResult_k_EResultParseFailure => Self::ParseFailure,
+            sys::EResult_k_EResultNoVerifiedPhone => Self::NoVerifiedPhone,
+            sys::EResult_k_EResultInsufficientBattery => Self::InsufficientBattery,
+            sys::EResult_k_EResultChargerRequired => Self::ChargerRequired,
+            sys::EResult_k_EResultCachedCredentialInvalid => Self::CachedCredentialInvalid,
+            sys::EResult_k_EResultNotSupported => Self::NotSupported,
+            sys::EResult_k_EResultFamilySizeLimitExceeded => Self::FamilySizeLimitExceeded,
+            code => Self::Unknown(code),
+        })
+    }
+
+    /// The raw `EResult` value.
+    pub fn code(&self) -> sys::EResult {
+        match self {
+            Self::None => sys::EResult_k_EResultNone,
+            Self::Fail => sys::EResult_k_EResultFail,
+            Self::NoConnection => sys::EResult_k_EResultNoConnection,
+            Self::InvalidPassword => sys::EResult_k_EResultInvalidPassword,
+            Self::LoggedInElsewhere => sys::EResult_k_EResultLoggedInElsewhere,
+            Self::InvalidProtocolVer => sys::EResult_k_EResultInvalidProtocolVer,
+            Self::InvalidParam => sys::EResult_k_EResultInvalidParam,
+            Self::FileNotFound => sys::EResult_k_EResultFileNotFound,
+            Self::Busy => sys::EResult_k_EResultBusy,
+            Self::InvalidState => sys::EResult_k_EResultInvalidState,
+            Self::InvalidName => sys::EResult_k_EResultInvalidName,
+            Self::InvalidEmail => sys::EResult_k_EResultInvalidEmail,
+            Self::DuplicateName => sys::EResult_k_EResultDuplicateName,
+            Self::AccessDenied => sys::EResult_k_EResultAccessDenied,
+            Self::Timeout => sys::EResult_k_EResultTimeout,
+            Self::Banned => sys::EResult_k_EResultBanned,
+            Self::AccountNotFound => sys::EResult_k_EResultAccountNotFound,
+            Self::InvalidSteamID => sys::EResult_k_EResultInvalidSteamID,
+            Self::ServiceUnavailable => sys::EResult_k_EResultServiceUnavailable,
+            Self::NotLoggedOn => sys::EResult_k_EResultNotLoggedOn,
+            Self::Pending => sys::EResult_k_EResultPending,
+            Self::EncryptionFailure => sys::EResult_k_EResultEncryptionFailure,
+            Self::InsufficientPrivilege => sys::EResult_k_EResultInsufficientPrivilege,
+            Self::LimitExceeded => sys::EResult_k_EResultLimitExceeded,
+            Self::Revoked => sys::EResult_k_EResultRevoked,
+            Self::Expired => sys::EResult_k_EResultExpired,
+            Self::AlreadyRedeemed => sys::EResult_k_EResultAlreadyRedeemed,
+            Self::DuplicateRequest => sys::EResult_k_EResultDuplicateRequest,
+            Self::AlreadyOwned => sys::EResult_k_EResultAlreadyOwned,
+            Self::IPNotFound => sys::EResult_k_EResultIPNotFound,
+            Self::PersistFailed => sys::EResult_k_EResultPersistFailed,
+            Self::LockingFailed => sys::EResult_k_EResultLockingFailed,
+            Self::LogonSessionReplaced => sys::EResult_k_EResultLogonSessionReplaced,
+            Self::ConnectFailed => sys::EResult_k_EResultConnectFailed,
+            Self::HandshakeFailed => sys::EResult_k_EResultHandshakeFailed,
+            Self::IOFailure => sys::EResult_k_EResultIOFailure,
+            Self::RemoteDisconnect => sys::EResult_k_EResultRemoteDisconnect,
+            Self::ShoppingCartNotFound => sys::EResult_k_EResultShoppingCartNotFound,
+            Self::Blocked => sys::EResult_k_EResultBlocked,
+            Self::Ignored => sys::EResult_k_EResultIgnored,
+            Self::NoMatch => sys::EResult_k_EResultNoMatch,
+            Self::AccountDisabled => sys::EResult_k_EResultAccountDisabled,
+            Self::ServiceReadOnly => sys::EResult_k_EResultServiceReadOnly,
+            Self::AccountNotFeatured => sys::EResult_k_EResultAccountNotFeatured,
+            Self::AdministratorOK => sys::EResult_k_EResultAdministratorOK,
+            Self::ContentVersion => sys::EResult_k_EResultContentVersion,
+            Self::TryAnotherCM => sys::EResult_k_EResultTryAnotherCM,
+            Self::PasswordRequiredToKickSession => {
+                sys::EResult_k_EResultPasswordRequiredToKickSession
+            }
+            Self::AlreadyLoggedInElsewhere => sys::EResult_k_EResultAlreadyLoggedInElsewhere,
+            Self::Suspended => sys::EResult_k_EResultSuspended,
+            Self::Cancelled => sys::EResult_k_EResultCancelled,
+            Self::DataCorruption => sys::EResult_k_EResultDataCorruption,
+            Self::DiskFull => sys::EResult_k_EResultDiskFull,
+            Self::RemoteCallFailed => sys::EResult_k_EResultRemoteCallFailed,
+            Self::PasswordUnset => sys::EResult_k_EResultPasswordUnset,
+            Self::ExternalAccountUnlinked => sys::EResult_k_EResultExternalAccountUnlinked,
+            Self::PSNTicketInvalid => sys::EResult_k_EResultPSNTicketInvalid,
+            Self::ExternalAccountAlreadyLinked => {
+                sys::EResult_k_EResultExternalAccountAlreadyLinked
+            }
+            Self::RemoteFileConflict => sys::EResult_k_EResultRemoteFileConflict,
+            Self::IllegalPassword => sys::EResult_k_EResultIllegalPassword,
+            Self::SameAsPreviousValue => sys::EResult_k_EResultSameAsPreviousValue,
+            Self::AccountLogonDenied => sys::EResult_k_EResultAccountLogonDenied,
+            Self::CannotUseOldPassword => sys::EResult_k_EResultCannotUseOldPassword,
+            Self::InvalidLoginAuthCode => sys::EResult_k_EResultInvalidLoginAuthCode,
+            Self::AccountLogonDeniedNoMail => sys::EResult_k_EResultAccountLogonDeniedNoMail,
+            Self::HardwareNotCapableOfIPT => sys::EResult_k_EResultHardwareNotCapableOfIPT,
+            Self::IPTInitError => sys::EResult_k_EResultIPTInitError,
+            Self::ParentalControlRestricted => sys::EResult_k_EResultParentalControlRestricted,
+            Self::FacebookQueryError => sys::EResult_k_EResultFacebookQueryError,
+            Self::ExpiredLoginAuthCode => sys::EResult_k_EResultExpiredLoginAuthCode,
+            Self::IPLoginRestrictionFailed => sys::EResult_k_EResultIPLoginRestrictionFailed,
+            Self::AccountLockedDown => sys::EResult_k_EResultAccountLockedDown,
+            Self::AccountLogonDeniedVerifiedEmailRequired => {
+                sys::EResult_k_EResultAccountLogonDeniedVerifiedEmailRequired
+            }
+            Self::NoMatchingURL => sys::EResult_k_EResultNoMatchingURL,
+            Self::BadResponse => sys::EResult_k_EResultBadResponse,
+            Self::RequirePasswordReEntry => sys::EResult_k_EResultRequirePasswordReEntry,
+            Self::ValueOutOfRange => sys::EResult_k_EResultValueOutOfRange,
+            Self::UnexpectedError => sys::EResult_k_EResultUnexpectedError,
+            Self::Disabled => sys::EResult_k_EResultDisabled,
+            Self::InvalidCEGSubmission => sys::EResult_k_EResultInvalidCEGSubmission,
+            Self::RestrictedDevice => sys::EResult_k_EResultRestrictedDevice,
+            Self::RegionLocked => sys::EResult_k_EResultRegionLocked,
+            Self::RateLimitExceeded => sys::EResult_k_EResultRateLimitExceeded,
+            Self::AccountLoginDeniedNeedTwoFactor => {
+                sys::EResult_k_EResultAccountLoginDeniedNeedTwoFactor
+            }
+            Self::ItemDeleted => sys::EResult_k_EResultItemDeleted,
+            Self::AccountLoginDeniedThrottle => sys::EResult_k_EResultAccountLoginDeniedThrottle,
+            Self::TwoFactorCodeMismatch => sys::EResult_k_EResultTwoFactorCodeMismatch,
+            Self::TwoFactorActivationCodeMismatch => {
+                sys::EResult_k_EResultTwoFactorActivationCodeMismatch
+            }
+            Self::AccountAssociatedToMultiplePartners => {
+                sys::EResult_k_EResultAccountAssociatedToMultiplePartners
+            }
+            Self::NotModified => sys::EResult_k_EResultNotModified,
+            Self::NoMobileDevice => sys::EResult_k_EResultNoMobileDevice,
+            Self::TimeNotSynced => sys::EResult_k_EResultTimeNotSynced,
+            Self::SmsCodeFailed => sys::EResult_k_EResultSmsCodeFailed,
+            Self::AccountLimitExceeded => sys::EResult_k_EResultAccountLimitExceeded,
+            Self::AccountActivityLimitExceeded => {
+                sys::EResult_k_EResultAccountActivityLimitExceeded
+            }
+            Self::PhoneActivityLimitExceeded => sys::EResult_k_EResultPhoneActivityLimitExceeded,
+            Self::RefundToWallet => sys::EResult_k_EResultRefundToWallet,
+            Self::EmailSendFailure => sys::EResult_k_EResultEmailSendFailure,
+            Self::NotSettled => sys::EResult_k_EResultNotSettled,
+            Self::NeedCaptcha => sys::EResult_k_EResultNeedCaptcha,
+            Self::GSLTDenied => sys::EResult_k_EResultGSLTDenied,
+            Self::GSOwnerDenied => sys::EResult_k_EResultGSOwnerDenied,
+            Self::InvalidItemType => sys::EResult_k_EResultInvalidItemType,
+            Self::IPBanned => sys::EResult_k_EResultIPBanned,
+            Self::GSLTExpired => sys::EResult_k_EResultGSLTExpired,
+            Self::InsufficientFunds => sys::EResult_k_EResultInsufficientFunds,
+            Self::TooManyPending => sys::EResult_k_EResultTooManyPending,
+            Self::NoSiteLicensesFound => sys::EResult_k_EResultNoSiteLicensesFound,
+            Self::WGNetworkSendExceeded => sys::EResult_k_EResultWGNetworkSendExceeded,
+            Self::AccountNotFriends => sys::EResult_k_EResultAccountNotFriends,
+            Self::LimitedUserAccount => sys::EResult_k_EResultLimitedUserAccount,
+            Self::CantRemoveItem => sys::EResult_k_EResultCantRemoveItem,
+            Self::AccountDeleted => sys::EResult_k_EResultAccountDeleted,
+            Self::ExistingUserCancelledLicense => {
+                sys::EResult_k_EResultExistingUserCancelledLicense
+            }
+            Self::CommunityCooldown => sys::EResult_k_EResultCommunityCooldown,
+            Self::NoLauncherSpecified => sys::EResult_k_EResultNoLauncherSpecified,
+            Self::MustAgreeToSSA => sys::EResult_k_EResultMustAgreeToSSA,
+            Self::LauncherMigrated => sys::EResult_k_EResultLauncherMigrated,
+            Self::SteamRealmMismatch => sys::EResult_k_EResultSteamRealmMismatch,
+            Self::InvalidSignature => sys::EResult_k_EResultInvalidSignature,
+            Self::ParseFailure => sys::EResult_k_EResultParseFailure,
+            Self::NoVerifiedPhone => sys::EResult_k_EResultNoVerifiedPhone,
+            Self::InsufficientBattery => sys::EResult_k_EResultInsufficientBattery,
+            Self::ChargerRequired => sys::EResult_k_EResultChargerRequired,
+            Self::CachedCredentialInvalid => sys::EResult_k_EResultCachedCredentialInvalid,
+            Self::NotSupported => sys::EResult_k_EResultNotSupported,
+            Self::FamilySizeLimitExceeded => sys::EResult_k_EResultFamilySizeLimitExceeded,
+            Self::Unknown(code) => *code,
+        }
+    }
+
+    /// Whether the same request may succeed when sent again later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::NoConnection
+                | Self::Busy
+                | Self::Timeout
+                | Self::ServiceUnavailable
+                | Self::Pending
+                | Self::PersistFailed
+                | Self::LockingFailed
+                | Self::ConnectFailed
+                | Self::HandshakeFailed
+                | Self::IOFailure
+                | Self::RemoteDisconnect
+                | Self::ServiceReadOnly
+                | Self::TryAnotherCM
+                | Self::RemoteCallFailed
+                | Self::RateLimitExceeded
+                | Self::TooManyPending
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Error)]
+pub(crate) struct UnknownCallback;
+
+impl Display for UnknownCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown callback type")
+    }
+}
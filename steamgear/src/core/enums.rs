@@ -1,16 +1,104 @@
 use steamgear_sys as sys;
 
 #[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SteamApiInitError {
     #[error("{0}")]
     FailedGeneric(String),
     #[error("{0}")]
     NoSteamClient(String),
-    #[error("{0}")]
-    VersionMismatch(String),
+    /// The Steam client lacks the SDK's version of `interface`, if Steam said which.
+    #[error(
+        "Steam client doesn't support {}: {msg}",
+        .interface.map_or("an interface", |interface| interface.version())
+    )]
+    VersionMismatch {
+        interface: Option<Interface>,
+        msg: String,
+    },
+    #[error("The game was not launched through Steam, Steam is restarting it")]
+    RestartRequired,
+    #[error("Restarting through Steam needs an app id")]
+    MissingAppId,
+    #[error("Server version must be a non-empty string without NUL bytes")]
+    InvalidVersion,
+    #[error("Invalid ports: game port {game}, query port {query}")]
+    InvalidPorts { game: u16, query: u16 },
+    #[error("Server mode must not be Invalid")]
+    InvalidServerMode,
+    #[error("Interface {0:?} is not available here")]
+    UnsupportedInterface(Interface),
+    #[error("Couldn't load the Steamworks library: {0}")]
+    LibraryLoad(String),
+    /// A result code the linked SDK doesn't know about.
+    #[error("Unknown init result {code}: {msg}")]
+    Unknown {
+        code: sys::ESteamAPIInitResult,
+        msg: String,
+    },
+}
+
+/// The interface was used before init or after shutdown.
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[error("Steam API is not initialized")]
+pub struct NotInitialized;
+
+/// Failure to read a string or list out of `ISteamApps`.
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AppsError {
+    #[error(transparent)]
+    NotInitialized(#[from] NotInitialized),
+    #[error("Steam has no value for this")]
+    NotFound,
+    /// The value still didn't fit after growing the buffer to `capacity`.
+    #[error("Value doesn't fit into a buffer of {capacity}")]
+    Truncated { capacity: usize },
+    #[error("Value is not valid UTF-8")]
+    InvalidUtf8,
+    #[error("Steam returned a malformed value")]
+    Malformed,
+    #[error("Couldn't spawn a worker thread")]
+    Spawn,
+}
+
+/// A shipped manifest line that isn't `<sha1 hex> <size> <path>`.
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[error("Malformed manifest line {line}")]
+pub struct ManifestError {
+    pub line: usize,
+}
+
+/// A link that isn't a valid `steam://run` link.
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SteamUrlError {
+    #[error("Not a steam://run link")]
+    NotRunUrl,
+    #[error("Invalid app id {0:?}")]
+    InvalidAppId(String),
+    #[error("Invalid percent encoding")]
+    InvalidEncoding,
+}
+
+/// Why a [`DlcManager`](crate::apps::dlc::DlcManager) install ended without the DLC.
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DlcInstallError {
+    #[error("The user doesn't own the DLC")]
+    NotOwned,
+    #[error("The DLC was uninstalled during the install")]
+    Uninstalled,
+    #[error("Steam API was shut down during the install")]
+    Shutdown,
+    #[error("The download made no progress for too long")]
+    Stalled,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum ServerMode {
     Invalid = sys::EServerMode_eServerModeInvalid as u32,
@@ -18,3 +106,448 @@ pub enum ServerMode {
     Authentication = sys::EServerMode_eServerModeAuthentication as u32,
     AuthenticationAndSecure = sys::EServerMode_eServerModeAuthenticationAndSecure as u32,
 }
+
+/// Steamworks interfaces whose version is checked during init.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interface {
+    Utils,
+    NetworkingUtils,
+    Apps,
+    Controller,
+    Friends,
+    GameSearch,
+    HtmlSurface,
+    Http,
+    Input,
+    Inventory,
+    MatchmakingServers,
+    Matchmaking,
+    MusicRemote,
+    Music,
+    NetworkingMessages,
+    NetworkingSockets,
+    Networking,
+    ParentalSettings,
+    Parties,
+    RemotePlay,
+    RemoteStorage,
+    Screenshots,
+    Ugc,
+    UserStats,
+    User,
+    Video,
+    GameServer,
+    GameServerStats,
+}
+
+impl Interface {
+    /// Every interface a client can request.
+    pub const CLIENT: &'static [Interface] = &[
+        Interface::Utils,
+        Interface::NetworkingUtils,
+        Interface::Apps,
+        Interface::Controller,
+        Interface::Friends,
+        Interface::GameSearch,
+        Interface::HtmlSurface,
+        Interface::Http,
+        Interface::Input,
+        Interface::Inventory,
+        Interface::MatchmakingServers,
+        Interface::Matchmaking,
+        Interface::MusicRemote,
+        Interface::Music,
+        Interface::NetworkingMessages,
+        Interface::NetworkingSockets,
+        Interface::Networking,
+        Interface::ParentalSettings,
+        Interface::Parties,
+        Interface::RemotePlay,
+        Interface::RemoteStorage,
+        Interface::Screenshots,
+        Interface::Ugc,
+        Interface::UserStats,
+        Interface::User,
+        Interface::Video,
+    ];
+
+    /// Every interface a game server can request.
+    pub const SERVER: &'static [Interface] = &[
+        Interface::Utils,
+        Interface::NetworkingUtils,
+        Interface::Http,
+        Interface::Inventory,
+        Interface::NetworkingMessages,
+        Interface::NetworkingSockets,
+        Interface::Networking,
+        Interface::Ugc,
+        Interface::GameServer,
+        Interface::GameServerStats,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum GameIdType {
+    App = sys::CGameID_EGameIDType_k_EGameIDTypeApp as u32,
+    Mod = sys::CGameID_EGameIDType_k_EGameIDTypeGameMod as u32,
+    Shortcut = sys::CGameID_EGameIDType_k_EGameIDTypeShortcut as u32,
+    P2P = sys::CGameID_EGameIDType_k_EGameIDTypeP2P as u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum Universe {
+    Invalid = sys::EUniverse_k_EUniverseInvalid as u32,
+    Public = sys::EUniverse_k_EUniversePublic as u32,
+    Beta = sys::EUniverse_k_EUniverseBeta as u32,
+    Internal = sys::EUniverse_k_EUniverseInternal as u32,
+    Dev = sys::EUniverse_k_EUniverseDev as u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum AccountType {
+    Invalid = sys::EAccountType_k_EAccountTypeInvalid as u32,
+    Individual = sys::EAccountType_k_EAccountTypeIndividual as u32,
+    Multiseat = sys::EAccountType_k_EAccountTypeMultiseat as u32,
+    GameServer = sys::EAccountType_k_EAccountTypeGameServer as u32,
+    AnonGameServer = sys::EAccountType_k_EAccountTypeAnonGameServer as u32,
+    Pending = sys::EAccountType_k_EAccountTypePending as u32,
+    ContentServer = sys::EAccountType_k_EAccountTypeContentServer as u32,
+    Clan = sys::EAccountType_k_EAccountTypeClan as u32,
+    Chat = sys::EAccountType_k_EAccountTypeChat as u32,
+    ConsoleUser = sys::EAccountType_k_EAccountTypeConsoleUser as u32,
+    AnonUser = sys::EAccountType_k_EAccountTypeAnonUser as u32,
+}
+
+/// Why a game server denied or kicked a user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum DenyReason {
+    Invalid = sys::EDenyReason_k_EDenyInvalid as u32,
+    InvalidVersion = sys::EDenyReason_k_EDenyInvalidVersion as u32,
+    Generic = sys::EDenyReason_k_EDenyGeneric as u32,
+    NotLoggedOn = sys::EDenyReason_k_EDenyNotLoggedOn as u32,
+    NoLicense = sys::EDenyReason_k_EDenyNoLicense as u32,
+    Cheater = sys::EDenyReason_k_EDenyCheater as u32,
+    LoggedInElseWhere = sys::EDenyReason_k_EDenyLoggedInElseWhere as u32,
+    UnknownText = sys::EDenyReason_k_EDenyUnknownText as u32,
+    IncompatibleAnticheat = sys::EDenyReason_k_EDenyIncompatibleAnticheat as u32,
+    MemoryCorruption = sys::EDenyReason_k_EDenyMemoryCorruption as u32,
+    IncompatibleSoftware = sys::EDenyReason_k_EDenyIncompatibleSoftware as u32,
+    SteamConnectionLost = sys::EDenyReason_k_EDenySteamConnectionLost as u32,
+    SteamConnectionError = sys::EDenyReason_k_EDenySteamConnectionError as u32,
+    SteamResponseTimedOut = sys::EDenyReason_k_EDenySteamResponseTimedOut as u32,
+    SteamValidationStalled = sys::EDenyReason_k_EDenySteamValidationStalled as u32,
+    SteamOwnerLeftGuestUser = sys::EDenyReason_k_EDenySteamOwnerLeftGuestUser as u32,
+}
+
+/// Outcome of validating a user's auth session ticket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum AuthSessionResponse {
+    Ok = sys::EAuthSessionResponse_k_EAuthSessionResponseOK as u32,
+    UserNotConnectedToSteam =
+        sys::EAuthSessionResponse_k_EAuthSessionResponseUserNotConnectedToSteam as u32,
+    NoLicenseOrExpired = sys::EAuthSessionResponse_k_EAuthSessionResponseNoLicenseOrExpired as u32,
+    VacBanned = sys::EAuthSessionResponse_k_EAuthSessionResponseVACBanned as u32,
+    LoggedInElseWhere = sys::EAuthSessionResponse_k_EAuthSessionResponseLoggedInElseWhere as u32,
+    VacCheckTimedOut = sys::EAuthSessionResponse_k_EAuthSessionResponseVACCheckTimedOut as u32,
+    AuthTicketCanceled = sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketCanceled as u32,
+    AuthTicketInvalidAlreadyUsed =
+        sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketInvalidAlreadyUsed as u32,
+    AuthTicketInvalid = sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketInvalid as u32,
+    PublisherIssuedBan = sys::EAuthSessionResponse_k_EAuthSessionResponsePublisherIssuedBan as u32,
+    AuthTicketNetworkIdentityFailure =
+        sys::EAuthSessionResponse_k_EAuthSessionResponseAuthTicketNetworkIdentityFailure as u32,
+    /// A response the linked SDK doesn't know about.
+    Unknown = u32::MAX,
+}
+
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ApiCallFailure {
+    #[error("Steam client went away")]
+    SteamGone,
+    #[error("Network connection to Steam was lost")]
+    NetworkFailure,
+    #[error("Call handle is invalid")]
+    InvalidHandle,
+    #[error("Call result has a different callback type")]
+    MismatchedCallback,
+    #[error("Call failed for an unknown reason")]
+    Unknown,
+}
+
+/// Failure reported by Steam through an `EResult`.
+///
+/// Results that the linked SDK doesn't know about end up in [`SteamError::Unknown`].
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SteamError {
+    #[error("No result")]
+    None,
+    #[error("Generic failure")]
+    Fail,
+    #[error("No/failed network connection")]
+    NoConnection,
+    #[error("Password/ticket is invalid")]
+    InvalidPassword,
+    #[error("Same user logged in elsewhere")]
+    LoggedInElsewhere,
+    #[error("Protocol version is incorrect")]
+    InvalidProtocolVer,
+    #[error("A parameter is incorrect")]
+    InvalidParam,
+    #[error("File was not found")]
+    FileNotFound,
+    #[error("Called method busy - action not taken")]
+    Busy,
+    #[error("Called object was in an invalid state")]
+    InvalidState,
+    #[error("Name is invalid")]
+    InvalidName,
+    #[error("Email is invalid")]
+    InvalidEmail,
+    #[error("Name is not unique")]
+    DuplicateName,
+    #[error("Access is denied")]
+    AccessDenied,
+    #[error("Operation timed out")]
+    Timeout,
+    #[error("VAC banned")]
+    Banned,
+    #[error("Account not found")]
+    AccountNotFound,
+    #[error("SteamID is invalid")]
+    InvalidSteamID,
+    #[error("The requested service is currently unavailable")]
+    ServiceUnavailable,
+    #[error("The user is not logged on")]
+    NotLoggedOn,
+    #[error("Request is pending (may be in process, or waiting on third party)")]
+    Pending,
+    #[error("Encryption or decryption failed")]
+    EncryptionFailure,
+    #[error("Insufficient privilege")]
+    InsufficientPrivilege,
+    #[error("Limit exceeded")]
+    LimitExceeded,
+    #[error("Access has been revoked (used for revoked guest passes)")]
+    Revoked,
+    #[error("License/Guest pass the user is trying to access is expired")]
+    Expired,
+    #[error("Guest pass has already been redeemed by account, cannot be acked again")]
+    AlreadyRedeemed,
+    #[error("The request is a duplicate and the action has already occurred in the past, ignored this time")]
+    DuplicateRequest,
+    #[error("All the games in this guest pass redemption request are already owned by the user")]
+    AlreadyOwned,
+    #[error("IP address not found")]
+    IPNotFound,
+    #[error("Failed to write change to the data store")]
+    PersistFailed,
+    #[error("Failed to acquire access lock for this operation")]
+    LockingFailed,
+    #[error("Logon session was replaced")]
+    LogonSessionReplaced,
+    #[error("Connection failed")]
+    ConnectFailed,
+    #[error("Handshake failed")]
+    HandshakeFailed,
+    #[error("IO failure")]
+    IOFailure,
+    #[error("Remote end disconnected")]
+    RemoteDisconnect,
+    #[error("Failed to find the shopping cart requested")]
+    ShoppingCartNotFound,
+    #[error("A user didn't allow it")]
+    Blocked,
+    #[error("Target is ignoring sender")]
+    Ignored,
+    #[error("Nothing matching the request found")]
+    NoMatch,
+    #[error("Account is disabled")]
+    AccountDisabled,
+    #[error("This service is not accepting content changes right now")]
+    ServiceReadOnly,
+    #[error("Account doesn't have value, so this feature isn't available")]
+    AccountNotFeatured,
+    #[error("Allowed to take this action, but only because requester is admin")]
+    AdministratorOK,
+    #[error("A version mismatch in content transmitted within the Steam protocol")]
+    ContentVersion,
+    #[error("The current CM can't service the user making a request, user should try another")]
+    TryAnotherCM,
+    #[error("You are already logged in elsewhere, this cached credential login has failed")]
+    PasswordRequiredToKickSession,
+    #[error("You are already logged in elsewhere, you must wait")]
+    AlreadyLoggedInElsewhere,
+    #[error("Long running operation (content download) suspended/paused")]
+    Suspended,
+    #[error("Operation canceled (typically by user: content download)")]
+    Cancelled,
+    #[error("Operation canceled because data is ill formed or unrecoverable")]
+    DataCorruption,
+    #[error("Operation canceled - not enough disk space")]
+    DiskFull,
+    #[error("A remote call or IPC call failed")]
+    RemoteCallFailed,
+    #[error("Password could not be verified as it's unset server side")]
+    PasswordUnset,
+    #[error("External account (PSN, Facebook...) is not linked to a Steam account")]
+    ExternalAccountUnlinked,
+    #[error("PSN ticket was invalid")]
+    PSNTicketInvalid,
+    #[error("External account (PSN, Facebook...) is already linked to some other account, must explicitly request to replace/delete the link first")]
+    ExternalAccountAlreadyLinked,
+    #[error("The sync cannot resume due to a conflict between the local and remote files")]
+    RemoteFileConflict,
+    #[error("The requested new password is not legal")]
+    IllegalPassword,
+    #[error("New value is the same as the old one (secret question and answer)")]
+    SameAsPreviousValue,
+    #[error("Account login denied due to 2nd factor authentication failure")]
+    AccountLogonDenied,
+    #[error("The requested new password was used before")]
+    CannotUseOldPassword,
+    #[error("Account login denied due to auth code invalid")]
+    InvalidLoginAuthCode,
+    #[error("Account login denied due to 2nd factor auth failure - and no mail has been sent - partner site specific")]
+    AccountLogonDeniedNoMail,
+    #[error("Hardware is not capable of IPT")]
+    HardwareNotCapableOfIPT,
+    #[error("IPT initialization failed")]
+    IPTInitError,
+    #[error("Operation failed due to parental control restrictions for current user")]
+    ParentalControlRestricted,
+    #[error("Facebook query returned an error")]
+    FacebookQueryError,
+    #[error("Account login denied due to auth code expired")]
+    ExpiredLoginAuthCode,
+    #[error("Login denied by IP restriction")]
+    IPLoginRestrictionFailed,
+    #[error("Account is locked down")]
+    AccountLockedDown,
+    #[error("Account login denied, a verified email is required")]
+    AccountLogonDeniedVerifiedEmailRequired,
+    #[error("No matching URL")]
+    NoMatchingURL,
+    #[error("Bad response (parse failure, missing field, etc.)")]
+    BadResponse,
+    #[error("The user cannot complete the action until they re-enter their password")]
+    RequirePasswordReEntry,
+    #[error("The value entered is outside the acceptable range")]
+    ValueOutOfRange,
+    #[error("Something happened that we didn't expect to ever happen")]
+    UnexpectedError,
+    #[error("The requested service has been configured to be unavailable")]
+    Disabled,
+    #[error("The set of files submitted to the CEG server is not valid")]
+    InvalidCEGSubmission,
+    #[error("The device being used is not allowed to perform this action")]
+    RestrictedDevice,
+    #[error("The action could not be completed because it is region restricted")]
+    RegionLocked,
+    #[error("Temporary rate limit exceeded, try again later")]
+    RateLimitExceeded,
+    #[error("Need two-factor code to login")]
+    AccountLoginDeniedNeedTwoFactor,
+    #[error("The thing we're trying to access has been deleted")]
+    ItemDeleted,
+    #[error("Login attempt failed, try to throttle response to possible attacker")]
+    AccountLoginDeniedThrottle,
+    #[error("Two factor code mismatch")]
+    TwoFactorCodeMismatch,
+    #[error("Activation code for two-factor didn't match")]
+    TwoFactorActivationCodeMismatch,
+    #[error("Account has been associated with multiple partners")]
+    AccountAssociatedToMultiplePartners,
+    #[error("Data not modified")]
+    NotModified,
+    #[error("The account does not have a mobile device associated with it")]
+    NoMobileDevice,
+    #[error("The time presented is out of range or tolerance")]
+    TimeNotSynced,
+    #[error("SMS code failure (no match, none pending, etc.)")]
+    SmsCodeFailed,
+    #[error("Too many accounts access this resource")]
+    AccountLimitExceeded,
+    #[error("Too many changes to this account")]
+    AccountActivityLimitExceeded,
+    #[error("Too many changes to this phone")]
+    PhoneActivityLimitExceeded,
+    #[error("Cannot refund to payment method, must use wallet")]
+    RefundToWallet,
+    #[error("Cannot send an email")]
+    EmailSendFailure,
+    #[error("Can't perform operation till payment has settled")]
+    NotSettled,
+    #[error("Needs to provide a valid captcha")]
+    NeedCaptcha,
+    #[error("A game server login token owned by this token's owner has been banned")]
+    GSLTDenied,
+    #[error("Game server owner is denied for other reason (account lock, community ban, vac ban, missing phone)")]
+    GSOwnerDenied,
+    #[error("The type of thing we were requested to act on is invalid")]
+    InvalidItemType,
+    #[error("The IP address has been banned from taking this action")]
+    IPBanned,
+    #[error("This token has expired from disuse; can be reset for use")]
+    GSLTExpired,
+    #[error("User doesn't have enough wallet funds to complete the action")]
+    InsufficientFunds,
+    #[error("There are too many of this thing pending already")]
+    TooManyPending,
+    #[error("No site licenses found")]
+    NoSiteLicensesFound,
+    #[error("The WG couldn't send a response because we exceeded max network send size")]
+    WGNetworkSendExceeded,
+    #[error("The user is not mutually friends")]
+    AccountNotFriends,
+    #[error("The user is limited")]
+    LimitedUserAccount,
+    #[error("Item can't be removed")]
+    CantRemoveItem,
+    #[error("Account has been deleted")]
+    AccountDeleted,
+    #[error("A license for this already exists, but cancelled")]
+    ExistingUserCancelledLicense,
+    #[error("Access is denied because of a community cooldown (probably from support profile data resets)")]
+    CommunityCooldown,
+    #[error("No launcher was specified, but a launcher was needed to choose correct realm for operation")]
+    NoLauncherSpecified,
+    #[error("User must agree to China SSA or global SSA before login")]
+    MustAgreeToSSA,
+    #[error(
+        "The specified launcher type is no longer supported; the user should be directed elsewhere"
+    )]
+    LauncherMigrated,
+    #[error("The user's realm does not match the realm of the requested resource")]
+    SteamRealmMismatch,
+    #[error("Signature check did not match")]
+    InvalidSignature,
+    #[error("Failed to parse input")]
+    ParseFailure,
+    #[error("Account does not have a verified phone number")]
+    NoVerifiedPhone,
+    #[error("User device doesn't have enough battery charge currently to complete the action")]
+    InsufficientBattery,
+    #[error("The operation requires a charger to be plugged in, which wasn't present")]
+    ChargerRequired,
+    #[error("Cached credential was invalid - user must reauthenticate")]
+    CachedCredentialInvalid,
+    #[error("The data being accessed is not supported by this API")]
+    NotSupported,
+    #[error("Reached the maximum size of the family")]
+    FamilySizeLimitExceeded,
+    #[error("Unknown result {0}")]
+    Unknown(sys::EResult),
+}
@@ -0,0 +1,7 @@
+//! Callback tables generated by `build.rs` from the SDK's `steam_api.json`.
+
+use steamgear_sys as sys;
+
+use super::{callback::SteamCallbackStruct, conv::UnknownCallback};
+
+include!(concat!(env!("OUT_DIR"), "/callbacks.rs"));
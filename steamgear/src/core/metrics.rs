@@ -0,0 +1,225 @@
+//! Instrumentation of `run_callbacks`.
+//!
+//! Every frame runs in a `run_callbacks` span and every dispatched callback in a child
+//! `callback` span, both at debug level. Install a hook with
+//! [`SteamApiClient::set_metrics_hook`](super::client::SteamApiClient::set_metrics_hook)
+//! to watch the dispatcher queues, a full queue stalls `run_callbacks` or drops callbacks.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use tracing::{field::Empty, span::EnteredSpan, Span};
+
+use super::callback::CallbackType;
+
+/// Totals of one `run_callbacks` frame, handed to the metrics hook.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameMetrics {
+    pub callbacks: usize,
+    pub call_results: usize,
+    /// Callbacks nobody was subscribed to.
+    pub unhandled: usize,
+    /// Callbacks lost because a subscriber lagged behind or went away.
+    pub dropped: usize,
+    /// Call results still awaited after the frame.
+    pub pending_call_results: usize,
+    pub duration: Duration,
+    pub queues: Vec<QueueDepth>,
+}
+
+/// Fill level of one dispatcher after a frame.
+///
+/// Deserializing takes the SDK struct name from `callback_id` again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QueueDepth {
+    pub callback_id: u32,
+    /// SDK struct name, `"unknown"` for ids the SDK doesn't know.
+    pub callback: &'static str,
+    pub subscribers: usize,
+    /// Callbacks waiting in the fullest subscriber queue.
+    pub queued: usize,
+    pub capacity: usize,
+}
+
+impl QueueDepth {
+    pub(crate) fn new(callback_id: u32) -> Self {
+        Self {
+            callback_id,
+            callback: callback_name(callback_id),
+            subscribers: 0,
+            queued: 0,
+            capacity: 0,
+        }
+    }
+
+    /// Accounts for a subscriber queue with `queued` of `capacity` callbacks.
+    pub(crate) fn add(&mut self, queued: usize, capacity: usize) {
+        self.subscribers += 1;
+        self.queued = self.queued.max(queued);
+        self.capacity = self.capacity.max(capacity);
+    }
+}
+
+type Hook = Arc<dyn Fn(&FrameMetrics) + Send + Sync>;
+
+#[derive(Default)]
+pub(crate) struct MetricsHook(Mutex<Option<Hook>>);
+
+impl std::fmt::Debug for MetricsHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MetricsHook")
+            .field(&self.0.lock().is_some())
+            .finish()
+    }
+}
+
+impl MetricsHook {
+    pub(crate) fn set(&self, hook: Option<Hook>) {
+        *self.0.lock() = hook;
+    }
+
+    fn get(&self) -> Option<Hook> {
+        self.0.lock().clone()
+    }
+}
+
+/// Outcome of handing one callback to its subscribers.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Dispatched {
+    pub(crate) delivered: usize,
+    pub(crate) dropped: usize,
+}
+
+impl Dispatched {
+    /// Nobody was subscribed.
+    pub(crate) fn is_unhandled(&self) -> bool {
+        self.delivered == 0 && self.dropped == 0
+    }
+}
+
+impl std::ops::Add for Dispatched {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            delivered: self.delivered + rhs.delivered,
+            dropped: self.dropped + rhs.dropped,
+        }
+    }
+}
+
+/// Tallies a `run_callbacks` frame while it runs.
+pub(crate) struct Frame {
+    span: Span,
+    started: Instant,
+    metrics: FrameMetrics,
+}
+
+impl Frame {
+    pub(crate) fn start(pipe: &'static str) -> Self {
+        Self {
+            span: tracing::debug_span!(
+                "run_callbacks",
+                pipe,
+                callbacks = Empty,
+                call_results = Empty,
+                unhandled = Empty,
+                dropped = Empty,
+                pending_call_results = Empty,
+            ),
+            started: Instant::now(),
+            metrics: FrameMetrics::default(),
+        }
+    }
+
+    pub(crate) fn enter(&self) -> EnteredSpan {
+        self.span.clone().entered()
+    }
+
+    pub(crate) fn call_result(&mut self) {
+        self.metrics.call_results += 1;
+    }
+
+    /// Runs `dispatch` for a callback inside its own span.
+    pub(crate) fn callback(&mut self, id: u32, size: usize, dispatch: impl FnOnce() -> Dispatched) {
+        let span = tracing::debug_span!(
+            "callback",
+            id,
+            callback = callback_name(id),
+            size,
+            latency_us = Empty,
+            delivered = Empty,
+            dropped = Empty,
+        );
+        let _entered = span.enter();
+
+        let started = Instant::now();
+        let dispatched = dispatch();
+        span.record("latency_us", started.elapsed().as_micros() as u64);
+        span.record("delivered", dispatched.delivered);
+        span.record("dropped", dispatched.dropped);
+
+        self.metrics.callbacks += 1;
+        self.metrics.dropped += dispatched.dropped;
+        if dispatched.is_unhandled() {
+            self.metrics.unhandled += 1;
+        }
+    }
+
+    /// Closes the span and reports to `hook`, `queues` is only collected when one is set.
+    pub(crate) fn finish(
+        mut self,
+        pending_call_results: usize,
+        hook: &MetricsHook,
+        queues: impl FnOnce() -> Vec<QueueDepth>,
+    ) {
+        let metrics = &mut self.metrics;
+        metrics.pending_call_results = pending_call_results;
+        metrics.duration = self.started.elapsed();
+
+        self.span.record("callbacks", metrics.callbacks);
+        self.span.record("call_results", metrics.call_results);
+        self.span.record("unhandled", metrics.unhandled);
+        self.span.record("dropped", metrics.dropped);
+        self.span
+            .record("pending_call_results", pending_call_results);
+
+        if let Some(hook) = hook.get() {
+            metrics.queues = queues();
+            hook(metrics);
+        }
+    }
+}
+
+fn callback_name(id: u32) -> &'static str {
+    CallbackType::try_from(id)
+        .map(|callback_type| callback_type.name())
+        .unwrap_or("unknown")
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for QueueDepth {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Fields {
+            callback_id: u32,
+            subscribers: usize,
+            queued: usize,
+            capacity: usize,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        Ok(Self {
+            callback_id: fields.callback_id,
+            callback: callback_name(fields.callback_id),
+            subscribers: fields.subscribers,
+            queued: fields.queued,
+            capacity: fields.capacity,
+        })
+    }
+}
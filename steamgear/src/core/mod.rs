@@ -2,13 +2,15 @@ pub mod callback;
 pub mod client;
 pub mod conv;
 pub mod enums;
+mod generated;
+pub mod metrics;
+#[cfg(feature = "serde")]
+mod serde_impls;
 pub mod server;
+mod steam_id;
 pub mod structs;
 
 use enums::SteamApiInitError;
-use std::sync::atomic::AtomicU8;
-
-static STEAM_INIT_STATUS: AtomicU8 = AtomicU8::new(SteamApiState::Stopped as u8);
 
 pub(crate) trait SteamApiInterface: Send + Sync {
     type InitArgs;
@@ -17,11 +19,13 @@ pub(crate) trait SteamApiInterface: Send + Sync {
     where
         Self: Sized;
     fn shutdown(&self);
+    fn run_callbacks(&self);
+    fn is_running(&self) -> bool;
 }
 
 #[derive(Clone, Copy, Debug)]
 #[repr(u8)]
-enum SteamApiState {
+pub(crate) enum SteamApiState {
     Stopped,
     Init,
     RunCallbacks,
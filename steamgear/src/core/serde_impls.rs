@@ -0,0 +1,86 @@
+//! 64-bit ids are strings in human readable formats, JavaScript numbers would round them.
+
+use std::fmt;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::structs::{GameId, SteamId};
+
+impl Serialize for SteamId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_id(self.0, serializer)
+    }
+}
+
+/// Also accepts plain numbers and every form [`SteamId::from_str`](std::str::FromStr) parses.
+impl<'de> Deserialize<'de> for SteamId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserialize_id(deserializer, "a Steam ID")? {
+            Id::Number(id) => Ok(SteamId(id)),
+            Id::Text(text) => text.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for GameId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_id(self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserialize_id(deserializer, "a game id")? {
+            Id::Number(id) => Ok(GameId(id)),
+            Id::Text(text) => text.trim().parse().map(GameId).map_err(de::Error::custom),
+        }
+    }
+}
+
+fn serialize_id<S: Serializer>(id: u64, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&id)
+    } else {
+        serializer.serialize_u64(id)
+    }
+}
+
+enum Id {
+    Number(u64),
+    Text(String),
+}
+
+fn deserialize_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    expecting: &'static str,
+) -> Result<Id, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(IdVisitor(expecting))
+    } else {
+        u64::deserialize(deserializer).map(Id::Number)
+    }
+}
+
+struct IdVisitor(&'static str);
+
+impl de::Visitor<'_> for IdVisitor {
+    type Value = Id;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Id::Number(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map(Id::Number)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Id::Text(v.to_string()))
+    }
+}
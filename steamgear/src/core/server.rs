@@ -1,223 +1,359 @@
-use super::callback::{CallbackDispatcher, CallbackType, CallbackTyped, ClientCallbackContainer};
-use super::enums::{ServerMode, SteamApiInitError};
-use super::structs::AppId;
-use super::{SteamApiInterface, SteamApiState, STEAM_INIT_STATUS};
+use std::sync::Arc;
 
+use super::callback::{
+    CallResult, CallbackDispatcher, CallbackStruct, CallbackType, ServerCallbackContainer,
+};
+use super::enums::SteamApiInitError;
+use super::metrics::{Dispatched, Frame, FrameMetrics, MetricsHook};
+use super::{SteamApiInterface, SteamApiState};
+
+use crate::api::ServerBuilder;
+use crate::backend::recording::{self, Recorder};
+use crate::backend::{BackendEvent, CallbackMsg, RecordedPipe, SteamBackend};
+use crate::game_server::SteamGameServer;
 use crate::utils::callbacks::SteamShutdown;
+use crate::utils::server::SteamUtilsServer;
 
+use futures_core::Stream;
+use parking_lot::Mutex;
 use steamgear_sys as sys;
-use tracing::{error, warn};
+use tracing::{debug, warn};
 
 #[derive(Debug)]
 pub struct SteamApiServer {
     pipe: sys::HSteamPipe,
 
-    //TODO: Replace
-    pub(crate) callback_container: ClientCallbackContainer,
+    backend: Arc<dyn SteamBackend>,
+    recorder: Mutex<Option<Recorder>>,
+    metrics_hook: MetricsHook,
+    callback_container: Arc<ServerCallbackContainer>,
+    steam_utils: SteamUtilsServer,
+    steam_game_server: SteamGameServer,
 }
 
-unsafe impl Send for SteamApiServer {}
-unsafe impl Sync for SteamApiServer {}
-
 impl SteamApiServer {
-    pub fn release_current_thread_memory(&self) {
-        unsafe {
-            sys::SteamAPI_ReleaseCurrentThreadMemory();
+    /// Fails pending call results that take longer than `timeout` with
+    /// [`CallResultError::Timeout`](super::callback::CallResultError::Timeout).
+    /// Only affects calls registered afterwards, `None` waits forever.
+    ///
+    /// The timeout is enforced by `run_callbacks`, use
+    /// [`CallResult::with_timeout`](super::callback::CallResult::with_timeout) for a call
+    /// awaited without pumping callbacks.
+    pub fn set_call_result_timeout(&self, timeout: Option<std::time::Duration>) {
+        *self.callback_container.call_results.timeout.lock() = timeout;
+    }
+
+    /// Writes every callback and call result `run_callbacks` hands out to `path`,
+    /// replacing a recording in progress. Play it back with
+    /// [`FakeBackend::replay`](crate::backend::FakeBackend::replay).
+    pub fn start_recording(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let recorder = Recorder::create(path.as_ref(), RecordedPipe::Server)?;
+
+        match self.recorder.lock().replace(recorder) {
+            Some(previous) => previous.finish(),
+            None => Ok(()),
         }
     }
 
+    pub fn stop_recording(&self) -> std::io::Result<()> {
+        match self.recorder.lock().take() {
+            Some(recorder) => recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Calls `hook` after every `run_callbacks` frame, see [`metrics`](super::metrics).
+    ///
+    /// Runs on the thread calling `run_callbacks`, keep it short.
+    pub fn set_metrics_hook(&self, hook: impl Fn(&FrameMetrics) + Send + Sync + 'static) {
+        self.metrics_hook.set(Some(Arc::new(hook)));
+    }
+
+    pub fn clear_metrics_hook(&self) {
+        self.metrics_hook.set(None);
+    }
+
+    pub fn release_current_thread_memory(&self) {
+        self.backend.release_current_thread_memory();
+    }
+
+    /// Called from a callback handler or the metrics hook, [`SteamApi::shutdown`] shuts
+    /// down once the current frame is done.
+    ///
+    /// [`SteamApi::shutdown`]: crate::api::SteamApi::shutdown
     pub fn run_callbacks(&self) {
-        unsafe {
-            sys::SteamAPI_ManualDispatch_RunFrame(self.pipe);
-            let mut callback = std::mem::zeroed();
-
-            if STEAM_INIT_STATUS
-                .compare_exchange(
-                    SteamApiState::Init as u8,
-                    SteamApiState::RunCallbacks as u8,
-                    std::sync::atomic::Ordering::SeqCst,
-                    std::sync::atomic::Ordering::Relaxed,
-                )
-                .is_err()
-            {
-                return;
-            }
+        let state = &self.backend.state().server;
+        // No frame once shut down, the pipe is released
+        let Some(frame_guard) = state.begin_frame() else {
+            return;
+        };
 
-            while sys::SteamAPI_ManualDispatch_GetNextCallback(self.pipe, &mut callback) {
-                if callback.m_iCallback as u32 == sys::SteamAPICallCompleted_t_k_iCallback as u32 {
-                    let apicall =
-                        &*(callback.m_pubParam as *const _ as *const sys::SteamAPICallCompleted_t);
-                    let id = apicall.m_hAsyncCall;
-
-                    if let Some((_, sender)) = self.callback_container.call_results.remove(&id) {
-                        match sender.send_blocking(callback) {
-                            Ok(_) => {
-                                tracing::debug!("Sent call result with id: {}", id)
-                            }
-                            Err(_) => {
-                                tracing::debug!(
-                                    "CallResult with id {} have received, but receiver is broken",
-                                    id
-                                )
-                            }
-                        }
-                    }
-                } else {
-                    self.proceed_callback(callback);
+        self.backend.run_frame(self.pipe);
+
+        let mut frame = Frame::start("server");
+        let span = frame.enter();
+
+        let mut recorder = self.recorder.lock();
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.next_frame();
+        }
+
+        while !state.shutdown_pending() {
+            let Some(event) = self.backend.next_event(self.pipe) else {
+                break;
+            };
+            recording::record(&mut recorder, &event);
+
+            match event {
+                BackendEvent::CallResult(result) => {
+                    frame.call_result();
+                    self.callback_container.call_results.complete(result)
+                }
+                BackendEvent::Callback(callback) => {
+                    frame.callback(callback.id, callback.data.len(), || {
+                        self.proceed_callback(&callback)
+                    })
                 }
-                sys::SteamAPI_ManualDispatch_FreeLastCallback(self.pipe);
             }
+        }
 
-            let _ = STEAM_INIT_STATUS.compare_exchange(
-                SteamApiState::RunCallbacks as u8,
-                SteamApiState::Init as u8,
-                std::sync::atomic::Ordering::SeqCst,
-                std::sync::atomic::Ordering::Relaxed,
-            );
+        drop(recorder);
+
+        self.callback_container
+            .call_results
+            .expire(std::time::Instant::now());
+
+        frame.finish(
+            self.callback_container.call_results.pending(),
+            &self.metrics_hook,
+            || self.callback_container.queue_depths(),
+        );
+        drop(span);
+        drop(frame_guard);
+
+        if state.take_shutdown_pending() {
+            SteamApiInterface::shutdown(self);
         }
     }
 }
 
 impl SteamApiServer {
-    fn init_internal(
-        addr: &std::net::SocketAddrV4,
-        query_port: u16,
-        mode: ServerMode,
-        version: &(u8, u8, u8, u8),
-    ) -> Result<(), SteamApiInitError> {
-        let versions = Self::get_server_interfaces();
-        let versions: Vec<u8> = versions.into_iter().flatten().cloned().collect();
-        let versions = versions.as_ptr() as *const ::std::os::raw::c_char;
-
-        let mut err_msg: sys::SteamErrMsg = [0; 1024];
-
-        let ip = u32::from_be_bytes(addr.ip().octets());
-        let version = format!("{}.{}.{}.{}", version.0, version.1, version.2, version.3).as_ptr();
-
-        let result = unsafe {
-            sys::SteamInternal_GameServer_Init_V2(
-                ip,
-                addr.port(),
-                query_port,
-                mode as _,
-                version as *const _,
-                versions,
-                &mut err_msg,
-            )
+    pub fn game_server(&self) -> &SteamGameServer {
+        &self.steam_game_server
+    }
+
+    pub fn utils(&self) -> &SteamUtilsServer {
+        &self.steam_utils
+    }
+}
+
+impl SteamApiServer {
+    /// Every callback with `id`, as the bytes Steam sent, including callbacks this crate
+    /// doesn't wrap.
+    pub fn on_raw_callback(&self, id: u32) -> impl Stream<Item = Vec<u8>> {
+        self.callback_container.raw_callbacks.register_bytes(id)
+    }
+
+    /// Every callback with `T::ID`, read as `T`. Callbacks whose size doesn't match `T` are
+    /// logged and skipped.
+    pub fn on_callback<T: CallbackStruct>(&self) -> impl Stream<Item = T> {
+        self.callback_container.raw_callbacks.register_struct::<T>()
+    }
+
+    /// Waits for a call made through the flat API, `callback_id` is the expected result type.
+    ///
+    /// The call is registered right away, a result dispatched before the first poll
+    /// isn't lost.
+    pub fn raw_call_result(
+        &self,
+        call: sys::SteamAPICall_t,
+        callback_id: u32,
+    ) -> CallResult<'_, Vec<u8>> {
+        let _guard = match self.backend.state().server.enter() {
+            Ok(guard) => guard,
+            Err(err) => return CallResult::ready(Err(err.into())),
         };
 
-        match result {
-            steamgear_sys::ESteamAPIInitResult_k_ESteamAPIInitResult_OK => Ok(()),
-            _ => Err(SteamApiInitError::from_raw(result, err_msg)),
-        }
+        self.callback_container
+            .call_results
+            .register_raw(call, callback_id)
     }
 
-    unsafe fn proceed_callback(&self, callback: sys::CallbackMsg_t) {
-        let Ok(callback_type): Result<CallbackType, _> = callback
-            .m_iCallback
-            .try_into()
-            .map(|ty: u32| ty.try_into())
-            .unwrap()
-        else {
-            warn!("Got unknown callback type: {}", callback.m_iCallback);
-            return;
+    /// Same as [`SteamApiServer::raw_call_result`], read as `T`.
+    pub fn call_result<T: CallbackStruct>(&self, call: sys::SteamAPICall_t) -> CallResult<'_, T> {
+        let _guard = match self.backend.state().server.enter() {
+            Ok(guard) => guard,
+            Err(err) => return CallResult::ready(Err(err.into())),
         };
 
-        let is_client = callback_type.is_for_server();
+        self.callback_container
+            .call_results
+            .register_struct::<T>(call)
+    }
+}
+
+impl SteamApiServer {
+    fn proceed_callback(&self, callback: &CallbackMsg) -> Dispatched {
+        let raw = self
+            .callback_container
+            .raw_callbacks
+            .proceed(callback.id, &callback.data);
 
-        match (callback_type, is_client) {
-            (CallbackType::SteamShutdown, _) => {
-                let value = SteamShutdown::from_raw(SteamShutdown::from_ptr(callback.m_pubParam));
-                self.callback_container
-                    .steam_shutdown_callback
-                    .proceed(value);
-            }
-            (callback_type, true) => {
-                error!(
-                    "Bug in steamgear. Didn't handle server callback: {:?}",
-                    callback_type
-                );
+        let Ok(callback_type) = CallbackType::try_from(callback.id) else {
+            if raw.is_unhandled() {
+                warn!("Got unknown callback type: {}", callback.id);
             }
-            _ => {}
+            return raw;
+        };
+
+        let dispatched = match callback_type {
+            CallbackType::SteamShutdown => self
+                .callback_container
+                .steam_shutdown_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::SteamServersConnected => self
+                .callback_container
+                .servers_connected_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::SteamServerConnectFailure => self
+                .callback_container
+                .server_connect_failure_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::SteamServersDisconnected => self
+                .callback_container
+                .servers_disconnected_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::ValidateAuthTicketResponse => self
+                .callback_container
+                .validate_auth_ticket_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::GSClientApprove => self
+                .callback_container
+                .client_approve_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::GSClientDeny => self
+                .callback_container
+                .client_deny_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::GSClientKick => self
+                .callback_container
+                .client_kick_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::GSPolicyResponse => self
+                .callback_container
+                .policy_response_callback
+                .proceed_bytes(&callback.data),
+            CallbackType::GSStatsReceived => self
+                .callback_container
+                .stats_received_callback
+                .proceed_bytes(&callback.data),
+            _ => Dispatched::default(),
+        };
+
+        let dispatched = raw + dispatched;
+        if dispatched.is_unhandled() {
+            debug!(
+                "Nobody subscribed to server callback {}",
+                callback_type.name()
+            );
         }
-    }
 
-    const fn get_server_interfaces() -> [&'static [u8]; 11] {
-        [
-            sys::STEAMUTILS_INTERFACE_VERSION,
-            sys::STEAMNETWORKINGUTILS_INTERFACE_VERSION,
-            sys::STEAMGAMESERVER_INTERFACE_VERSION,
-            sys::STEAMGAMESERVERSTATS_INTERFACE_VERSION,
-            sys::STEAMHTTP_INTERFACE_VERSION,
-            sys::STEAMINVENTORY_INTERFACE_VERSION,
-            sys::STEAMNETWORKING_INTERFACE_VERSION,
-            sys::STEAMNETWORKINGMESSAGES_INTERFACE_VERSION,
-            sys::STEAMNETWORKINGSOCKETS_INTERFACE_VERSION,
-            sys::STEAMUGC_INTERFACE_VERSION,
-            b"\0",
-        ]
+        dispatched
     }
 }
 
 impl SteamApiInterface for SteamApiServer {
-    type InitArgs = (
-        Option<AppId>,
-        std::net::SocketAddrV4,
-        u16,
-        ServerMode,
-        (u8, u8, u8, u8),
-    );
+    type InitArgs = ServerBuilder;
 
     fn init(args: Self::InitArgs) -> Result<Self, SteamApiInitError>
     where
         Self: Sized,
     {
-        let (app_id, addr, query_port, mode, version) = args;
-        unsafe {
-            if let Some(app_id) = app_id {
-                let app_id = app_id.0.to_string();
-                std::env::set_var("SteamAppId", &app_id);
-                std::env::set_var("SteamGameId", &app_id);
-            }
+        let version = args.version_cstring()?;
+        let addr = args.addr();
+        let ServerBuilder {
+            backend,
+            app_id,
+            query_port,
+            mode,
+            interfaces,
+            ..
+        } = args;
 
-            let pipe = sys::SteamGameServer_GetHSteamPipe();
-
-            if STEAM_INIT_STATUS
-                .compare_exchange(
-                    SteamApiState::Stopped as u8,
-                    SteamApiState::Init as u8,
-                    std::sync::atomic::Ordering::AcqRel,
-                    std::sync::atomic::Ordering::SeqCst,
-                )
-                .is_ok()
+        if backend
+            .state()
+            .server
+            .status
+            .compare_exchange(
+                SteamApiState::Stopped as u8,
+                SteamApiState::Init as u8,
+                std::sync::atomic::Ordering::AcqRel,
+                std::sync::atomic::Ordering::SeqCst,
+            )
+            .is_ok()
+        {
+            if let Err(err) =
+                backend.server_init(app_id, addr, query_port, mode, &version, &interfaces)
             {
-                Self::init_internal(&addr, query_port, mode, &version)?;
+                backend.state().server.status.store(
+                    SteamApiState::Stopped as u8,
+                    std::sync::atomic::Ordering::Release,
+                );
 
-                sys::SteamAPI_ManualDispatch_Init();
+                return Err(err);
             }
-
-            Ok(Self {
-                pipe,
-                callback_container: Default::default(),
-            })
         }
+
+        let pipe = backend.server_pipe();
+        let callback_container = Default::default();
+
+        Ok(Self {
+            pipe,
+            steam_utils: SteamUtilsServer::new(
+                Arc::clone(&backend),
+                Arc::clone(&callback_container),
+            ),
+            steam_game_server: SteamGameServer::new(
+                Arc::clone(&backend),
+                Arc::clone(&callback_container),
+            ),
+
+            backend,
+            recorder: Default::default(),
+            metrics_hook: Default::default(),
+            callback_container,
+        })
     }
 
     fn shutdown(&self) {
+        let state = &self.backend.state().server;
+        if state.defer_shutdown() {
+            debug!("Shutdown called inside run_callbacks, finishing the frame first");
+            return;
+        }
+
+        let status = &state.status;
+
         loop {
-            let current = STEAM_INIT_STATUS.load(std::sync::atomic::Ordering::Acquire);
+            let current = status.load(std::sync::atomic::Ordering::Acquire);
+
+            if current == SteamApiState::RunCallbacks as u8 {
+                // Let the frame in flight finish before the interfaces go away
+                std::thread::yield_now();
+                continue;
+            }
 
             if current != SteamApiState::Stopped as u8 {
-                match STEAM_INIT_STATUS.compare_exchange(
+                match status.compare_exchange(
                     current,
                     SteamApiState::Stopped as u8,
                     std::sync::atomic::Ordering::SeqCst,
                     std::sync::atomic::Ordering::Relaxed,
                 ) {
                     Ok(_) => {
-                        unsafe {
-                            sys::SteamGameServer_Shutdown();
-                        }
+                        // Wait for interface calls in flight on other threads
+                        let _calls = self.backend.state().server.calls.write();
+                        self.backend.server_shutdown();
 
                         break;
                     }
@@ -228,8 +364,23 @@ impl SteamApiInterface for SteamApiServer {
             }
         }
 
+        self.callback_container.call_results.cancel();
         self.callback_container
             .steam_shutdown_callback
-            .proceed(SteamShutdown)
+            .proceed(SteamShutdown);
+        self.callback_container.close();
+    }
+
+    fn run_callbacks(&self) {
+        Self::run_callbacks(self)
+    }
+
+    fn is_running(&self) -> bool {
+        self.backend
+            .state()
+            .server
+            .status
+            .load(std::sync::atomic::Ordering::Acquire)
+            != SteamApiState::Stopped as u8
     }
 }
@@ -0,0 +1,321 @@
+use std::{fmt::Display, ops::Deref, str::FromStr};
+
+use steamgear_sys as sys;
+
+use super::enums::{AccountType, Universe};
+
+const ACCOUNT_ID_MASK: u64 = 0xFFFF_FFFF;
+const INSTANCE_SHIFT: u64 = 32;
+const INSTANCE_MASK: u64 = 0x000F_FFFF;
+const ACCOUNT_TYPE_SHIFT: u64 = 52;
+const ACCOUNT_TYPE_MASK: u64 = 0xF;
+const UNIVERSE_SHIFT: u64 = 56;
+
+/// Instance of a user logged in from the desktop client.
+const DESKTOP_INSTANCE: u32 = 1;
+const WEB_INSTANCE: u32 = 4;
+
+const COMMUNITY_URL: &str = "https://steamcommunity.com";
+
+/// 64-bit Steam ID, laid out like the SDK's `CSteamID`:
+/// account id (32 bits), instance (20 bits), account type (4 bits) and universe (8 bits).
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct SteamId(pub u64);
+
+impl SteamId {
+    pub fn new(
+        account_id: u32,
+        instance: u32,
+        account_type: AccountType,
+        universe: Universe,
+    ) -> Self {
+        Self(
+            account_id as u64
+                | (instance as u64 & INSTANCE_MASK) << INSTANCE_SHIFT
+                | (account_type as u64 & ACCOUNT_TYPE_MASK) << ACCOUNT_TYPE_SHIFT
+                | (universe as u64) << UNIVERSE_SHIFT,
+        )
+    }
+
+    /// A user logged in from the desktop client.
+    pub fn individual(universe: Universe, account_id: u32) -> Self {
+        Self::new(
+            account_id,
+            DESKTOP_INSTANCE,
+            AccountType::Individual,
+            universe,
+        )
+    }
+
+    pub fn clan(universe: Universe, account_id: u32) -> Self {
+        Self::new(account_id, 0, AccountType::Clan, universe)
+    }
+
+    /// A persistent game server, logged in with a game server login token.
+    pub fn game_server(universe: Universe, account_id: u32) -> Self {
+        Self::new(account_id, 0, AccountType::GameServer, universe)
+    }
+
+    /// The blank ID an anonymous game server logs in with, Steam assigns the account id.
+    pub fn anon_game_server(universe: Universe) -> Self {
+        Self::new(0, 0, AccountType::AnonGameServer, universe)
+    }
+
+    /// The blank ID an anonymous user logs in with.
+    pub fn anon_user(universe: Universe) -> Self {
+        Self::new(0, 0, AccountType::AnonUser, universe)
+    }
+
+    pub fn account_id(&self) -> u32 {
+        (self.0 & ACCOUNT_ID_MASK) as u32
+    }
+
+    pub fn instance(&self) -> u32 {
+        ((self.0 >> INSTANCE_SHIFT) & INSTANCE_MASK) as u32
+    }
+
+    pub fn account_type(&self) -> AccountType {
+        AccountType::from_raw(
+            ((self.0 >> ACCOUNT_TYPE_SHIFT) & ACCOUNT_TYPE_MASK) as sys::EAccountType,
+        )
+    }
+
+    pub fn universe(&self) -> Universe {
+        Universe::from_raw(self.raw_universe() as sys::EUniverse)
+    }
+
+    fn raw_universe(&self) -> u32 {
+        (self.0 >> UNIVERSE_SHIFT) as u32
+    }
+
+    /// Same rules as `CSteamID::IsValid`.
+    pub fn is_valid(&self) -> bool {
+        if self.universe() == Universe::Invalid {
+            return false;
+        }
+
+        match self.account_type() {
+            AccountType::Invalid => false,
+            AccountType::Individual => self.account_id() != 0 && self.instance() <= WEB_INSTANCE,
+            AccountType::Clan => self.account_id() != 0 && self.instance() == 0,
+            AccountType::GameServer => self.account_id() != 0,
+            _ => true,
+        }
+    }
+
+    /// Legacy `STEAM_X:Y:Z` form, only defined for individual accounts.
+    ///
+    /// The public universe is rendered as `STEAM_0` like most games do.
+    pub fn steam2(&self) -> Option<String> {
+        if self.account_type() != AccountType::Individual {
+            return None;
+        }
+
+        let universe = match self.universe() {
+            Universe::Public => 0,
+            _ => self.raw_universe(),
+        };
+
+        Some(format!(
+            "STEAM_{}:{}:{}",
+            universe,
+            self.account_id() & 1,
+            self.account_id() >> 1
+        ))
+    }
+
+    /// `[U:1:2468]` form, as printed by the Steam client and game server consoles.
+    pub fn steam3(&self) -> String {
+        let account_type = self.account_type();
+        let instance = self.instance();
+
+        let letter = match account_type {
+            AccountType::Chat
+                if instance & sys::EChatSteamIDInstanceFlags_k_EChatInstanceFlagClan as u32
+                    != 0 =>
+            {
+                'c'
+            }
+            AccountType::Chat
+                if instance & sys::EChatSteamIDInstanceFlags_k_EChatInstanceFlagLobby as u32
+                    != 0 =>
+            {
+                'L'
+            }
+            account_type => account_type.steam3_letter(),
+        };
+
+        let show_instance = match account_type {
+            AccountType::AnonGameServer | AccountType::Multiseat => true,
+            AccountType::Individual => instance != DESKTOP_INSTANCE,
+            _ => false,
+        };
+
+        if show_instance {
+            format!(
+                "[{}:{}:{}:{}]",
+                letter,
+                self.raw_universe(),
+                self.account_id(),
+                instance
+            )
+        } else {
+            format!("[{}:{}:{}]", letter, self.raw_universe(), self.account_id())
+        }
+    }
+
+    /// Steam Community page of a user or group.
+    pub fn community_url(&self) -> Option<String> {
+        match self.account_type() {
+            AccountType::Individual => Some(format!("{}/profiles/{}", COMMUNITY_URL, self.0)),
+            AccountType::Clan => Some(format!("{}/gid/{}", COMMUNITY_URL, self.0)),
+            _ => None,
+        }
+    }
+
+    fn parse_steam2(s: &str) -> Result<Self, SteamIdParseError> {
+        let mut parts = s.splitn(3, ':');
+        let (Some(universe), Some(auth_server), Some(account)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(SteamIdParseError::Format);
+        };
+
+        let universe = match parse_number::<u8>(universe)? {
+            0 => Universe::Public,
+            universe => Universe::from_raw(universe as sys::EUniverse),
+        };
+        let auth_server = parse_number::<u32>(auth_server)?;
+        let account = parse_number::<u32>(account)?;
+
+        if auth_server > 1 {
+            return Err(SteamIdParseError::OutOfRange);
+        }
+
+        let account_id = account
+            .checked_mul(2)
+            .and_then(|account| account.checked_add(auth_server))
+            .ok_or(SteamIdParseError::OutOfRange)?;
+
+        Ok(Self::individual(universe, account_id))
+    }
+
+    fn parse_steam3(s: &str) -> Result<Self, SteamIdParseError> {
+        let mut parts = s.split(':');
+        let (Some(letter), Some(universe), Some(account_id)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(SteamIdParseError::Format);
+        };
+        let instance = parts.next().map(parse_number::<u32>).transpose()?;
+
+        if parts.next().is_some() {
+            return Err(SteamIdParseError::Format);
+        }
+
+        let mut letters = letter.chars();
+        let (Some(letter), None) = (letters.next(), letters.next()) else {
+            return Err(SteamIdParseError::Format);
+        };
+        let account_type =
+            AccountType::from_steam3_letter(letter).ok_or(SteamIdParseError::Format)?;
+
+        let universe = Universe::from_raw(parse_number::<u8>(universe)? as sys::EUniverse);
+        let account_id = parse_number::<u32>(account_id)?;
+
+        let instance = match (instance, letter) {
+            (Some(instance), _) if instance as u64 > INSTANCE_MASK => {
+                return Err(SteamIdParseError::OutOfRange)
+            }
+            (Some(instance), _) => instance,
+            (None, 'U') => DESKTOP_INSTANCE,
+            (None, 'c') => sys::EChatSteamIDInstanceFlags_k_EChatInstanceFlagClan as u32,
+            (None, 'L') => sys::EChatSteamIDInstanceFlags_k_EChatInstanceFlagLobby as u32,
+            (None, _) => 0,
+        };
+
+        Ok(Self::new(account_id, instance, account_type, universe))
+    }
+
+    fn parse_community_url(s: &str) -> Result<Self, SteamIdParseError> {
+        let path = s
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_start_matches("www.")
+            .strip_prefix("steamcommunity.com/")
+            .ok_or(SteamIdParseError::Format)?
+            .trim_end_matches('/');
+
+        let (kind, id) = path.split_once('/').ok_or(SteamIdParseError::Format)?;
+        let expected = match kind {
+            "profiles" => AccountType::Individual,
+            "gid" => AccountType::Clan,
+            "id" | "groups" => return Err(SteamIdParseError::VanityUrl),
+            _ => return Err(SteamIdParseError::Format),
+        };
+
+        let steam_id = Self(parse_number(id)?);
+        if steam_id.account_type() != expected {
+            return Err(SteamIdParseError::Format);
+        }
+
+        Ok(steam_id)
+    }
+}
+
+fn parse_number<T: FromStr<Err = std::num::ParseIntError>>(
+    s: &str,
+) -> Result<T, SteamIdParseError> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(SteamIdParseError::Format);
+    }
+
+    s.parse().map_err(|_| SteamIdParseError::OutOfRange)
+}
+
+impl Deref for SteamId {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Renders the 64-bit form, use [`SteamId::steam2`] or [`SteamId::steam3`] for the others.
+impl Display for SteamId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Accepts the 64-bit, Steam2, Steam3 and community profile URL forms.
+impl FromStr for SteamId {
+    type Err = SteamIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(steam2) = s.strip_prefix("STEAM_") {
+            Self::parse_steam2(steam2)
+        } else if let Some(steam3) = s.strip_prefix('[') {
+            Self::parse_steam3(steam3.strip_suffix(']').ok_or(SteamIdParseError::Format)?)
+        } else if s.contains("steamcommunity.com/") {
+            Self::parse_community_url(s)
+        } else if s.contains(':') {
+            Self::parse_steam3(s)
+        } else {
+            Ok(Self(parse_number(s)?))
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SteamIdParseError {
+    #[error("Unrecognized Steam ID format")]
+    Format,
+    #[error("Steam ID component is out of range")]
+    OutOfRange,
+    #[error("Vanity URLs can only be resolved by Steam")]
+    VanityUrl,
+}
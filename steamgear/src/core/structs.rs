@@ -1,41 +1,136 @@
-use std::{fmt::Display, ops::Deref};
+use std::{fmt::Display, path::Path};
 
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
-pub struct SteamId(pub u64);
+use steamgear_sys as sys;
 
-impl Deref for SteamId {
-    type Target = u64;
+use super::enums::GameIdType;
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+pub use super::steam_id::{SteamId, SteamIdParseError};
+
+const GAME_ID_APP_MASK: u64 = 0xFF_FFFF;
+const GAME_ID_TYPE_SHIFT: u64 = 24;
+const GAME_ID_TYPE_MASK: u64 = 0xFF;
+const GAME_ID_MOD_SHIFT: u64 = 32;
+/// Mod, shortcut and P2P ids always have the high bit set.
+const GAME_ID_MOD_FLAG: u32 = 0x8000_0000;
+/// Shortcuts are numbered above the ids of local apps.
+const GAME_ID_MIN_SHORTCUT: u32 = 5000 | GAME_ID_MOD_FLAG;
+
+/// 64-bit game id, laid out like the SDK's `CGameID`:
+/// app id (24 bits), type (8 bits) and mod id (32 bits).
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct GameId(pub u64);
+
+impl GameId {
+    pub fn new(app_id: AppId, mod_id: u32, game_type: GameIdType) -> Self {
+        Self(
+            (app_id.0 as u64 & GAME_ID_APP_MASK)
+                | (game_type as u64) << GAME_ID_TYPE_SHIFT
+                | (mod_id as u64) << GAME_ID_MOD_SHIFT,
+        )
     }
-}
 
-impl Display for SteamId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SteamId({})", self.0)
+    /// Id of a mod of `app_id` living in `mod_dir`, same as the SDK's `GameIDFromAppAndModPath`.
+    ///
+    /// Only the last component of `mod_dir` without its extension is hashed, like the
+    /// SDK's `V_FileBase`.
+    pub fn from_mod_dir(app_id: AppId, mod_dir: impl AsRef<Path>) -> Self {
+        let mod_dir = mod_dir.as_ref();
+        let name = mod_dir.file_stem().unwrap_or(mod_dir.as_os_str());
+
+        Self::new(
+            app_id,
+            crc32(name.as_encoded_bytes()) | GAME_ID_MOD_FLAG,
+            GameIdType::Mod,
+        )
+    }
+
+    pub fn app_id(&self) -> AppId {
+        AppId((self.0 & GAME_ID_APP_MASK) as u32)
+    }
+
+    pub fn mod_id(&self) -> u32 {
+        (self.0 >> GAME_ID_MOD_SHIFT) as u32
+    }
+
+    /// `None` for types unknown to the linked SDK.
+    pub fn game_type(&self) -> Option<GameIdType> {
+        GameIdType::from_raw(
+            ((self.0 >> GAME_ID_TYPE_SHIFT) & GAME_ID_TYPE_MASK) as sys::CGameID_EGameIDType,
+        )
+    }
+
+    /// The app id when this is a plain Steam app.
+    pub fn to_app_id(&self) -> Option<AppId> {
+        (self.game_type() == Some(GameIdType::App)).then(|| self.app_id())
+    }
+
+    /// Same rules as `CGameID::IsValid`.
+    pub fn is_valid(&self) -> bool {
+        let app_id = self.app_id();
+        let mod_id = self.mod_id();
+
+        match self.game_type() {
+            Some(GameIdType::App) => app_id != AppId::INVALID,
+            Some(GameIdType::Mod) => app_id != AppId::INVALID && mod_id & GAME_ID_MOD_FLAG != 0,
+            Some(GameIdType::Shortcut) => {
+                app_id == AppId::INVALID
+                    && mod_id & GAME_ID_MOD_FLAG != 0
+                    && mod_id >= GAME_ID_MIN_SHORTCUT
+            }
+            Some(GameIdType::P2P) => app_id == AppId::INVALID && mod_id & GAME_ID_MOD_FLAG != 0,
+            None => false,
+        }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
-pub struct GameId(pub u64);
+impl From<AppId> for GameId {
+    fn from(app_id: AppId) -> Self {
+        Self::new(app_id, 0, GameIdType::App)
+    }
+}
 
 impl Display for GameId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "GameId({})", self.0)
+        write!(f, "{}", self.0)
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// CRC-32 (IEEE) as used by Steam for mod ids.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct AppId(pub u32);
 
+impl AppId {
+    pub const INVALID: AppId = AppId(sys::k_uAppIdInvalid);
+}
+
 impl Display for AppId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "AppId({})", self.0)
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct DepotId(pub u32);
 
 impl Display for DepotId {
@@ -0,0 +1,137 @@
+#![allow(private_bounds)]
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::{api::SteamApi, core::SteamApiInterface};
+
+/// Default interval between two `run_callbacks` calls, roughly one frame at 60 FPS.
+pub const DEFAULT_TICK_RATE: Duration = Duration::from_millis(16);
+
+/// Handle to a thread that pumps callbacks in the background.
+///
+/// The thread exits on its own after [`SteamApi::shutdown`] or when every [`SteamApi`]
+/// clone is dropped. Dropping the handle stops the thread and waits for it.
+#[derive(Debug)]
+pub struct CallbackThread {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl CallbackThread {
+    pub fn is_finished(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_none_or(|handle| handle.is_finished())
+    }
+
+    pub fn stop(mut self) {
+        self.stop_internal();
+    }
+
+    fn stop_internal(&mut self) {
+        self.stop.store(true, Ordering::Release);
+
+        if let Some(handle) = self.handle.take() {
+            if handle.thread().id() == std::thread::current().id() {
+                return;
+            }
+
+            if handle.join().is_err() {
+                tracing::error!("Callback thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for CallbackThread {
+    fn drop(&mut self) {
+        self.stop_internal();
+    }
+}
+
+impl<T: SteamApiInterface + 'static> SteamApi<T> {
+    /// Starts a thread that calls `run_callbacks` every `tick_rate`.
+    ///
+    /// Fails when the OS can't create the thread.
+    pub fn spawn_callback_thread(&self, tick_rate: Duration) -> std::io::Result<CallbackThread> {
+        let api = Arc::downgrade(&self.0);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let stop = Arc::clone(&stop);
+
+            std::thread::Builder::new()
+                .name("steamgear-callbacks".to_string())
+                .spawn(move || {
+                    while !stop.load(Ordering::Acquire) && tick(&api) {
+                        std::thread::sleep(tick_rate);
+                    }
+
+                    tracing::debug!("Callback thread stopped");
+                })?
+        };
+
+        Ok(CallbackThread {
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Returns a future that calls `run_callbacks` every `tick_rate` until the API is shut down.
+    ///
+    /// `sleep` is the timer of the async runtime the future is spawned on,
+    /// see [`SteamApi::smol_callback_task`] and [`SteamApi::tokio_callback_task`].
+    pub fn callback_task<S, F>(
+        &self,
+        tick_rate: Duration,
+        mut sleep: S,
+    ) -> impl Future<Output = ()> + Send + 'static
+    where
+        S: FnMut(Duration) -> F + Send + 'static,
+        F: Future<Output = ()> + Send,
+    {
+        let api = Arc::downgrade(&self.0);
+
+        async move {
+            while tick(&api) {
+                sleep(tick_rate).await;
+            }
+
+            tracing::debug!("Callback task stopped");
+        }
+    }
+
+    #[cfg(feature = "smol")]
+    pub fn smol_callback_task(&self, tick_rate: Duration) -> impl Future<Output = ()> + Send {
+        self.callback_task(tick_rate, |duration| async move {
+            async_io::Timer::after(duration).await;
+        })
+    }
+
+    #[cfg(feature = "tokio")]
+    pub fn tokio_callback_task(&self, tick_rate: Duration) -> impl Future<Output = ()> + Send {
+        self.callback_task(tick_rate, tokio::time::sleep)
+    }
+}
+
+fn tick<T: SteamApiInterface>(api: &Weak<T>) -> bool {
+    let Some(api) = api.upgrade() else {
+        return false;
+    };
+
+    if !api.is_running() {
+        return false;
+    }
+
+    api.run_callbacks();
+
+    true
+}
@@ -1,25 +1,37 @@
+pub mod structs;
+
 use std::sync::Arc;
 
-use steamgear_sys as sys;
+use structs::FriendGameInfo;
 
-use crate::core::callback::ClientCallbackContainer;
+use crate::{
+    backend::SteamBackend,
+    core::{callback::ClientCallbackContainer, enums::NotInitialized, structs::SteamId},
+};
 
 #[derive(Clone, Debug)]
 pub struct SteamFriends {
-    raw: *mut sys::ISteamFriends,
+    backend: Arc<dyn SteamBackend>,
     container: Arc<ClientCallbackContainer>,
 }
 
-unsafe impl Send for SteamFriends {}
-unsafe impl Sync for SteamFriends {}
+impl SteamFriends {
+    pub(crate) fn new(
+        backend: Arc<dyn SteamBackend>,
+        container: Arc<ClientCallbackContainer>,
+    ) -> Self {
+        SteamFriends { backend, container }
+    }
+}
 
 impl SteamFriends {
-    pub(crate) fn new(container: Arc<ClientCallbackContainer>) -> Self {
-        unsafe {
-            SteamFriends {
-                raw: sys::SteamAPI_SteamFriends_v017(),
-                container,
-            }
-        }
+    /// The game `friend` is running, `None` when they aren't in game.
+    pub fn get_friend_game_played(
+        &self,
+        friend: SteamId,
+    ) -> Result<Option<FriendGameInfo>, NotInitialized> {
+        let _guard = self.backend.state().client.enter()?;
+
+        Ok(self.backend.friends_game_played(friend))
     }
 }
@@ -0,0 +1,13 @@
+use std::net::SocketAddrV4;
+
+use crate::core::structs::{GameId, SteamId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FriendGameInfo {
+    pub game_id: GameId,
+    /// Address of the game server the friend is playing on, unspecified when there is none.
+    pub game_addr: SocketAddrV4,
+    pub query_port: u16,
+    pub lobby: Option<SteamId>,
+}
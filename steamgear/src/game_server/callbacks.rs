@@ -0,0 +1,243 @@
+use crate::core::{
+    callback::{CallbackDispatcher, CallbackType, CallbackTyped},
+    enums::{AuthSessionResponse, DenyReason, SteamError},
+    structs::SteamId,
+};
+
+use futures_core::Stream;
+use steamgear_sys as sys;
+
+use super::SteamGameServer;
+
+/// The server logged on to Steam.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SteamServersConnected;
+
+impl CallbackTyped for SteamServersConnected {
+    const TYPE: CallbackType = CallbackType::SteamServersConnected;
+    type Raw = sys::SteamServersConnected_t;
+    type Mapped = Self;
+
+    fn from_raw(_: Self::Raw) -> Self::Mapped {
+        SteamServersConnected
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SteamServerConnectFailure {
+    pub error: SteamError,
+    pub still_retrying: bool,
+}
+
+impl CallbackTyped for SteamServerConnectFailure {
+    const TYPE: CallbackType = CallbackType::SteamServerConnectFailure;
+    type Raw = sys::SteamServerConnectFailure_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        SteamServerConnectFailure {
+            error: SteamError::from_code(raw.m_eResult)
+                .err()
+                .unwrap_or(SteamError::Fail),
+            still_retrying: raw.m_bStillRetrying,
+        }
+    }
+}
+
+/// The connection to Steam was lost, Steam keeps trying to reconnect.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SteamServersDisconnected {
+    pub error: SteamError,
+}
+
+impl CallbackTyped for SteamServersDisconnected {
+    const TYPE: CallbackType = CallbackType::SteamServersDisconnected;
+    type Raw = sys::SteamServersDisconnected_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        SteamServersDisconnected {
+            error: SteamError::from_code(raw.m_eResult)
+                .err()
+                .unwrap_or(SteamError::Fail),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidateAuthTicketResponse {
+    pub steam_id: SteamId,
+    pub response: AuthSessionResponse,
+    /// Differs from `steam_id` when the game is borrowed through Family Sharing.
+    pub owner: SteamId,
+}
+
+impl CallbackTyped for ValidateAuthTicketResponse {
+    const TYPE: CallbackType = CallbackType::ValidateAuthTicketResponse;
+    type Raw = sys::ValidateAuthTicketResponse_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        ValidateAuthTicketResponse {
+            steam_id: SteamId::from_raw(raw.m_SteamID),
+            response: AuthSessionResponse::from_raw(raw.m_eAuthSessionResponse),
+            owner: SteamId::from_raw(raw.m_OwnerSteamID),
+        }
+    }
+}
+
+/// Steam accepted a user, who may now play on the server.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientApprove {
+    pub steam_id: SteamId,
+    pub owner: SteamId,
+}
+
+impl CallbackTyped for ClientApprove {
+    const TYPE: CallbackType = CallbackType::GSClientApprove;
+    type Raw = sys::GSClientApprove_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        ClientApprove {
+            steam_id: SteamId::from_raw(raw.m_SteamID),
+            owner: SteamId::from_raw(raw.m_OwnerSteamID),
+        }
+    }
+}
+
+/// Steam rejected a user, the server should disconnect them.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientDeny {
+    pub steam_id: SteamId,
+    pub reason: DenyReason,
+    pub text: String,
+}
+
+impl CallbackTyped for ClientDeny {
+    const TYPE: CallbackType = CallbackType::GSClientDeny;
+    type Raw = sys::GSClientDeny_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        let text = raw
+            .m_rgchOptionalText
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8)
+            .collect::<Vec<_>>();
+
+        ClientDeny {
+            steam_id: SteamId::from_raw(raw.m_SteamID),
+            reason: DenyReason::from_raw(raw.m_eDenyReason),
+            text: String::from_utf8_lossy(&text).into_owned(),
+        }
+    }
+}
+
+/// Steam asks the server to kick a user who was already approved.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientKick {
+    pub steam_id: SteamId,
+    pub reason: DenyReason,
+}
+
+impl CallbackTyped for ClientKick {
+    const TYPE: CallbackType = CallbackType::GSClientKick;
+    type Raw = sys::GSClientKick_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        ClientKick {
+            steam_id: SteamId::from_raw(raw.m_SteamID),
+            reason: DenyReason::from_raw(raw.m_eDenyReason),
+        }
+    }
+}
+
+/// Whether the server was marked VAC secure after logging on.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolicyResponse {
+    pub secure: bool,
+}
+
+impl CallbackTyped for PolicyResponse {
+    const TYPE: CallbackType = CallbackType::GSPolicyResponse;
+    type Raw = sys::GSPolicyResponse_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        PolicyResponse {
+            secure: raw.m_bSecure != 0,
+        }
+    }
+}
+
+/// Stats of a user were loaded for the server.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsReceived {
+    pub steam_id: SteamId,
+    pub result: Result<(), SteamError>,
+}
+
+impl CallbackTyped for StatsReceived {
+    const TYPE: CallbackType = CallbackType::GSStatsReceived;
+    type Raw = sys::GSStatsReceived_t;
+    type Mapped = Self;
+
+    fn from_raw(raw: Self::Raw) -> Self::Mapped {
+        StatsReceived {
+            steam_id: SteamId::from_raw(raw.m_steamIDUser),
+            result: SteamError::from_code(raw.m_eResult),
+        }
+    }
+}
+
+impl SteamGameServer {
+    pub fn on_servers_connected(&self) -> impl Stream<Item = SteamServersConnected> {
+        self.container.servers_connected_callback.register()
+    }
+
+    pub fn on_server_connect_failure(&self) -> impl Stream<Item = SteamServerConnectFailure> {
+        self.container.server_connect_failure_callback.register()
+    }
+
+    pub fn on_servers_disconnected(&self) -> impl Stream<Item = SteamServersDisconnected> {
+        self.container.servers_disconnected_callback.register()
+    }
+
+    pub fn on_validate_auth_ticket_response(
+        &self,
+    ) -> impl Stream<Item = ValidateAuthTicketResponse> {
+        self.container.validate_auth_ticket_callback.register()
+    }
+
+    pub fn on_client_approve(&self) -> impl Stream<Item = ClientApprove> {
+        self.container.client_approve_callback.register()
+    }
+
+    pub fn on_client_deny(&self) -> impl Stream<Item = ClientDeny> {
+        self.container.client_deny_callback.register()
+    }
+
+    pub fn on_client_kick(&self) -> impl Stream<Item = ClientKick> {
+        self.container.client_kick_callback.register()
+    }
+
+    pub fn on_policy_response(&self) -> impl Stream<Item = PolicyResponse> {
+        self.container.policy_response_callback.register()
+    }
+
+    pub fn on_stats_received(&self) -> impl Stream<Item = StatsReceived> {
+        self.container.stats_received_callback.register()
+    }
+}
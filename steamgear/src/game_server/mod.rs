@@ -0,0 +1,20 @@
+pub mod callbacks;
+
+use std::sync::Arc;
+
+use crate::{backend::SteamBackend, core::callback::ServerCallbackContainer};
+
+#[derive(Clone, Debug)]
+pub struct SteamGameServer {
+    backend: Arc<dyn SteamBackend>,
+    container: Arc<ServerCallbackContainer>,
+}
+
+impl SteamGameServer {
+    pub(crate) fn new(
+        backend: Arc<dyn SteamBackend>,
+        container: Arc<ServerCallbackContainer>,
+    ) -> Self {
+        SteamGameServer { backend, container }
+    }
+}
@@ -4,6 +4,12 @@
 
 pub mod api;
 pub mod apps;
+pub mod backend;
 pub mod core;
+pub mod driver;
 pub mod friends;
+pub mod game_server;
+pub mod sdk;
 pub mod utils;
+
+pub use sdk::sdk_info;
@@ -0,0 +1,46 @@
+//! The Steamworks SDK steamgear was built against, read from the SDK at build time.
+//!
+//! Set `STEAM_SDK_VERSION` when building if the SDK has no `Readme.txt` to take the
+//! version from, the version is `"unknown"` otherwise.
+
+use std::fmt::Display;
+
+include!(concat!(env!("OUT_DIR"), "/sdk_info.rs"));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SdkInfo {
+    pub version: &'static str,
+    /// `(define, version)` of every `*_INTERFACE_VERSION` in the SDK headers.
+    pub interfaces: &'static [(&'static str, &'static str)],
+}
+
+impl SdkInfo {
+    /// Looks up a version by its define, e.g. `STEAMAPPS_INTERFACE_VERSION`.
+    pub fn interface_version(&self, define: &str) -> Option<&'static str> {
+        self.interfaces
+            .iter()
+            .find(|(name, _)| *name == define)
+            .map(|(_, version)| *version)
+    }
+}
+
+impl Display for SdkInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Steamworks SDK {} (", self.version)?;
+        for (i, (_, version)) in self.interfaces.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(version)?;
+        }
+        f.write_str(")")
+    }
+}
+
+pub fn sdk_info() -> SdkInfo {
+    SdkInfo {
+        version: SDK_VERSION,
+        interfaces: INTERFACE_VERSIONS,
+    }
+}
@@ -3,9 +3,10 @@ use crate::core::callback::{CallbackDispatcher, CallbackType, CallbackTyped};
 use futures_core::Stream;
 use steamgear_sys as sys;
 
-use super::client::SteamUtilsClient;
+use super::{client::SteamUtilsClient, server::SteamUtilsServer};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SteamShutdown;
 
 impl CallbackTyped for SteamShutdown {
@@ -24,3 +25,9 @@ impl SteamUtilsClient {
         self.container.steam_shutdown_callback.register()
     }
 }
+
+impl SteamUtilsServer {
+    pub fn on_steam_shutdown(&self) -> impl Stream<Item = SteamShutdown> {
+        self.container.steam_shutdown_callback.register()
+    }
+}
@@ -1 +1,18 @@
+use std::sync::Arc;
 
+use crate::{backend::SteamBackend, core::callback::ServerCallbackContainer};
+
+#[derive(Clone, Debug)]
+pub struct SteamUtilsServer {
+    pub(super) backend: Arc<dyn SteamBackend>,
+    pub(super) container: Arc<ServerCallbackContainer>,
+}
+
+impl SteamUtilsServer {
+    pub(crate) fn new(
+        backend: Arc<dyn SteamBackend>,
+        container: Arc<ServerCallbackContainer>,
+    ) -> Self {
+        SteamUtilsServer { backend, container }
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use steamgear::{
+        api::{SteamApi, QUERY_PORT_SHARED},
+        backend::FakeBackend,
+        core::{
+            enums::{Interface, ServerMode, SteamApiInitError},
+            structs::AppId,
+        },
+    };
+
+    #[test]
+    fn client_builder_interfaces() {
+        let backend = Arc::new(FakeBackend::new());
+
+        let client = SteamApi::client()
+            .backend(backend.clone())
+            .interfaces([Interface::Utils, Interface::Apps])
+            .build()
+            .unwrap();
+        assert_eq!(
+            backend.requested_interfaces(),
+            vec![Interface::Utils, Interface::Apps]
+        );
+        client.shutdown();
+
+        let err = SteamApi::client()
+            .backend(backend.clone())
+            .interfaces([Interface::GameServer])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            SteamApiInitError::UnsupportedInterface(Interface::GameServer)
+        );
+        assert!(!backend.is_client_running());
+    }
+
+    #[test]
+    fn client_builder_restart() {
+        let backend = Arc::new(FakeBackend::new());
+        backend.set_restart_required(true);
+
+        let err = SteamApi::client()
+            .backend(backend.clone())
+            .restart_if_necessary(true)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, SteamApiInitError::MissingAppId);
+
+        let err = SteamApi::client()
+            .backend(backend.clone())
+            .app_id(AppId(480))
+            .restart_if_necessary(true)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, SteamApiInitError::RestartRequired);
+        assert!(!backend.is_client_running());
+
+        backend.set_restart_required(false);
+        let client = SteamApi::client()
+            .backend(backend.clone())
+            .app_id(AppId(480))
+            .restart_if_necessary(true)
+            .build()
+            .unwrap();
+        assert!(backend.is_client_running());
+        client.shutdown();
+    }
+
+    #[test]
+    fn server_builder_validation() {
+        let backend = Arc::new(FakeBackend::new());
+        let builder = SteamApi::server().backend(backend.clone());
+
+        assert_eq!(
+            builder
+                .clone()
+                .game_port(27015)
+                .query_port(27015)
+                .build()
+                .unwrap_err(),
+            SteamApiInitError::InvalidPorts {
+                game: 27015,
+                query: 27015
+            }
+        );
+        assert_eq!(
+            builder.clone().game_port(0).build().unwrap_err(),
+            SteamApiInitError::InvalidPorts {
+                game: 0,
+                query: 27016
+            }
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .mode(ServerMode::Invalid)
+                .build()
+                .unwrap_err(),
+            SteamApiInitError::InvalidServerMode
+        );
+        assert_eq!(
+            builder.clone().version("1.0\0").build().unwrap_err(),
+            SteamApiInitError::InvalidVersion
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .interfaces([Interface::Friends])
+                .build()
+                .unwrap_err(),
+            SteamApiInitError::UnsupportedInterface(Interface::Friends)
+        );
+        assert!(!backend.is_server_running());
+
+        let server = builder
+            .query_port(QUERY_PORT_SHARED)
+            .version("1.2.3.4")
+            .build()
+            .unwrap();
+        assert!(backend.is_server_running());
+        assert_eq!(backend.requested_interfaces(), Interface::SERVER);
+
+        server.shutdown();
+        assert!(!backend.is_server_running());
+    }
+
+    #[cfg(feature = "runtime-link")]
+    #[test]
+    fn missing_library() {
+        let err = SteamApi::client()
+            .library_path("/nonexistent/libsteam_api.so")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, SteamApiInitError::LibraryLoad(_)));
+
+        let err = SteamApi::server()
+            .library_path("/nonexistent/libsteam_api.so")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, SteamApiInitError::LibraryLoad(_)));
+    }
+}
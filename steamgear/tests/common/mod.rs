@@ -0,0 +1,32 @@
+//! Helpers shared by the integration tests, each test crate uses some of them.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use steamgear::{
+    api::SteamApi,
+    backend::FakeBackend,
+    core::{client::SteamApiClient, server::SteamApiServer},
+};
+
+pub fn fake_client() -> (Arc<FakeBackend>, SteamApi<SteamApiClient>) {
+    let backend = Arc::new(FakeBackend::new());
+    let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+
+    (backend, client)
+}
+
+pub fn fake_server() -> (Arc<FakeBackend>, SteamApi<SteamApiServer>) {
+    let backend = Arc::new(FakeBackend::new());
+    let server = SteamApi::server().backend(backend.clone()).build().unwrap();
+
+    (backend, server)
+}
+
+/// Runs callbacks until the future it's raced against completes.
+pub async fn pump<T>(client: &SteamApi<SteamApiClient>) -> T {
+    loop {
+        client.run_callbacks();
+        smol::future::yield_now().await;
+    }
+}
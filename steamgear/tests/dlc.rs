@@ -0,0 +1,198 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use smol::stream::StreamExt;
+    use steamgear::{
+        apps::dlc::DlcEvent,
+        backend::FakeBackend,
+        core::{callback::CallbackError, enums::DlcInstallError, structs::AppId},
+    };
+
+    use crate::common::fake_client;
+
+    fn add_owned_dlc(backend: &FakeBackend, dlc_id: AppId, name: &str) {
+        backend.add_dlc(dlc_id, name, true);
+        backend.add_owned_app(dlc_id);
+    }
+
+    async fn progress(stream: &mut (impl StreamExt<Item = DlcEvent> + Unpin)) -> (u64, f32) {
+        match stream.next().await {
+            Some(DlcEvent::Progress(progress)) => (progress.downloaded, progress.percent),
+            event => panic!("Expected progress, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn concurrent_installs() {
+        let (backend, client) = fake_client();
+        add_owned_dlc(&backend, AppId(1001), "Soundtrack");
+        add_owned_dlc(&backend, AppId(1002), "Expansion");
+        backend.hold_dlc_installs(true);
+
+        let dlc = client.apps().dlc();
+        let mut soundtrack = std::pin::pin!(dlc.install(AppId(1001)).unwrap());
+        let mut expansion = std::pin::pin!(dlc.install(AppId(1002)).unwrap());
+        let mut expansion_again = std::pin::pin!(dlc.install(AppId(1002)).unwrap());
+
+        let mut installing = dlc.installing();
+        installing.sort_by_key(|dlc_id| dlc_id.0);
+        assert_eq!(installing, vec![AppId(1001), AppId(1002)]);
+
+        smol::block_on(async {
+            backend.set_dlc_download_progress(AppId(1001), Some((25, 100)));
+            backend.set_dlc_download_progress(AppId(1002), Some((0, 0)));
+            client.run_callbacks();
+            assert_eq!(progress(&mut soundtrack).await, (25, 25.0));
+            assert_eq!(progress(&mut expansion).await, (0, 0.0));
+            assert_eq!(progress(&mut expansion_again).await, (0, 0.0));
+
+            backend.set_dlc_download_progress(AppId(1001), Some((75, 100)));
+            client.run_callbacks();
+            match soundtrack.next().await {
+                Some(DlcEvent::Progress(progress)) => {
+                    assert_eq!(progress.percent, 75.0);
+                    assert!(progress.bytes_per_second.unwrap() > 0.0);
+                }
+                event => panic!("Expected progress, got {:?}", event),
+            }
+            // Unchanged progress isn't reported again
+            assert!(smol::future::poll_once(expansion.next()).await.is_none());
+
+            backend.finish_dlc_install(AppId(1001));
+            client.run_callbacks();
+            assert_eq!(soundtrack.next().await, Some(DlcEvent::Installed));
+            assert_eq!(soundtrack.next().await, None);
+
+            dlc.uninstall(AppId(1002)).unwrap();
+            let failed = Some(DlcEvent::Failed(DlcInstallError::Uninstalled));
+            assert_eq!(expansion.next().await, failed);
+            assert_eq!(expansion_again.next().await, failed);
+        });
+
+        assert!(dlc.installing().is_empty());
+        client.shutdown();
+    }
+
+    #[test]
+    fn install_outcomes() {
+        let (backend, client) = fake_client();
+        add_owned_dlc(&backend, AppId(1001), "Soundtrack");
+        add_owned_dlc(&backend, AppId(1002), "Expansion");
+        backend.add_dlc(AppId(1003), "Season Pass", true);
+        backend.set_dlc_installed(AppId(1001), true);
+        backend.hold_dlc_installs(true);
+
+        let dlc = client.apps().dlc();
+        let snapshot = dlc.snapshot().unwrap();
+        let state = snapshot
+            .iter()
+            .map(|status| (status.info.dlc_id, status.owned, status.installed))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            state,
+            vec![
+                (AppId(1001), true, true),
+                (AppId(1002), true, false),
+                (AppId(1003), false, false),
+            ]
+        );
+
+        let installed = dlc.install(AppId(1001)).unwrap();
+        let not_owned = dlc.install(AppId(1003)).unwrap();
+        let pending = dlc.install(AppId(1002)).unwrap();
+
+        smol::block_on(async {
+            assert_eq!(
+                installed.collect::<Vec<_>>().await,
+                vec![DlcEvent::Installed]
+            );
+            assert_eq!(
+                not_owned.collect::<Vec<_>>().await,
+                vec![DlcEvent::Failed(DlcInstallError::NotOwned)]
+            );
+
+            client.shutdown();
+            assert_eq!(
+                pending.collect::<Vec<_>>().await,
+                vec![DlcEvent::Failed(DlcInstallError::Shutdown)]
+            );
+        });
+    }
+
+    #[test]
+    fn stalled_install() {
+        let (backend, client) = fake_client();
+        add_owned_dlc(&backend, AppId(1001), "Soundtrack");
+        backend.hold_dlc_installs(true);
+
+        let dlc = client.apps().dlc();
+        dlc.set_stall_timeout(Some(Duration::from_millis(50)));
+        let mut soundtrack = std::pin::pin!(dlc.install(AppId(1001)).unwrap());
+
+        smol::block_on(async {
+            backend.set_dlc_download_progress(AppId(1001), Some((10, 100)));
+            client.run_callbacks();
+            assert_eq!(progress(&mut soundtrack).await, (10, 10.0));
+
+            std::thread::sleep(Duration::from_millis(60));
+            client.run_callbacks();
+            assert_eq!(
+                soundtrack.next().await,
+                Some(DlcEvent::Failed(DlcInstallError::Stalled))
+            );
+            assert_eq!(soundtrack.next().await, None);
+        });
+
+        assert!(dlc.installing().is_empty());
+        client.shutdown();
+    }
+
+    #[test]
+    fn install_dlc_shutdown() {
+        let (backend, client) = fake_client();
+        add_owned_dlc(&backend, AppId(1001), "Soundtrack");
+        backend.hold_dlc_installs(true);
+
+        smol::block_on(async {
+            let install = client.apps().install_dlc(AppId(1001));
+            let shutdown = async {
+                smol::future::yield_now().await;
+                client.shutdown();
+                std::future::pending().await
+            };
+
+            assert_eq!(
+                smol::future::or(install, shutdown).await.unwrap_err(),
+                CallbackError::Shutdown
+            );
+        });
+    }
+
+    #[test]
+    fn install_dlc_concurrently() {
+        let (backend, client) = fake_client();
+        backend.add_dlc(AppId(1001), "Soundtrack", true);
+        backend.add_dlc(AppId(1002), "Expansion", true);
+
+        smol::block_on(async {
+            let apps = client.apps();
+            let both =
+                smol::future::zip(apps.install_dlc(AppId(1001)), apps.install_dlc(AppId(1002)));
+            let pump = async {
+                loop {
+                    client.run_callbacks();
+                    smol::future::yield_now().await;
+                }
+            };
+
+            let (soundtrack, expansion) = smol::future::or(both, pump).await;
+            assert_eq!(soundtrack.unwrap().id, AppId(1001));
+            assert_eq!(expansion.unwrap().id, AppId(1002));
+        });
+
+        client.shutdown();
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use smol::stream::StreamExt;
+    use steamgear::{api::SteamApi, backend::FakeBackend, core::structs::AppId};
+
+    #[test]
+    fn callback_thread_pumps_until_shutdown() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+
+        let thread = client
+            .spawn_callback_thread(Duration::from_millis(1))
+            .unwrap();
+        let launch_stream = client.apps().on_new_launch_query_param();
+        backend.push_new_url_launch_params();
+
+        smol::block_on(async {
+            let mut launch_stream = std::pin::pin!(launch_stream);
+            assert!(launch_stream.next().await.is_some());
+        });
+
+        client.shutdown();
+        for _ in 0..1000 {
+            if thread.is_finished() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(thread.is_finished());
+    }
+
+    #[test]
+    fn no_frame_after_shutdown() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+
+        client.run_callbacks();
+        assert_eq!(backend.client_frames(), 1);
+
+        client.shutdown();
+        client.run_callbacks();
+        assert_eq!(backend.client_frames(), 1);
+    }
+
+    #[test]
+    fn callback_thread_stops_on_drop() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend).build().unwrap();
+
+        let thread = client
+            .spawn_callback_thread(Duration::from_millis(1))
+            .unwrap();
+        assert!(!thread.is_finished());
+        thread.stop();
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn callback_task_resolves_futures() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        backend.add_dlc(AppId(1001), "Soundtrack", true);
+
+        let task = smol::spawn(client.callback_task(
+            Duration::from_millis(1),
+            |duration| async move {
+                smol::Timer::after(duration).await;
+            },
+        ));
+
+        smol::block_on(async {
+            let installed = client.apps().install_dlc(AppId(1001)).await.unwrap();
+            assert_eq!(installed.id, AppId(1001));
+
+            client.shutdown();
+            task.await;
+        });
+    }
+}
@@ -0,0 +1,488 @@
+// The SDK constants are `c_int` on Windows
+#![allow(clippy::unnecessary_cast)]
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, SocketAddrV4},
+        path::PathBuf,
+        sync::Arc,
+        time::Duration,
+    };
+
+    use smol::stream::StreamExt;
+    use steamgear::{
+        api::SteamApi,
+        apps::structs::FileDetails,
+        backend::{FakeBackend, FakeCallResults, SteamBackend},
+        core::{
+            callback::{CallResultError, CallbackError},
+            enums::{
+                ApiCallFailure, AppsError, NotInitialized, SteamApiInitError, SteamError, Universe,
+            },
+            structs::{AppId, DepotId, GameId, SteamId},
+        },
+        friends::structs::FriendGameInfo,
+    };
+
+    use crate::common::{fake_client, pump};
+
+    #[test]
+    fn fake_init_shutdown() {
+        let (backend, client) = fake_client();
+        assert!(backend.is_client_running());
+
+        client.shutdown();
+        assert!(!backend.is_client_running());
+    }
+
+    #[test]
+    fn fake_init_error() {
+        let backend = Arc::new(FakeBackend::new());
+        backend.set_init_error(Some(SteamApiInitError::NoSteamClient(
+            "no client".to_string(),
+        )));
+
+        let client = SteamApi::client().backend(backend.clone()).build();
+        assert_eq!(
+            client.unwrap_err(),
+            SteamApiInitError::NoSteamClient("no client".to_string())
+        );
+        assert!(!backend.is_client_running());
+    }
+
+    #[test]
+    fn fake_apps_state() {
+        let (backend, client) = fake_client();
+
+        backend.add_owned_app(AppId(480));
+        backend.add_dlc(AppId(1001), "Soundtrack", true);
+        backend.add_dlc(AppId(1002), "Expansion", false);
+        backend.set_dlc_installed(AppId(1001), true);
+        backend.install_app(AppId(480), "/games/spacewar");
+        backend.set_installed_depots(AppId(480), vec![DepotId(481), DepotId(482)]);
+        backend.set_launch_command_line("+connect 127.0.0.1");
+        backend.set_launch_query_param("server", "127.0.0.1");
+        backend.set_available_game_languages(&["english", "german"]);
+
+        let apps = client.apps();
+        assert!(apps.is_subscribe_app(AppId(480)).unwrap());
+        assert!(!apps.is_subscribe_app(AppId(481)).unwrap());
+        assert!(apps.is_dlc_installed(AppId(1001)).unwrap());
+        assert!(!apps.is_dlc_installed(AppId(1002)).unwrap());
+
+        let dlcs = apps.get_dlc_information().unwrap();
+        assert_eq!(dlcs.len(), 2);
+        assert_eq!(dlcs[1].dlc_id, AppId(1002));
+        assert_eq!(dlcs[1].dlc_name, "Expansion");
+        assert!(!dlcs[1].available);
+
+        assert_eq!(
+            apps.get_app_install_dir(AppId(480)).unwrap(),
+            PathBuf::from("/games/spacewar")
+        );
+        assert_eq!(
+            apps.get_app_install_dir(AppId(481)),
+            Err(AppsError::NotFound)
+        );
+        assert_eq!(
+            apps.get_installed_depots(AppId(480))
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![DepotId(481), DepotId(482)]
+        );
+        assert_eq!(
+            apps.get_launch_command_line().unwrap(),
+            "+connect 127.0.0.1"
+        );
+        assert_eq!(apps.get_launch_query_param(c"server").unwrap(), "127.0.0.1");
+        assert_eq!(
+            apps.get_available_game_languages().unwrap(),
+            vec!["english".to_string(), "german".to_string()]
+        );
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_queued_callback() {
+        let (backend, client) = fake_client();
+
+        let launch_stream = client.apps().on_new_launch_query_param();
+        backend.push_new_url_launch_params();
+        client.run_callbacks();
+
+        smol::block_on(async move {
+            let mut launch_stream = std::pin::pin!(launch_stream);
+            assert!(launch_stream.next().await.is_some());
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_callback_broadcast() {
+        let (backend, client) = fake_client();
+
+        let ui_stream = client.utils().on_steam_shutdown();
+        let save_stream = client.utils().on_steam_shutdown();
+        backend.push_steam_shutdown();
+        client.run_callbacks();
+
+        smol::block_on(async move {
+            let mut ui_stream = std::pin::pin!(ui_stream);
+            let mut save_stream = std::pin::pin!(save_stream);
+            assert!(ui_stream.next().await.is_some());
+            assert!(save_stream.next().await.is_some());
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_callback_lagging_subscriber() {
+        let (backend, client) = fake_client();
+
+        let lagging_stream = client.apps().on_new_launch_query_param();
+        for _ in 0..40 {
+            backend.push_new_url_launch_params();
+        }
+        client.run_callbacks();
+
+        smol::block_on(async move {
+            let mut lagging_stream = std::pin::pin!(lagging_stream);
+            for _ in 0..32 {
+                assert!(lagging_stream.next().await.is_some());
+            }
+            assert!(smol::future::poll_once(lagging_stream.next())
+                .await
+                .is_none());
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_install_dlc() {
+        let (backend, client) = fake_client();
+        backend.add_dlc(AppId(1001), "Soundtrack", true);
+
+        smol::block_on(async {
+            let installed = smol::future::or(client.apps().install_dlc(AppId(1001)), pump(&client));
+            assert_eq!(installed.await.unwrap().id, AppId(1001));
+        });
+
+        assert!(client.apps().is_dlc_installed(AppId(1001)).unwrap());
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_file_details() {
+        let (backend, client) = fake_client();
+        backend.set_file_details(
+            "game.pak",
+            FileDetails {
+                file_size: 42,
+                sha1: [7; 20],
+            },
+        );
+
+        smol::block_on(async {
+            let details =
+                smol::future::or(client.apps().get_file_details("game.pak"), pump(&client))
+                    .await
+                    .unwrap();
+            assert_eq!(details.file_size, 42);
+            assert_eq!(details.sha1, [7; 20]);
+
+            let missing =
+                smol::future::or(client.apps().get_file_details("missing.pak"), pump(&client))
+                    .await;
+            assert_eq!(
+                missing.unwrap_err(),
+                CallResultError::Steam(SteamError::FileNotFound)
+            );
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_api_call_completed() {
+        let backend = FakeBackend::new();
+        assert_eq!(backend.utils_is_api_call_completed(42), None);
+
+        let call = backend.next_call_handle();
+        backend.push_call_result(call, 999_001, Ok(vec![1, 2, 3]));
+        assert_eq!(backend.utils_is_api_call_completed(call), Some(false));
+        assert_eq!(
+            backend.utils_api_call_result(call, 999_001, 3),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(backend.utils_is_api_call_completed(call), Some(true));
+    }
+
+    #[test]
+    fn fake_call_result_failure() {
+        let (backend, client) = fake_client();
+        backend.set_call_results(FakeCallResults::Fail(ApiCallFailure::NetworkFailure));
+
+        smol::block_on(async {
+            let result =
+                smol::future::or(client.apps().get_file_details("game.pak"), pump(&client)).await;
+            assert_eq!(
+                result.unwrap_err(),
+                CallResultError::Failed(ApiCallFailure::NetworkFailure)
+            );
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_call_result_timeout() {
+        let (backend, client) = fake_client();
+        backend.set_call_results(FakeCallResults::Hang);
+        client.set_call_result_timeout(Some(Duration::from_millis(10)));
+
+        smol::block_on(async {
+            let result =
+                smol::future::or(client.apps().get_file_details("game.pak"), pump(&client)).await;
+            assert_eq!(result.unwrap_err(), CallResultError::Timeout);
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_call_result_with_timeout() {
+        let (backend, client) = fake_client();
+        backend.set_call_results(FakeCallResults::Hang);
+
+        smol::block_on(async {
+            // Enforced on poll, nothing pumps callbacks here
+            let details = client
+                .apps()
+                .get_file_details("game.pak")
+                .with_timeout(Duration::ZERO);
+            assert_eq!(details.await.unwrap_err(), CallResultError::Timeout);
+
+            let details = client
+                .apps()
+                .get_file_details("game.pak")
+                .with_timeout(Duration::from_millis(10));
+            let result = smol::future::or(details, pump(&client)).await;
+            assert_eq!(result.unwrap_err(), CallResultError::Timeout);
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_call_result_cancelled_on_shutdown() {
+        let (backend, client) = fake_client();
+        backend.set_call_results(FakeCallResults::Hang);
+
+        smol::block_on(async {
+            let details = client.apps().get_file_details("game.pak");
+            let shutdown = async {
+                client.run_callbacks();
+                smol::future::yield_now().await;
+                client.shutdown();
+                std::future::pending().await
+            };
+
+            assert_eq!(
+                smol::future::or(details, shutdown).await.unwrap_err(),
+                CallResultError::Shutdown
+            );
+        });
+    }
+
+    #[test]
+    fn fake_friend_game_played() {
+        let (backend, client) = fake_client();
+        let friend = SteamId::individual(Universe::Public, 22202);
+        let info = FriendGameInfo {
+            game_id: GameId::from_mod_dir(AppId(70), "valve"),
+            game_addr: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 27015),
+            query_port: 27016,
+            lobby: None,
+        };
+
+        assert_eq!(client.friends().get_friend_game_played(friend), Ok(None));
+
+        backend.set_friend_game_played(friend, Some(info));
+        assert_eq!(
+            client.friends().get_friend_game_played(friend),
+            Ok(Some(info))
+        );
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_apps_long_values() {
+        let (backend, client) = fake_client();
+
+        let dir = format!(
+            "/home/{}/.local/share/Steam/steamapps/common/Spacewar",
+            "a".repeat(600)
+        );
+        let line = format!("+connect 127.0.0.1 {}", "+exec autoexec.cfg ".repeat(100));
+        let name = "Soundtrack ".repeat(40);
+        let depots = (0..100).map(DepotId).collect::<Vec<_>>();
+
+        backend.install_app(AppId(480), &dir);
+        backend.set_launch_command_line(&line);
+        backend.add_dlc(AppId(1001), &name, true);
+        backend.set_beta_name(Some(&name));
+        backend.set_installed_depots(AppId(480), depots.clone());
+
+        let apps = client.apps();
+        assert_eq!(
+            apps.get_app_install_dir(AppId(480)).unwrap(),
+            PathBuf::from(dir)
+        );
+        assert_eq!(apps.get_launch_command_line().unwrap(), line);
+        assert_eq!(apps.get_dlc_information().unwrap()[0].dlc_name, name);
+        assert_eq!(apps.get_current_beta_name().unwrap(), Some(name));
+        assert_eq!(
+            apps.get_installed_depots(AppId(480))
+                .unwrap()
+                .collect::<Vec<_>>(),
+            depots
+        );
+
+        backend.set_launch_command_line(&"x".repeat(100_000));
+        assert_eq!(
+            apps.get_launch_command_line(),
+            Err(AppsError::Truncated {
+                capacity: 64 * 1024
+            })
+        );
+
+        client.shutdown();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fake_apps_non_utf8_install_dir() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let (backend, client) = fake_client();
+        let dir = OsStr::from_bytes(b"/games/spacewar\xff");
+        backend.install_app(AppId(480), dir);
+
+        assert_eq!(
+            client.apps().get_app_install_dir(AppId(480)).unwrap(),
+            PathBuf::from(dir)
+        );
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_use_after_shutdown() {
+        let (backend, client) = fake_client();
+        let apps = client.apps().clone();
+        let friends = client.friends().clone();
+        backend.add_dlc(AppId(1001), "Soundtrack", true);
+
+        assert_eq!(apps.is_subscribe(), Ok(false));
+
+        client.shutdown();
+
+        assert_eq!(apps.is_subscribe(), Err(NotInitialized));
+        assert_eq!(
+            apps.get_dlc_information().unwrap_err(),
+            AppsError::NotInitialized(NotInitialized)
+        );
+        assert_eq!(
+            friends.get_friend_game_played(SteamId::default()),
+            Err(NotInitialized)
+        );
+        assert_eq!(client.utils().enable_debug_hook(), Err(NotInitialized));
+
+        smol::block_on(async {
+            assert_eq!(
+                apps.install_dlc(AppId(1001)).await.unwrap_err(),
+                CallbackError::NotInitialized(NotInitialized)
+            );
+            assert_eq!(
+                apps.get_file_details("game.pak").await.unwrap_err(),
+                CallResultError::NotInitialized(NotInitialized)
+            );
+        });
+    }
+
+    #[test]
+    fn fake_shutdown_waits_for_calls() {
+        let (backend, client) = fake_client();
+        let apps = client.apps().clone();
+
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let worker = {
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                let mut calls = 0;
+                while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                    match apps.is_subscribe() {
+                        Ok(_) => calls += 1,
+                        Err(NotInitialized) => break,
+                    }
+                }
+                calls
+            })
+        };
+
+        std::thread::sleep(Duration::from_millis(5));
+        client.shutdown();
+        assert!(!backend.is_client_running());
+
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(worker.join().unwrap() > 0);
+    }
+
+    #[test]
+    fn fake_listen_server() {
+        let (backend, client) = fake_client();
+        let server = SteamApi::server().backend(backend.clone()).build().unwrap();
+        assert!(backend.is_client_running());
+        assert!(backend.is_server_running());
+
+        let shutdown_stream = client.utils().on_steam_shutdown();
+        backend.push_server_callback(steamgear_sys::SteamShutdown_t_k_iCallback as u32, vec![0]);
+        server.run_callbacks();
+        client.run_callbacks();
+
+        server.shutdown();
+        assert!(!backend.is_server_running());
+        assert!(backend.is_client_running());
+        assert_eq!(client.apps().is_subscribe(), Ok(false));
+
+        smol::block_on(async move {
+            let mut shutdown_stream = std::pin::pin!(shutdown_stream);
+            assert!(smol::future::poll_once(shutdown_stream.next())
+                .await
+                .is_none());
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn fake_client_shutdown_keeps_server() {
+        let (backend, client) = fake_client();
+        let server = SteamApi::server().backend(backend.clone()).build().unwrap();
+
+        client.shutdown();
+        assert!(!backend.is_client_running());
+        assert!(backend.is_server_running());
+
+        server.shutdown();
+        assert!(!backend.is_server_running());
+    }
+}
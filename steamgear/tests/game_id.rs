@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use steamgear::core::{
+        enums::GameIdType,
+        structs::{AppId, GameId},
+    };
+
+    #[test]
+    fn game_id_app() {
+        let game_id = GameId::from(AppId(480));
+        assert_eq!(game_id.0, 480);
+        assert_eq!(game_id.app_id(), AppId(480));
+        assert_eq!(game_id.game_type(), Some(GameIdType::App));
+        assert_eq!(game_id.to_app_id(), Some(AppId(480)));
+        assert!(game_id.is_valid());
+
+        assert!(!GameId::from(AppId::INVALID).is_valid());
+    }
+
+    #[test]
+    fn game_id_mod() {
+        let game_id = GameId::from_mod_dir(AppId(70), "/games/half-life/123456789");
+        assert_eq!(game_id.app_id(), AppId(70));
+        assert_eq!(game_id.game_type(), Some(GameIdType::Mod));
+        assert_eq!(game_id.mod_id(), 0xCBF4_3926);
+        assert_eq!(game_id.to_app_id(), None);
+        assert!(game_id.is_valid());
+
+        assert_eq!(game_id, GameId::from_mod_dir(AppId(70), "123456789"));
+        assert_eq!(
+            game_id,
+            GameId::new(AppId(70), 0xCBF4_3926, GameIdType::Mod)
+        );
+        assert!(!GameId::new(AppId(70), 1, GameIdType::Mod).is_valid());
+    }
+
+    #[test]
+    fn game_id_mod_path_base() {
+        // CRC32 of the base name with the mod flag set, as `GameIDFromAppAndModPath` computes it
+        let tfc = GameId::from_mod_dir(AppId(70), "tfc");
+        assert_eq!(tfc.mod_id(), 0xCBA5_E55D);
+        assert_eq!(tfc.0, 0xCBA5_E55D_0100_0046);
+
+        assert_eq!(
+            tfc,
+            GameId::from_mod_dir(AppId(70), "/games/half-life/tfc.dir")
+        );
+        assert_eq!(tfc, GameId::from_mod_dir(AppId(70), "mods/tfc/"));
+    }
+
+    #[test]
+    fn game_id_shortcut_and_p2p() {
+        let shortcut = GameId::new(AppId::INVALID, 0x8000_1388, GameIdType::Shortcut);
+        assert_eq!(shortcut.game_type(), Some(GameIdType::Shortcut));
+        assert!(shortcut.is_valid());
+        assert!(!GameId::new(AppId::INVALID, 0x8000_0001, GameIdType::Shortcut).is_valid());
+
+        let p2p = GameId::new(AppId::INVALID, 0x8000_0001, GameIdType::P2P);
+        assert_eq!(p2p.game_type(), Some(GameIdType::P2P));
+        assert!(p2p.is_valid());
+
+        assert_eq!(GameId(0xFF << 24).game_type(), None);
+    }
+}
@@ -0,0 +1,86 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use smol::stream::StreamExt;
+    use steamgear::{
+        api::SteamApi,
+        core::{
+            enums::{DenyReason, SteamError, Universe},
+            structs::SteamId,
+        },
+    };
+
+    use crate::common::fake_server;
+
+    #[test]
+    fn server_connection_callbacks() {
+        let (backend, server) = fake_server();
+
+        let connected = server.game_server().on_servers_connected();
+        let failures = server.game_server().on_server_connect_failure();
+        backend.push_server_connect_failure(SteamError::NoConnection, true);
+        backend.push_servers_connected();
+        server.run_callbacks();
+
+        smol::block_on(async move {
+            let mut connected = std::pin::pin!(connected);
+            let mut failures = std::pin::pin!(failures);
+
+            let failure = failures.next().await.unwrap();
+            assert_eq!(failure.error, SteamError::NoConnection);
+            assert!(failure.still_retrying);
+            assert!(connected.next().await.is_some());
+        });
+
+        server.shutdown();
+    }
+
+    #[test]
+    fn server_client_deny() {
+        let (backend, server) = fake_server();
+        let user = SteamId::individual(Universe::Public, 2468);
+
+        let denies = server.game_server().on_client_deny();
+        backend.push_client_deny(user, DenyReason::NoLicense, "no license");
+        server.run_callbacks();
+
+        smol::block_on(async move {
+            let mut denies = std::pin::pin!(denies);
+
+            let deny = denies.next().await.unwrap();
+            assert_eq!(deny.steam_id, user);
+            assert_eq!(deny.reason, DenyReason::NoLicense);
+            assert_eq!(deny.text, "no license");
+        });
+
+        server.shutdown();
+    }
+
+    #[test]
+    fn server_callbacks_stay_on_server_pipe() {
+        let (backend, server) = fake_server();
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+
+        let connected = server.game_server().on_servers_connected();
+        let server_shutdown = server.utils().on_steam_shutdown();
+        backend.push_servers_connected();
+        client.run_callbacks();
+
+        smol::block_on(async {
+            let mut connected = std::pin::pin!(connected);
+            assert!(smol::future::poll_once(connected.next()).await.is_none());
+
+            server.run_callbacks();
+            assert!(connected.next().await.is_some());
+        });
+
+        client.shutdown();
+        server.shutdown();
+
+        smol::block_on(async move {
+            let mut server_shutdown = std::pin::pin!(server_shutdown);
+            assert!(server_shutdown.next().await.is_some());
+        });
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use smol::stream::StreamExt;
+    use steamgear::{
+        api::SteamApi,
+        apps::launch::{parse_command_line, LaunchParams, SteamRunUrl},
+        backend::FakeBackend,
+        core::{enums::SteamUrlError, structs::AppId},
+    };
+
+    #[test]
+    fn command_line_quoting() {
+        assert_eq!(
+            parse_command_line(r#"+connect 127.0.0.1  -name "John \"JD\" Doe" 'a b'"#),
+            vec!["+connect", "127.0.0.1", "-name", r#"John "JD" Doe"#, "a b"]
+        );
+        assert_eq!(
+            parse_command_line(r#"-path C:\Games\Spacewar "" "unterminated x"#),
+            vec!["-path", r"C:\Games\Spacewar", "", "unterminated x"]
+        );
+        assert!(parse_command_line("   ").is_empty());
+    }
+
+    #[test]
+    fn steam_run_urls() {
+        let url: SteamRunUrl = "steam://run/480//+connect%20127.0.0.1%3A27015/?lobby=42;mode=ctf"
+            .parse()
+            .unwrap();
+        assert_eq!(url.app_id, AppId(480));
+        assert_eq!(url.language, "");
+        assert_eq!(url.args, vec!["+connect", "127.0.0.1:27015"]);
+        assert_eq!(url.query.get("mode"), Some("ctf"));
+        assert_eq!(url.query.get_as::<u64>("lobby"), Some(Ok(42)));
+        assert_eq!(url.query.get_as::<u64>("missing"), None);
+
+        let url: SteamRunUrl = "STEAM://RUN/480/german".parse().unwrap();
+        assert_eq!(url.language, "german");
+        assert!(url.args.is_empty() && url.query.is_empty());
+
+        assert_eq!(
+            "steam://open/friends".parse::<SteamRunUrl>(),
+            Err(SteamUrlError::NotRunUrl)
+        );
+        assert_eq!(
+            "steam://run/spacewar//".parse::<SteamRunUrl>(),
+            Err(SteamUrlError::InvalidAppId("spacewar".to_string()))
+        );
+        assert_eq!(
+            "steam://run/480//%zz/".parse::<SteamRunUrl>(),
+            Err(SteamUrlError::InvalidEncoding)
+        );
+
+        let params = LaunchParams::from_command_line("steam://run/480//+connect%20host/?lobby=1");
+        assert_eq!(params.value_of("+connect"), Some("host"));
+        assert_eq!(params.query.get("lobby"), Some("1"));
+    }
+
+    #[test]
+    fn launch_params_stream() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        backend.set_launch_command_line("-windowed");
+
+        let apps = client.apps();
+        let params = apps.launch_params(&["server"]).unwrap();
+        assert_eq!(params.args, vec!["-windowed"]);
+        assert!(params.query.is_empty());
+
+        let mut joins = std::pin::pin!(apps.on_launch_params(&["server", "lobby"]).unwrap());
+
+        backend.set_launch_command_line("+connect \"my server\"");
+        backend.set_launch_query_param("server", "127.0.0.1");
+        backend.push_new_url_launch_params();
+        client.run_callbacks();
+
+        smol::block_on(async {
+            let params = joins.next().await.unwrap();
+            assert_eq!(params.command_line, "+connect \"my server\"");
+            assert_eq!(params.value_of("+connect"), Some("my server"));
+            assert_eq!(params.query.get("server"), Some("127.0.0.1"));
+            assert_eq!(params.query.get("lobby"), None);
+            assert!(smol::future::poll_once(joins.next()).await.is_none());
+        });
+
+        client.shutdown();
+        assert!(smol::block_on(joins.next()).is_none());
+    }
+}
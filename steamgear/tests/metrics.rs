@@ -0,0 +1,130 @@
+// The SDK constants are `c_int` on Windows
+#![allow(clippy::unnecessary_cast)]
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::AssertUnwindSafe,
+        sync::{Arc, Mutex},
+    };
+
+    use steamgear::{
+        api::SteamApi,
+        backend::{FakeBackend, FakeCallResults},
+        core::{client::SteamApiClient, enums::NotInitialized, metrics::FrameMetrics},
+    };
+    use steamgear_sys as sys;
+
+    const UNKNOWN_CALLBACK: u32 = 999_002;
+
+    fn collect(client: &SteamApi<SteamApiClient>) -> Arc<Mutex<Vec<FrameMetrics>>> {
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        client.set_metrics_hook({
+            let frames = frames.clone();
+            move |metrics| frames.lock().unwrap().push(metrics.clone())
+        });
+        frames
+    }
+
+    #[test]
+    fn frame_metrics() {
+        let backend = Arc::new(FakeBackend::new());
+        backend.set_call_results(FakeCallResults::Hang);
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        let frames = collect(&client);
+
+        let _launch = client.apps().on_new_launch_query_param();
+        backend.push_new_url_launch_params();
+        backend.push_callback(UNKNOWN_CALLBACK, vec![1, 2]);
+
+        let call = client.raw_call_result(backend.next_call_handle(), UNKNOWN_CALLBACK);
+        let mut call = std::pin::pin!(call);
+        assert!(smol::block_on(smol::future::poll_once(&mut call)).is_none());
+
+        client.run_callbacks();
+
+        let frame = frames.lock().unwrap().pop().unwrap();
+        assert_eq!(frame.callbacks, 2);
+        assert_eq!(frame.unhandled, 1);
+        assert_eq!(frame.dropped, 0);
+        assert_eq!(frame.pending_call_results, 1);
+
+        let launch = frame
+            .queues
+            .iter()
+            .find(|queue| queue.callback_id == sys::NewUrlLaunchParameters_t_k_iCallback as u32)
+            .unwrap();
+        assert_eq!(launch.callback, "NewUrlLaunchParameters_t");
+        assert_eq!(launch.subscribers, 1);
+        assert_eq!(launch.queued, 1);
+
+        client.clear_metrics_hook();
+        client.run_callbacks();
+        assert!(frames.lock().unwrap().is_empty());
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn lagging_subscriber_drops() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        let frames = collect(&client);
+
+        let _raw = client.on_raw_callback(UNKNOWN_CALLBACK);
+        for _ in 0..40 {
+            backend.push_callback(UNKNOWN_CALLBACK, vec![0]);
+        }
+        client.run_callbacks();
+
+        let frame = frames.lock().unwrap().pop().unwrap();
+        assert_eq!(frame.callbacks, 40);
+        assert_eq!(frame.unhandled, 0);
+        assert_eq!(frame.dropped, 8);
+
+        let raw = frame
+            .queues
+            .iter()
+            .find(|queue| queue.callback_id == UNKNOWN_CALLBACK)
+            .unwrap();
+        assert_eq!(raw.callback, "unknown");
+        assert_eq!(raw.queued, raw.capacity);
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn shutdown_from_metrics_hook() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = Arc::new(SteamApi::client().backend(backend.clone()).build().unwrap());
+        client.set_metrics_hook({
+            let client = client.clone();
+            move |_| client.shutdown()
+        });
+
+        backend.push_callback(UNKNOWN_CALLBACK, vec![0]);
+        client.run_callbacks();
+        assert!(!backend.is_client_running());
+        assert_eq!(client.apps().is_subscribe(), Err(NotInitialized));
+
+        client.clear_metrics_hook();
+    }
+
+    #[test]
+    fn panic_mid_frame() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        client.set_metrics_hook(|_| panic!("hook failed"));
+
+        let frame = std::panic::catch_unwind(AssertUnwindSafe(|| client.run_callbacks()));
+        assert!(frame.is_err());
+
+        // The next frame runs and shutdown doesn't wait for the one that panicked
+        let frames = collect(&client);
+        client.run_callbacks();
+        assert_eq!(frames.lock().unwrap().len(), 1);
+
+        client.shutdown();
+        assert!(!backend.is_client_running());
+    }
+}
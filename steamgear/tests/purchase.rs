@@ -0,0 +1,71 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use smol::stream::StreamExt;
+    use steamgear::core::{
+        enums::{AppsError, SteamError},
+        structs::{AppId, SteamId},
+    };
+
+    use crate::common::{fake_client, pump};
+
+    #[test]
+    fn proof_of_purchase_keys() {
+        let (backend, client) = fake_client();
+        backend.set_proof_of_purchase_key(AppId(480), Ok("AAAAA-BBBBB-CCCCC"));
+        backend.set_proof_of_purchase_key(AppId(1001), Err(SteamError::AccessDenied));
+
+        let apps = client.apps();
+        smol::block_on(async {
+            let both = smol::future::zip(
+                apps.request_proof_of_purchase_key(AppId(1001)),
+                apps.request_proof_of_purchase_key(AppId(480)),
+            );
+            let (dlc, game) = smol::future::or(both, pump(&client)).await;
+            assert_eq!(dlc.unwrap().key, Err(SteamError::AccessDenied));
+            assert_eq!(game.unwrap().key.as_deref(), Ok("AAAAA-BBBBB-CCCCC"));
+
+            let unknown = smol::future::or(
+                apps.request_proof_of_purchase_key(AppId(1002)),
+                pump(&client),
+            );
+            assert_eq!(unknown.await.unwrap().key, Err(SteamError::Fail));
+
+            let mut all = std::pin::pin!(apps.request_all_proof_of_purchase_keys().unwrap());
+            client.run_callbacks();
+            assert_eq!(all.next().await.unwrap().app_id, AppId(480));
+            assert_eq!(all.next().await.unwrap().app_id, AppId(1001));
+            assert!(smol::future::poll_once(all.next()).await.is_none());
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn dlc_context_and_ownership_ticket() {
+        let (backend, client) = fake_client();
+        let owner = SteamId(76561197960265728);
+        backend.set_app_ownership_ticket(AppId(480), owner, &[0xab; 2000]);
+
+        let apps = client.apps();
+        assert!(apps.set_dlc_context(Some(AppId(1001))).unwrap());
+        assert_eq!(backend.dlc_context(), Some(AppId(1001)));
+        assert!(apps.set_dlc_context(None).unwrap());
+        assert_eq!(backend.dlc_context(), Some(AppId(0)));
+
+        // Larger than the first buffer
+        let ticket = apps.get_app_ownership_ticket(AppId(480)).unwrap();
+        assert_eq!(ticket.app_id, AppId(480));
+        assert_eq!(ticket.steam_id, owner);
+        assert_eq!(ticket.signature, vec![0xab; 2000]);
+        assert_eq!(ticket.ticket.len(), 2012);
+
+        assert_eq!(
+            apps.get_app_ownership_ticket(AppId(1001)),
+            Err(AppsError::NotFound)
+        );
+
+        client.shutdown();
+    }
+}
@@ -0,0 +1,166 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use smol::stream::StreamExt;
+    use steamgear::core::{
+        callback::{CallResultError, CallbackStruct},
+        enums::ApiCallFailure,
+        structs::AppId,
+    };
+    use steamgear_sys as sys;
+
+    use crate::common::fake_client;
+
+    const CUSTOM_CALLBACK: u32 = 999_001;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Custom {
+        value: u32,
+        flag: u8,
+    }
+
+    unsafe impl CallbackStruct for Custom {
+        const ID: u32 = CUSTOM_CALLBACK;
+    }
+
+    fn custom_bytes(value: u32, flag: u8) -> Vec<u8> {
+        let mut data = vec![0; std::mem::size_of::<Custom>()];
+        data[..4].copy_from_slice(&value.to_ne_bytes());
+        data[4] = flag;
+        data
+    }
+
+    #[test]
+    fn raw_unknown_callback() {
+        let (backend, client) = fake_client();
+
+        let bytes = client.on_raw_callback(CUSTOM_CALLBACK);
+        let typed = client.on_callback::<Custom>();
+        backend.push_callback(CUSTOM_CALLBACK, vec![1, 2, 3]);
+        backend.push_callback(CUSTOM_CALLBACK, custom_bytes(7, 1));
+        client.run_callbacks();
+
+        smol::block_on(async move {
+            let mut bytes = std::pin::pin!(bytes);
+            let mut typed = std::pin::pin!(typed);
+
+            assert_eq!(bytes.next().await, Some(vec![1, 2, 3]));
+            assert_eq!(bytes.next().await, Some(custom_bytes(7, 1)));
+            // The short callback doesn't fit `Custom` and is skipped
+            assert_eq!(typed.next().await, Some(Custom { value: 7, flag: 1 }));
+            assert!(smol::future::poll_once(typed.next()).await.is_none());
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn raw_sdk_callback() {
+        let (backend, client) = fake_client();
+
+        let installed = client.on_callback::<sys::DlcInstalled_t>();
+        backend.push_dlc_installed(AppId(1001));
+        client.run_callbacks();
+
+        smol::block_on(async move {
+            let mut installed = std::pin::pin!(installed);
+            assert_eq!(installed.next().await.unwrap().m_nAppID, 1001);
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn raw_call_result() {
+        let (backend, client) = fake_client();
+
+        let ok = backend.next_call_handle();
+        let failed = backend.next_call_handle();
+        let mismatched = backend.next_call_handle();
+        backend.push_call_result(ok, CUSTOM_CALLBACK, Ok(custom_bytes(3, 0)));
+        backend.push_call_result(failed, CUSTOM_CALLBACK, Err(ApiCallFailure::NetworkFailure));
+        backend.push_call_result(mismatched, 1, Ok(vec![]));
+
+        smol::block_on(async {
+            let results = smol::future::zip(
+                smol::future::zip(
+                    client.call_result::<Custom>(ok),
+                    client.raw_call_result(failed, CUSTOM_CALLBACK),
+                ),
+                client.call_result::<Custom>(mismatched),
+            );
+            let pump = async {
+                loop {
+                    client.run_callbacks();
+                    smol::future::yield_now().await;
+                }
+            };
+
+            let ((ok, failed), mismatched) = smol::future::or(results, pump).await;
+            assert_eq!(ok, Ok(Custom { value: 3, flag: 0 }));
+            assert_eq!(
+                failed,
+                Err(CallResultError::Failed(ApiCallFailure::NetworkFailure))
+            );
+            assert_eq!(
+                mismatched,
+                Err(CallResultError::Failed(ApiCallFailure::MismatchedCallback))
+            );
+        });
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn raw_streams_end_on_shutdown() {
+        let (_backend, client) = fake_client();
+
+        let bytes = client.on_raw_callback(CUSTOM_CALLBACK);
+        let typed = client.on_callback::<Custom>();
+        client.shutdown();
+
+        smol::block_on(async move {
+            let mut bytes = std::pin::pin!(bytes);
+            let mut typed = std::pin::pin!(typed);
+            assert_eq!(bytes.next().await, None);
+            assert_eq!(typed.next().await, None);
+        });
+    }
+
+    #[test]
+    fn call_result_completed_before_poll() {
+        let (backend, client) = fake_client();
+        let call = backend.next_call_handle();
+
+        let result = client.call_result::<Custom>(call);
+        backend.push_call_result(call, CUSTOM_CALLBACK, Ok(custom_bytes(7, 1)));
+        client.run_callbacks();
+
+        assert_eq!(smol::block_on(result), Ok(Custom { value: 7, flag: 1 }));
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn reused_call_handle() {
+        let (backend, client) = fake_client();
+        let call = backend.next_call_handle();
+
+        smol::block_on(async {
+            let mut first = Box::pin(client.raw_call_result(call, CUSTOM_CALLBACK));
+            assert!(smol::future::poll_once(&mut first).await.is_none());
+            let mut second = Box::pin(client.raw_call_result(call, CUSTOM_CALLBACK));
+            assert!(smol::future::poll_once(&mut second).await.is_none());
+
+            // Dropping the stale registration keeps the newer one
+            drop(first);
+            backend.push_call_result(call, CUSTOM_CALLBACK, Ok(custom_bytes(5, 1)));
+            client.run_callbacks();
+            assert_eq!(second.await, Ok(custom_bytes(5, 1)));
+        });
+
+        client.shutdown();
+    }
+}
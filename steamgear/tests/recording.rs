@@ -0,0 +1,119 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc, time::Duration};
+
+    use smol::stream::StreamExt;
+    use steamgear::{
+        api::SteamApi,
+        apps::structs::FileDetails,
+        backend::{
+            BackendEvent, CallResultMsg, CallbackMsg, FakeBackend, RecordedEvent, RecordedPipe,
+            Recording,
+        },
+        core::{enums::ApiCallFailure, structs::AppId},
+    };
+
+    use crate::common::pump;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("steamgear-{}-{}.rec", name, std::process::id()))
+    }
+
+    #[test]
+    fn recording_roundtrip() {
+        let recording = Recording {
+            events: vec![
+                RecordedEvent {
+                    pipe: RecordedPipe::Client,
+                    frame: 1,
+                    timestamp: Duration::from_micros(250),
+                    event: BackendEvent::Callback(CallbackMsg {
+                        id: 1005,
+                        data: vec![1, 2, 3, 4],
+                    }),
+                },
+                RecordedEvent {
+                    pipe: RecordedPipe::Server,
+                    frame: 3,
+                    timestamp: Duration::from_millis(40),
+                    event: BackendEvent::CallResult(CallResultMsg {
+                        call: 42,
+                        id: 1023,
+                        data: Err(ApiCallFailure::NetworkFailure),
+                    }),
+                },
+            ],
+        };
+
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+        assert_eq!(Recording::read(&bytes[..]).unwrap(), recording);
+        assert!(Recording::read(&b"not a recording"[..]).is_err());
+    }
+
+    #[test]
+    fn record_and_replay_session() {
+        let path = temp_path("session");
+
+        // Capture a DLC install and a file details call
+        {
+            let backend = Arc::new(FakeBackend::new());
+            let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+            backend.add_dlc(AppId(1001), "Soundtrack", true);
+            backend.set_file_details(
+                "game.pak",
+                FileDetails {
+                    file_size: 42,
+                    sha1: [7; 20],
+                },
+            );
+
+            client.start_recording(&path).unwrap();
+            client.run_callbacks();
+            backend.push_dlc_installed(AppId(1001));
+            client.run_callbacks();
+
+            smol::block_on(async {
+                let details = smol::future::or(
+                    async { client.apps().get_file_details("game.pak").await },
+                    pump(&client),
+                )
+                .await;
+                assert_eq!(details.unwrap().file_size, 42);
+            });
+
+            client.stop_recording().unwrap();
+            client.shutdown();
+        }
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.events.len(), 2);
+        assert_eq!(recording.events[0].frame, 2);
+
+        // Play it back without any scripted state
+        let backend = Arc::new(FakeBackend::replay(&path).unwrap());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        let dlc_stream = client.on_callback::<steamgear_sys::DlcInstalled_t>();
+
+        client.run_callbacks();
+        smol::block_on(async {
+            let mut dlc_stream = std::pin::pin!(dlc_stream);
+            assert!(smol::future::poll_once(dlc_stream.next()).await.is_none());
+
+            client.run_callbacks();
+            assert_eq!(dlc_stream.next().await.unwrap().m_nAppID, 1001);
+
+            let details = smol::future::or(
+                async { client.apps().get_file_details("game.pak").await },
+                pump(&client),
+            )
+            .await;
+            assert_eq!(details.unwrap().sha1, [7; 20]);
+        });
+
+        client.shutdown();
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests {
+    use steamgear::{
+        core::enums::Interface,
+        sdk::{self, SDK_VERSION},
+        sdk_info,
+    };
+    use steamgear_sys as sys;
+
+    #[test]
+    fn interface_versions_match_headers() {
+        let info = sdk_info();
+        assert_eq!(info.version, SDK_VERSION);
+        assert_eq!(
+            info.interface_version("STEAMAPPS_INTERFACE_VERSION"),
+            Some(sdk::STEAMAPPS_INTERFACE_VERSION)
+        );
+        assert_eq!(
+            info.interface_version("STEAMMISSING_INTERFACE_VERSION"),
+            None
+        );
+
+        let raw = sys::STEAMAPPS_INTERFACE_VERSION;
+        assert_eq!(Interface::Apps.version().as_bytes(), &raw[..raw.len() - 1]);
+        assert_eq!(
+            Interface::GameServer.version(),
+            sdk::STEAMGAMESERVER_INTERFACE_VERSION
+        );
+
+        assert!(info.to_string().contains(sdk::STEAMUTILS_INTERFACE_VERSION));
+    }
+}
@@ -0,0 +1,153 @@
+// The SDK constants are `c_int` on Windows
+#![allow(clippy::unnecessary_cast)]
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use steamgear::{
+        apps::structs::FileDetails,
+        backend::{
+            BackendEvent, CallResultMsg, CallbackMsg, RecordedEvent, RecordedPipe, Recording,
+        },
+        core::{
+            enums::{ApiCallFailure, Interface, SteamApiInitError, SteamError},
+            metrics::{FrameMetrics, QueueDepth},
+            structs::{AppId, GameId, SteamId},
+        },
+    };
+    use steamgear_sys as sys;
+
+    #[test]
+    fn ids() {
+        let steam_id = SteamId(76561197960287930);
+        assert_eq!(
+            serde_json::to_value(steam_id).unwrap(),
+            json!("76561197960287930")
+        );
+        assert_eq!(
+            serde_json::from_value::<SteamId>(json!("76561197960287930")).unwrap(),
+            steam_id
+        );
+        assert_eq!(
+            serde_json::from_value::<SteamId>(json!(76561197960287930u64)).unwrap(),
+            steam_id
+        );
+        assert_eq!(
+            serde_json::from_value::<SteamId>(json!("[U:1:22202]")).unwrap(),
+            steam_id
+        );
+        assert!(serde_json::from_value::<SteamId>(json!("nope")).is_err());
+
+        let game_id = GameId(0x8000_0001_0000_01e0);
+        assert_eq!(
+            serde_json::to_value(game_id).unwrap(),
+            json!(game_id.0.to_string())
+        );
+        assert_eq!(
+            serde_json::from_value::<GameId>(json!(game_id.0.to_string())).unwrap(),
+            game_id
+        );
+
+        assert_eq!(serde_json::to_value(AppId(480)).unwrap(), json!(480));
+        assert_eq!(
+            serde_json::from_value::<AppId>(json!(480)).unwrap(),
+            AppId(480)
+        );
+    }
+
+    #[test]
+    fn values() {
+        let details = FileDetails {
+            file_size: 42,
+            sha1: [7; 20],
+        };
+        let value = serde_json::to_value(details).unwrap();
+        assert_eq!(value["file_size"], json!(42));
+        let details: FileDetails = serde_json::from_value(value).unwrap();
+        assert_eq!(details.sha1, [7; 20]);
+
+        let err = SteamApiInitError::VersionMismatch {
+            interface: Some(Interface::Friends),
+            msg: "No interface".to_string(),
+        };
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SteamApiInitError>(&json).unwrap(),
+            err
+        );
+
+        assert_eq!(
+            serde_json::to_value(SteamError::FileNotFound).unwrap(),
+            json!("FileNotFound")
+        );
+    }
+
+    #[test]
+    fn frame_metrics() {
+        let queue = |callback_id, callback| QueueDepth {
+            callback_id,
+            callback,
+            subscribers: 2,
+            queued: 3,
+            capacity: 32,
+        };
+        let metrics = FrameMetrics {
+            callbacks: 4,
+            call_results: 1,
+            unhandled: 1,
+            dropped: 0,
+            pending_call_results: 2,
+            duration: Duration::from_micros(250),
+            queues: vec![
+                queue(
+                    sys::NewUrlLaunchParameters_t_k_iCallback as u32,
+                    "NewUrlLaunchParameters_t",
+                ),
+                queue(999_002, "unknown"),
+            ],
+        };
+
+        let value = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(
+            value["queues"][0]["callback"],
+            json!("NewUrlLaunchParameters_t")
+        );
+        assert_eq!(
+            serde_json::from_value::<FrameMetrics>(value).unwrap(),
+            metrics
+        );
+    }
+
+    #[test]
+    fn recording() {
+        let recording = Recording {
+            events: vec![
+                RecordedEvent {
+                    pipe: RecordedPipe::Client,
+                    frame: 1,
+                    timestamp: Duration::from_millis(16),
+                    event: BackendEvent::Callback(CallbackMsg {
+                        id: 1014,
+                        data: vec![1, 2, 3],
+                    }),
+                },
+                RecordedEvent {
+                    pipe: RecordedPipe::Server,
+                    frame: 2,
+                    timestamp: Duration::from_millis(32),
+                    event: BackendEvent::CallResult(CallResultMsg {
+                        call: 7,
+                        id: 1023,
+                        data: Err(ApiCallFailure::NetworkFailure),
+                    }),
+                },
+            ],
+        };
+
+        let json = serde_json::to_string(&recording).unwrap();
+        assert_eq!(serde_json::from_str::<Recording>(&json).unwrap(), recording);
+    }
+}
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod tests {
+    use steamgear::core::enums::SteamError;
+    use steamgear_sys as sys;
+
+    #[test]
+    fn steam_error_from_code() {
+        assert_eq!(SteamError::from_code(sys::EResult_k_EResultOK), Ok(()));
+        assert_eq!(
+            SteamError::from_code(sys::EResult_k_EResultAccessDenied),
+            Err(SteamError::AccessDenied)
+        );
+        assert_eq!(SteamError::from_code(4), Err(SteamError::Unknown(4)));
+
+        for code in (0..200).filter(|code| *code != sys::EResult_k_EResultOK) {
+            assert_eq!(SteamError::from_code(code).unwrap_err().code(), code);
+        }
+    }
+
+    #[test]
+    fn steam_error_classification() {
+        assert!(SteamError::Timeout.is_retryable());
+        assert!(SteamError::ServiceUnavailable.is_retryable());
+        assert!(!SteamError::AccessDenied.is_retryable());
+
+        assert_eq!(SteamError::AccessDenied.to_string(), "Access is denied");
+        assert_eq!(SteamError::Unknown(4).to_string(), "Unknown result 4");
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use steamgear::core::{
+        enums::{AccountType, Universe},
+        structs::{SteamId, SteamIdParseError},
+    };
+
+    const GABEN: SteamId = SteamId(76561197960287930);
+
+    #[test]
+    fn steam_id_decomposition() {
+        assert_eq!(GABEN.account_id(), 22202);
+        assert_eq!(GABEN.instance(), 1);
+        assert_eq!(GABEN.account_type(), AccountType::Individual);
+        assert_eq!(GABEN.universe(), Universe::Public);
+        assert!(GABEN.is_valid());
+
+        assert_eq!(SteamId::individual(Universe::Public, 22202), GABEN);
+        assert_eq!(SteamId::clan(Universe::Public, 1).0, 103582791429521409);
+        assert_eq!(
+            SteamId::game_server(Universe::Public, 7).account_type(),
+            AccountType::GameServer
+        );
+        assert!(SteamId::anon_game_server(Universe::Public).is_valid());
+        assert!(!SteamId::default().is_valid());
+    }
+
+    #[test]
+    fn steam_id_formatting() {
+        assert_eq!(GABEN.to_string(), "76561197960287930");
+        assert_eq!(GABEN.steam2().unwrap(), "STEAM_0:0:11101");
+        assert_eq!(GABEN.steam3(), "[U:1:22202]");
+        assert_eq!(
+            GABEN.community_url().unwrap(),
+            "https://steamcommunity.com/profiles/76561197960287930"
+        );
+
+        let clan = SteamId::clan(Universe::Public, 1);
+        assert_eq!(clan.steam2(), None);
+        assert_eq!(clan.steam3(), "[g:1:1]");
+
+        let anon = SteamId::new(1234, 5, AccountType::AnonGameServer, Universe::Public);
+        assert_eq!(anon.steam3(), "[A:1:1234:5]");
+    }
+
+    #[test]
+    fn steam_id_parsing() {
+        for input in [
+            "76561197960287930",
+            "STEAM_0:0:11101",
+            "STEAM_1:0:11101",
+            "[U:1:22202]",
+            "U:1:22202",
+            " https://steamcommunity.com/profiles/76561197960287930/ ",
+            "steamcommunity.com/profiles/76561197960287930",
+        ] {
+            assert_eq!(input.parse::<SteamId>(), Ok(GABEN), "{}", input);
+        }
+
+        let steam2 = "STEAM_0:1:1234".parse::<SteamId>().unwrap();
+        assert_eq!(steam2.account_id(), 2469);
+        assert_eq!(steam2.steam2().unwrap(), "STEAM_0:1:1234");
+
+        let anon = "[A:1:1234:5]".parse::<SteamId>().unwrap();
+        assert_eq!(anon.instance(), 5);
+        assert_eq!(anon.steam3(), "[A:1:1234:5]");
+
+        let lobby = "[L:1:42]".parse::<SteamId>().unwrap();
+        assert_eq!(lobby.account_type(), AccountType::Chat);
+        assert_eq!(lobby.steam3(), "[L:1:42]");
+
+        assert_eq!(
+            "STEAM_0:2:1".parse::<SteamId>(),
+            Err(SteamIdParseError::OutOfRange)
+        );
+        assert_eq!(
+            "[X:1:22202]".parse::<SteamId>(),
+            Err(SteamIdParseError::Format)
+        );
+        assert_eq!(
+            "https://steamcommunity.com/id/gabelogannewell".parse::<SteamId>(),
+            Err(SteamIdParseError::VanityUrl)
+        );
+        assert_eq!("".parse::<SteamId>(), Err(SteamIdParseError::Format));
+    }
+}
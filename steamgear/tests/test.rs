@@ -5,7 +5,7 @@ mod tests {
 
     #[test]
     fn steam_api_init() {
-        let client = SteamApi::new_client(None);
+        let client = SteamApi::client().build();
         assert!(client.is_ok());
         let client = client.unwrap();
 
@@ -14,7 +14,7 @@ mod tests {
 
     #[test]
     fn steam_api_callback_shutdown() {
-        let client = SteamApi::new_client(None);
+        let client = SteamApi::client().build();
         assert!(client.is_ok());
         let client = client.unwrap();
 
@@ -32,8 +32,8 @@ mod tests {
     }
 
     #[test]
-    fn steam_api_callback_override() {
-        let client = SteamApi::new_client(None);
+    fn steam_api_callback_broadcast() {
+        let client = SteamApi::client().build();
         assert!(client.is_ok());
         let client = client.unwrap();
 
@@ -42,7 +42,7 @@ mod tests {
 
         let task = smol::spawn(async move {
             let mut shutdown_stream = std::pin::pin!(shutdown_stream);
-            assert!(shutdown_stream.next().await.is_none());
+            assert!(shutdown_stream.next().await.is_some());
         });
 
         let another_task = smol::spawn(async move {
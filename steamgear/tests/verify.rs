@@ -0,0 +1,210 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    use smol::stream::StreamExt;
+    use steamgear::{
+        api::SteamApi,
+        apps::{
+            structs::FileDetails,
+            verify::{FileMismatch, FileProblem, Manifest},
+        },
+        backend::{FakeBackend, FakeCallResults},
+        core::{
+            callback::CallResultError,
+            enums::{AppsError, ManifestError, SteamError},
+            structs::AppId,
+        },
+    };
+
+    use crate::common::pump;
+
+    const HELLO_SHA1: &str = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d";
+
+    fn sha1(hex: &str) -> [u8; 20] {
+        let mut digest = [0; 20];
+        for (i, byte) in digest.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        digest
+    }
+
+    fn hello() -> FileDetails {
+        FileDetails {
+            file_size: 5,
+            sha1: sha1(HELLO_SHA1),
+        }
+    }
+
+    fn install_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("steamgear-{}-{}", name, std::process::id()));
+        for (path, content) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn mismatch(path: &str, problem: FileProblem) -> FileMismatch {
+        FileMismatch {
+            path: path.into(),
+            problem,
+        }
+    }
+
+    #[test]
+    fn verify_against_steam() {
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+
+        let dir = install_dir(
+            "verify",
+            &[
+                ("game.pak", "hello"),
+                ("data/level.bin", "hellO"),
+                ("config.ini", ""),
+                ("mod.pak", "modded"),
+            ],
+        );
+        backend.install_app(AppId(480), &dir);
+        for path in ["game.pak", "data/level.bin", "missing.pak"] {
+            backend.set_file_details(path, hello());
+        }
+
+        let mut manifest = Manifest::from_paths(["game.pak", "data/level.bin", "missing.pak"]);
+        manifest.push("config.ini", Some(hello()));
+        manifest.push("mod.pak", None);
+
+        let apps = client.apps();
+        let check = apps.verify_install(AppId(480), manifest).repair(true);
+        let mismatches = smol::block_on(smol::future::or(
+            check.start().unwrap().collect::<Vec<_>>(),
+            pump(&client),
+        ));
+
+        assert_eq!(
+            mismatches,
+            vec![
+                mismatch(
+                    "data/level.bin",
+                    FileProblem::Sha1 {
+                        expected: sha1(HELLO_SHA1),
+                        actual: sha1("568a752b7f6b5007e86d5b38be599931dfe87d50"),
+                    }
+                ),
+                mismatch("missing.pak", FileProblem::Missing),
+                mismatch(
+                    "config.ini",
+                    FileProblem::Size {
+                        expected: 5,
+                        actual: 0
+                    }
+                ),
+                mismatch(
+                    "mod.pak",
+                    FileProblem::Unknown(CallResultError::Steam(SteamError::FileNotFound))
+                ),
+            ]
+        );
+        assert_eq!(backend.content_marked_corrupt(), Some(false));
+
+        assert_eq!(
+            apps.verify_install(AppId(1001), Manifest::new())
+                .start()
+                .err(),
+            Some(AppsError::NotFound)
+        );
+
+        client.shutdown();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn dropped_while_asking_steam() {
+        let backend = Arc::new(FakeBackend::new());
+        backend.set_call_results(FakeCallResults::Hang);
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        let dir = install_dir("dropped", &[("game.pak", "hello")]);
+        backend.install_app(AppId(480), &dir);
+
+        let pending = Arc::new(AtomicUsize::new(0));
+        client.set_metrics_hook({
+            let pending = pending.clone();
+            move |metrics| pending.store(metrics.pending_call_results, Ordering::SeqCst)
+        });
+        let wait_for = |count: usize| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                client.run_callbacks();
+                if pending.load(Ordering::SeqCst) == count {
+                    break;
+                }
+                assert!(Instant::now() < deadline, "Verify thread didn't exit");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        };
+
+        let check = client
+            .apps()
+            .verify_install(AppId(480), Manifest::from_paths(["game.pak"]));
+        let stream = check.start().unwrap();
+        wait_for(1);
+
+        // The thread gives up the call once it notices
+        drop(stream);
+        wait_for(0);
+
+        client.shutdown();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn shipped_manifest() {
+        let text = format!(
+            "# sha1 size path\n{} 5 game.pak\n\n{}  5  maps/first level.map\n",
+            HELLO_SHA1, HELLO_SHA1
+        );
+        let manifest = Manifest::parse(&text).unwrap();
+        assert_eq!(manifest.entries().len(), 2);
+        assert_eq!(
+            manifest.entries()[1].path,
+            PathBuf::from("maps/first level.map")
+        );
+        assert_eq!(manifest.entries()[1].expected, Some(hello()));
+
+        assert_eq!(
+            Manifest::parse("# header\nnot-a-sha1 5 game.pak"),
+            Err(ManifestError { line: 2 })
+        );
+
+        let backend = Arc::new(FakeBackend::new());
+        let client = SteamApi::client().backend(backend.clone()).build().unwrap();
+        let dir = install_dir("manifest", &[("game.pak", "hello")]);
+        backend.install_app(AppId(480), &dir);
+
+        // Everything is shipped, Steam isn't asked and nothing needs pumping
+        let check = client
+            .apps()
+            .verify_install(AppId(480), manifest)
+            .repair(true);
+        let mismatches = smol::block_on(check.start().unwrap().collect::<Vec<_>>());
+        assert_eq!(
+            mismatches,
+            vec![mismatch("maps/first level.map", FileProblem::Missing)]
+        );
+        assert_eq!(backend.content_marked_corrupt(), Some(true));
+
+        client.shutdown();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}